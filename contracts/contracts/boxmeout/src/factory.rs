@@ -24,6 +24,13 @@ const ADMIN_KEY: &str = "admin";
 const USDC_KEY: &str = "usdc";
 const TREASURY_KEY: &str = "treasury";
 const MARKET_COUNT_KEY: &str = "market_count";
+const MARKET_IDS_KEY: &str = "market_ids";
+const MAX_MARKET_DURATION_KEY: &str = "max_market_duration";
+const MAX_ACTIVE_PER_USER_KEY: &str = "max_active_per_user";
+const TRACKED_MARKET_KEY: &str = "tracked_market";
+const TRACKED_MARKETS_KEY: &str = "tracked_markets";
+const USER_ACTIVE_COUNT_KEY: &str = "user_active_count";
+const USER_POSITION_KEY: &str = "user_position";
 
 /// MARKET FACTORY - Handles market creation, fee collection, and market registry
 #[contract]
@@ -90,6 +97,175 @@ impl MarketFactory {
             .expect("Treasury not set")
     }
 
+    /// Admin: cap how far in the future a market's resolution_time may be (0 = no cap)
+    pub fn set_max_market_duration(env: Env, admin: Address, max_duration: u64) {
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, MAX_MARKET_DURATION_KEY), &max_duration);
+    }
+
+    /// Get the maximum market duration in seconds (0 = no cap)
+    pub fn get_max_market_duration(env: Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, MAX_MARKET_DURATION_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Admin: cap how many markets a user may hold open positions in at once (0 = no cap)
+    pub fn set_max_active_per_user(env: Env, admin: Address, max_active: u32) {
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, MAX_ACTIVE_PER_USER_KEY), &max_active);
+    }
+
+    /// Get the per-user active market cap (0 = no cap)
+    pub fn get_max_active_per_user(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, MAX_ACTIVE_PER_USER_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Admin: enroll a market contract so its commits and claims count toward the cap
+    pub fn track_market(env: Env, admin: Address, market: Address) {
+        Self::require_admin(&env, &admin);
+
+        let tracked_key = (Symbol::new(&env, TRACKED_MARKET_KEY), market.clone());
+        if !env.storage().persistent().has(&tracked_key) {
+            let mut markets = Self::get_tracked_markets(env.clone());
+            markets.push_back(market.clone());
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, TRACKED_MARKETS_KEY), &markets);
+        }
+        env.storage().persistent().set(&tracked_key, &true);
+
+        env.invoke_contract::<()>(
+            &market,
+            &Symbol::new(&env, "enable_position_tracking"),
+            Vec::new(&env),
+        );
+    }
+
+    /// Get every market contract enrolled with `track_market`
+    pub fn get_tracked_markets(env: Env) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, TRACKED_MARKETS_KEY))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Get up to `limit` created market ids starting at `offset`, in creation order
+    ///
+    /// Returns an empty page once `offset` reaches `get_market_count`.
+    pub fn get_markets_page(env: Env, offset: u32, limit: u32) -> Vec<BytesN<32>> {
+        let markets: Vec<BytesN<32>> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_IDS_KEY))
+            .unwrap_or_else(|| Vec::new(&env));
+        let end = offset.saturating_add(limit).min(markets.len());
+
+        let mut page = Vec::new(&env);
+        for i in offset..end {
+            page.push_back(markets.get(i).unwrap());
+        }
+        page
+    }
+
+    /// Admin: add or remove a keeper on a market, letting it trigger close and resolution
+    pub fn set_market_keeper(
+        env: Env,
+        admin: Address,
+        market: Address,
+        keeper: Address,
+        enabled: bool,
+    ) {
+        Self::require_admin(&env, &admin);
+
+        env.invoke_contract::<()>(
+            &market,
+            &Symbol::new(&env, "set_keeper"),
+            (keeper, enabled).into_val(&env),
+        );
+    }
+
+    /// Market: record that `user` committed in the calling market
+    ///
+    /// Panics if the user already holds positions in the maximum number of markets.
+    pub fn record_position_opened(env: Env, market: Address, user: Address) {
+        Self::require_tracked_market(&env, &market);
+
+        let position_key = (Symbol::new(&env, USER_POSITION_KEY), user.clone(), market);
+        if env.storage().persistent().has(&position_key) {
+            return;
+        }
+
+        let count = Self::get_user_active_count(env.clone(), user.clone());
+        let max_active = Self::get_max_active_per_user(env.clone());
+        if max_active > 0 && count >= max_active {
+            panic!("user active market limit reached");
+        }
+
+        env.storage().persistent().set(&position_key, &true);
+        env.storage().persistent().set(
+            &(Symbol::new(&env, USER_ACTIVE_COUNT_KEY), user),
+            &(count + 1),
+        );
+    }
+
+    /// Market: record that `user` claimed or was refunded in the calling market
+    pub fn record_position_closed(env: Env, market: Address, user: Address) {
+        Self::require_tracked_market(&env, &market);
+
+        let position_key = (Symbol::new(&env, USER_POSITION_KEY), user.clone(), market);
+        if !env.storage().persistent().has(&position_key) {
+            return;
+        }
+        env.storage().persistent().remove(&position_key);
+
+        let count = Self::get_user_active_count(env.clone(), user.clone());
+        env.storage().persistent().set(
+            &(Symbol::new(&env, USER_ACTIVE_COUNT_KEY), user),
+            &count.saturating_sub(1),
+        );
+    }
+
+    /// Get how many tracked markets `user` currently holds open positions in
+    pub fn get_user_active_count(env: Env, user: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, USER_ACTIVE_COUNT_KEY), user))
+            .unwrap_or(0)
+    }
+
+    fn require_admin(env: &Env, admin: &Address) {
+        admin.require_auth();
+
+        let stored_admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, ADMIN_KEY))
+            .expect("not initialized");
+        if *admin != stored_admin {
+            panic!("unauthorized");
+        }
+    }
+
+    fn require_tracked_market(env: &Env, market: &Address) {
+        market.require_auth();
+
+        let tracked_key = (Symbol::new(env, TRACKED_MARKET_KEY), market.clone());
+        if !env.storage().persistent().has(&tracked_key) {
+            panic!("market not tracked");
+        }
+    }
+
     /// Create a new market instance
     pub fn create_market(
         env: Env,
@@ -112,6 +288,12 @@ impl MarketFactory {
             panic!("invalid timestamps");
         }
 
+        // Validate resolution_time - now <= max_market_duration (when capped)
+        let max_duration = Self::get_max_market_duration(env.clone());
+        if max_duration > 0 && resolution_time - current_time > max_duration {
+            panic!("market duration exceeds maximum");
+        }
+
         // Get market count and increment
         let market_count: u32 = env
             .storage()
@@ -131,6 +313,16 @@ impl MarketFactory {
         let market_key = (Symbol::new(&env, "market"), market_id.clone());
         env.storage().persistent().set(&market_key, &true);
 
+        let mut market_ids: Vec<BytesN<32>> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_IDS_KEY))
+            .unwrap_or_else(|| Vec::new(&env));
+        market_ids.push_back(market_id.clone());
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, MARKET_IDS_KEY), &market_ids);
+
         // Store market metadata
         let metadata_key = (Symbol::new(&env, "market_meta"), market_id.clone());
         let metadata = (
@@ -195,6 +387,64 @@ impl MarketFactory {
         todo!("See get market resolution TODO above")
     }
 
+    /// Get a user's aggregate ROI across the given market contracts
+    ///
+    /// Queries each market's `get_user_result`; unresolved markets contribute nothing.
+    ///
+    /// # Returns
+    /// `(total_staked, total_net_returned)`
+    pub fn get_user_roi(env: Env, user: Address, markets: Vec<Address>) -> (i128, i128) {
+        let mut total_staked: i128 = 0;
+        let mut total_net_returned: i128 = 0;
+
+        for market in markets.iter() {
+            let (staked, net_returned): (i128, i128) = env.invoke_contract(
+                &market,
+                &Symbol::new(&env, "get_user_result"),
+                (user.clone(),).into_val(&env),
+            );
+            total_staked += staked;
+            total_net_returned += net_returned;
+        }
+
+        (total_staked, total_net_returned)
+    }
+
+    /// Get a protocol-wide solvency indicator across all tracked markets
+    ///
+    /// Queries each market's `get_escrow_balance` and `get_liabilities`.
+    ///
+    /// # Returns
+    /// `(total_escrow, total_liabilities, health_bps)` where `health_bps` is escrow over
+    /// liabilities (10000 = fully collateralized, and 10000 when nothing is owed).
+    pub fn get_protocol_health(env: Env) -> (i128, i128, u32) {
+        let mut total_escrow: i128 = 0;
+        let mut total_liabilities: i128 = 0;
+
+        for market in Self::get_tracked_markets(env.clone()).iter() {
+            let escrow: i128 = env.invoke_contract(
+                &market,
+                &Symbol::new(&env, "get_escrow_balance"),
+                Vec::new(&env),
+            );
+            let liabilities: i128 = env.invoke_contract(
+                &market,
+                &Symbol::new(&env, "get_liabilities"),
+                Vec::new(&env),
+            );
+            total_escrow += escrow;
+            total_liabilities += liabilities;
+        }
+
+        let health_bps = if total_liabilities <= 0 {
+            10000
+        } else {
+            (total_escrow * 10000 / total_liabilities).clamp(0, u32::MAX as i128) as u32
+        };
+
+        (total_escrow, total_liabilities, health_bps)
+    }
+
     /// Admin: Pause market creation (emergency)
     pub fn set_market_creation_pause(_env: Env, _paused: bool) {
         todo!("See set market creation pause TODO above")
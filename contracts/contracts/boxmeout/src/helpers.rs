@@ -182,3 +182,230 @@ pub fn calculate_payout(
         yes_reserve - new_yes_reserve
     }
 }
+
+/// Calculate a pari-mutuel winner's payout
+/// gross = amount * total_pool / winner_shares, fee = ceil(gross * fee_bps / 10000)
+/// The fee rounds up so the protocol never loses dust, capped at gross so net >= 0
+/// Returns (net, fee) where net + fee == gross; (0, 0) when there are no winner shares
+pub fn compute_payout(
+    amount: i128,
+    winner_shares: i128,
+    total_pool: i128,
+    fee_bps: i128,
+) -> (i128, i128) {
+    if winner_shares == 0 {
+        return (0, 0);
+    }
+
+    // Split total_pool into whole multiples of winner_shares when the direct product overflows
+    let gross = amount
+        .checked_mul(total_pool)
+        .map(|product| product / winner_shares)
+        .unwrap_or_else(|| {
+            let whole = amount
+                .checked_mul(total_pool / winner_shares)
+                .expect("Overflow in payout calculation");
+            let part = amount
+                .checked_mul(total_pool % winner_shares)
+                .expect("Overflow in payout calculation")
+                / winner_shares;
+            whole
+                .checked_add(part)
+                .expect("Overflow in payout calculation")
+        });
+
+    let remainder_fee = (gross % 10000 * fee_bps + 9999) / 10000;
+    let fee = (gross / 10000 * fee_bps + remainder_fee).min(gross);
+    (gross - fee, fee)
+}
+
+/// Integer square root (floor) by Newton's method
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+
+    let mut x = n;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+/// Geometric mean floor(sqrt(a * b))
+/// Falls back to a binary search over 256-bit products when a * b overflows u128,
+/// so the result stays exact for any inputs
+pub fn geo_mean(a: u128, b: u128) -> u128 {
+    if let Some(product) = a.checked_mul(b) {
+        return isqrt(product);
+    }
+
+    // sqrt(a * b) lies between min(a, b) and max(a, b)
+    let target = widening_mul(a, b);
+    let mut lo = a.min(b);
+    let mut hi = a.max(b);
+    while lo < hi {
+        let mid = lo + (hi - lo).div_ceil(2);
+        if widening_mul(mid, mid) <= target {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    lo
+}
+
+/// Full 256-bit product of two u128 values as (high, low) words
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_lo, a_hi) = (a & MASK, a >> 64);
+    let (b_lo, b_hi) = (b & MASK, b >> 64);
+
+    let lo_lo = a_lo * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_lo = a_hi * b_lo;
+    let hi_hi = a_hi * b_hi;
+
+    let mid = (lo_lo >> 64) + (lo_hi & MASK) + (hi_lo & MASK);
+    let low = (lo_lo & MASK) | (mid << 64);
+    let high = hi_hi + (lo_hi >> 64) + (hi_lo >> 64) + (mid >> 64);
+    (high, low)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_payout_sole_winner_takes_pool_minus_fee() {
+        assert_eq!(compute_payout(1000, 1000, 1000, 1000), (900, 100));
+        assert_eq!(compute_payout(1000, 1000, 1500, 1000), (1350, 150));
+    }
+
+    #[test]
+    fn test_compute_payout_proportional_shares() {
+        // 500/300/200 of a 1000-share winning side, 1500 total pool
+        assert_eq!(compute_payout(500, 1000, 1500, 1000), (675, 75));
+        assert_eq!(compute_payout(300, 1000, 1500, 1000), (405, 45));
+        assert_eq!(compute_payout(200, 1000, 1500, 1000), (270, 30));
+    }
+
+    #[test]
+    fn test_compute_payout_net_plus_fee_is_gross() {
+        for amount in [1i128, 7, 333, 9_999, 123_456_789] {
+            for fee_bps in [0i128, 1, 250, 1000, 10000] {
+                let (net, fee) = compute_payout(amount, 1_000_003, 2_500_007, fee_bps);
+                assert_eq!(net + fee, amount * 2_500_007 / 1_000_003);
+                assert!(fee >= 0 && net >= 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_compute_payout_zero_fee_and_full_fee() {
+        assert_eq!(compute_payout(1000, 2000, 3000, 0), (1500, 0));
+        assert_eq!(compute_payout(1000, 2000, 3000, 10000), (0, 1500));
+    }
+
+    #[test]
+    fn test_compute_payout_fee_rounds_up_on_small_payouts() {
+        // 10% of 1..=9 would floor to zero
+        for gross in 1i128..10 {
+            let (net, fee) = compute_payout(gross, 1, 1, 1000);
+            assert_eq!(fee, 1);
+            assert_eq!(net, gross - 1);
+        }
+        assert_eq!(compute_payout(11, 1, 1, 1000), (9, 2));
+        assert_eq!(compute_payout(15, 1, 1, 1), (14, 1));
+        // Exact multiples are not rounded further
+        assert_eq!(compute_payout(100, 1, 1, 1000), (90, 10));
+    }
+
+    #[test]
+    fn test_compute_payout_fee_never_exceeds_gross() {
+        assert_eq!(compute_payout(1, 1, 1, 10000), (0, 1));
+        assert_eq!(compute_payout(3, 1, 1, 10000), (0, 3));
+        assert_eq!(compute_payout(1, 1, 1, 0), (1, 0));
+    }
+
+    #[test]
+    fn test_compute_payout_zero_shares() {
+        assert_eq!(compute_payout(1000, 0, 5000, 1000), (0, 0));
+        assert_eq!(compute_payout(0, 1000, 5000, 1000), (0, 0));
+    }
+
+    #[test]
+    fn test_compute_payout_overflow_prone_inputs() {
+        // amount * total_pool exceeds i128::MAX but the payout itself fits
+        let big = i128::MAX / 4;
+        let (net, fee) = compute_payout(big, big, big * 2, 1000);
+        assert_eq!(net + fee, big * 2);
+        assert_eq!(
+            fee,
+            (big * 2) / 10000 * 1000 + ((big * 2) % 10000 * 1000 + 9999) / 10000
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Overflow in payout calculation")]
+    fn test_compute_payout_unrepresentable_payout_panics() {
+        compute_payout(i128::MAX / 2, 1, 4, 0);
+    }
+
+    #[test]
+    fn test_isqrt_zero_and_one() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(1), 1);
+    }
+
+    #[test]
+    fn test_isqrt_perfect_squares_and_neighbours() {
+        for root in [2u128, 3, 10, 255, 1 << 20, 1_000_000_007] {
+            let square = root * root;
+            assert_eq!(isqrt(square), root);
+            assert_eq!(isqrt(square - 1), root - 1);
+            assert_eq!(isqrt(square + 1), root);
+        }
+        assert_eq!(isqrt(2), 1);
+        assert_eq!(isqrt(3), 1);
+    }
+
+    #[test]
+    fn test_isqrt_near_u128_max() {
+        let root = u64::MAX as u128;
+        assert_eq!(isqrt(u128::MAX), root);
+        assert_eq!(isqrt(root * root), root);
+        assert_eq!(isqrt(root * root - 1), root - 1);
+        assert_eq!(isqrt(1u128 << 127), 13_043_817_825_332_782_212);
+    }
+
+    #[test]
+    fn test_geo_mean_edge_cases() {
+        assert_eq!(geo_mean(0, 0), 0);
+        assert_eq!(geo_mean(0, u128::MAX), 0);
+        assert_eq!(geo_mean(1, 1), 1);
+        assert_eq!(geo_mean(4, 9), 6);
+        assert_eq!(geo_mean(2, 3), 2);
+        assert_eq!(geo_mean(7, 7), 7);
+        assert_eq!(geo_mean(1, u128::MAX), isqrt(u128::MAX));
+    }
+
+    #[test]
+    fn test_geo_mean_overflowing_product_is_exact() {
+        assert_eq!(geo_mean(u128::MAX, u128::MAX), u128::MAX);
+        assert_eq!(geo_mean(1u128 << 100, 1u128 << 60), 1u128 << 80);
+        assert_eq!(geo_mean(1u128 << 127, 2), 1u128 << 64);
+
+        // floor(sqrt((2^100 + 1) * (2^100 - 1))) = 2^100 - 1
+        let base = 1u128 << 100;
+        assert_eq!(geo_mean(base + 1, base - 1), base - 1);
+    }
+
+    #[test]
+    fn test_geo_mean_is_symmetric() {
+        assert_eq!(geo_mean(3, 1u128 << 126), geo_mean(1u128 << 126, 3));
+        assert_eq!(geo_mean(12, 75), geo_mean(75, 12));
+    }
+}
@@ -34,4 +34,7 @@ pub use amm::AMMContract;
 #[cfg(any(feature = "amm", test))]
 pub use amm::AMM;
 
+#[cfg(any(feature = "amm", test))]
+pub use amm::AmmError;
+
 // Additional test-only exports can be placed here if needed
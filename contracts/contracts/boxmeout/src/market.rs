@@ -1,9 +1,12 @@
 // contracts/market.rs - Individual Prediction Market Contract
 // Handles predictions, bet commitment/reveal, market resolution, and winnings claims
 
+use crate::helpers::{compute_payout, isqrt};
 use soroban_sdk::{
-    contract, contracterror, contractevent, contractimpl, contracttype, token, Address, BytesN,
-    Env, Symbol, Vec,
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contracterror, contractevent, contractimpl, contracttype, token, vec,
+    xdr::ToXdr,
+    Address, BytesN, Env, IntoVal, Symbol, Vec,
 };
 
 #[contractevent]
@@ -23,12 +26,33 @@ pub struct CommitmentMadeEvent {
     pub amount: i128,
 }
 
+#[contractevent]
+pub struct CommitmentIncreasedEvent {
+    pub user: Address,
+    pub market_id: BytesN<32>,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct CommitmentWithdrawnEvent {
+    pub user: Address,
+    pub market_id: BytesN<32>,
+    pub amount: i128,
+}
+
 #[contractevent]
 pub struct MarketClosedEvent {
     pub market_id: BytesN<32>,
     pub timestamp: u64,
 }
 
+#[contractevent]
+pub struct MarketReopenedEvent {
+    pub market_id: BytesN<32>,
+    pub closing_time: u64,
+    pub timestamp: u64,
+}
+
 #[contractevent]
 pub struct MarketResolvedEvent {
     pub market_id: BytesN<32>,
@@ -40,6 +64,9 @@ pub struct MarketResolvedEvent {
 pub struct WinningsClaimedEvent {
     pub user: Address,
     pub market_id: BytesN<32>,
+    pub gross_payout: i128,
+    pub protocol_fee: i128,
+    pub creator_fee: i128,
     pub net_payout: i128,
 }
 
@@ -61,9 +88,74 @@ pub struct MarketDisputedEvent {
 }
 
 #[contractevent]
-pub struct RefundedEvent {
+pub struct DisputeResolvedEvent {
+    pub market_id: BytesN<32>,
+    pub disputer: Address,
+    pub upheld: bool,
+    pub final_outcome: u32,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+pub struct ClaimsReopenedEvent {
+    pub market_id: BytesN<32>,
+    pub final_outcome: u32,
+    pub stale_payout: i128,
+    pub insurance_drawn: i128,
+    pub claim_window_end: u64,
+}
+
+#[contractevent]
+pub struct RevealBondForfeitedEvent {
     pub user: Address,
     pub market_id: BytesN<32>,
+    pub bond: i128,
+    pub treasury: Address,
+}
+
+#[contractevent]
+pub struct CreatorBondSettledEvent {
+    pub market_id: BytesN<32>,
+    pub creator: Address,
+    pub bond: i128,
+    pub slashed: bool,
+}
+
+#[contractevent]
+pub struct AmmSeededEvent {
+    pub market_id: BytesN<32>,
+    pub amm: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct PoolUpdatedEvent {
+    pub market_id: BytesN<32>,
+    pub yes_pool: i128,
+    pub no_pool: i128,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+pub struct MarketStateChangedEvent {
+    pub market_id: BytesN<32>,
+    pub old_state: u32,
+    pub new_state: u32,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+pub struct ResolutionBountyPaidEvent {
+    pub market_id: BytesN<32>,
+    pub caller: Address,
+    pub action: Symbol,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct RefundProcessedEvent {
+    pub market_id: BytesN<32>,
+    pub user: Address,
     pub amount: i128,
     pub timestamp: u64,
 }
@@ -80,15 +172,64 @@ const MARKET_STATE_KEY: &str = "market_state";
 const YES_POOL_KEY: &str = "yes_pool";
 const NO_POOL_KEY: &str = "no_pool";
 const TOTAL_VOLUME_KEY: &str = "total_volume";
+const YES_COUNT_KEY: &str = "yes_count";
+const NO_COUNT_KEY: &str = "no_count";
 const PENDING_COUNT_KEY: &str = "pending_count";
+const MAX_PENDING_KEY: &str = "max_pending";
+const QUADRATIC_WEIGHTING_KEY: &str = "quadratic_weighting";
+const PRINCIPAL_PROTECTION_KEY: &str = "principal_protection";
+const FEE_BPS_KEY: &str = "fee_bps";
+const COMMIT_AMOUNT_BUCKET_KEY: &str = "commit_amount_bucket";
 const COMMIT_PREFIX: &str = "commit";
+const USED_COMMIT_HASH_PREFIX: &str = "used_commit_hash";
 const PARTICIPANTS_KEY: &str = "participants";
 const PREDICTION_PREFIX: &str = "prediction";
+const CLAIMED_PAYOUT_PREFIX: &str = "claimed_payout";
 const REVEALED_PARTICIPANTS_KEY: &str = "revealed_participants";
 const REFUNDED_PREFIX: &str = "refunded";
+const REFUND_FAILED_PREFIX: &str = "refund_failed";
+const PRIVATE_MARKET_KEY: &str = "private_market";
+const ALLOWED_BETTOR_PREFIX: &str = "allowed_bettor";
+const REQUIRE_KYC_KEY: &str = "require_kyc";
+const KYC_PREFIX: &str = "kyc";
 const WINNING_OUTCOME_KEY: &str = "winning_outcome";
 const WINNER_SHARES_KEY: &str = "winner_shares";
+const WINNER_WEIGHT_KEY: &str = "winner_weight";
 const LOSER_SHARES_KEY: &str = "loser_shares";
+const FEES_COLLECTED_KEY: &str = "fees_collected";
+const DISPUTE_STAKE_HELD_KEY: &str = "dispute_stake_held";
+const DISPUTE_STAKE_FORFEITED_KEY: &str = "dispute_stake_forfeited";
+const REVEAL_BOND_KEY: &str = "reveal_bond";
+const MIN_REVEAL_DELAY_KEY: &str = "min_reveal_delay";
+const FEE_FREE_UNTIL_KEY: &str = "fee_free_until";
+const GOV_TOKEN_KEY: &str = "gov_token";
+const FEE_DISCOUNT_TIERS_KEY: &str = "fee_discount_tiers";
+const COMMIT_COUNT_KEY: &str = "commit_count";
+const CREATOR_BOND_KEY: &str = "creator_bond";
+const AMM_KEY: &str = "amm";
+const AMM_SEED_BPS_KEY: &str = "amm_seed_bps";
+const AMM_SEEDED_KEY: &str = "amm_seeded";
+const SETTLE_AMM_ON_CLOSE_KEY: &str = "settle_amm_on_close";
+const MIN_REVEAL_RATIO_KEY: &str = "min_reveal_ratio";
+const SPLIT_ON_TIE_KEY: &str = "split_on_tie";
+const POSITION_TRACKING_KEY: &str = "position_tracking";
+const KEEPER_PREFIX: &str = "keeper";
+const OUTCOME_LABELS_KEY: &str = "outcome_labels";
+const INSURANCE_FUND_KEY: &str = "insurance_fund";
+const RESOLUTION_EPOCH_KEY: &str = "resolution_epoch";
+const CLAIM_EPOCH_PREFIX: &str = "claim_epoch";
+const CLAIMED_GROSS_TOTAL_KEY: &str = "claimed_gross_total";
+const UNCOVERED_SHORTFALL_KEY: &str = "uncovered_shortfall";
+const DISPUTE_CLAIM_GRACE_KEY: &str = "dispute_claim_grace";
+const DISPUTE_STAKE_KEY: &str = "dispute_stake";
+const DISPUTE_WINDOW_KEY: &str = "dispute_window";
+const CLAIM_WINDOW_END_KEY: &str = "claim_window_end";
+const RESOLUTION_BOUNTY_KEY: &str = "resolution_bounty";
+const BOUNTY_PAID_PREFIX: &str = "bounty_paid";
+const BOUNTIES_PAID_TOTAL_KEY: &str = "bounties_paid_total";
+const CANCEL_SIGNERS_KEY: &str = "cancel_signers";
+const CANCEL_THRESHOLD_KEY: &str = "cancel_threshold";
+const CANCEL_APPROVALS_KEY: &str = "cancel_approvals";
 
 /// Market states
 const STATE_OPEN: u32 = 0;
@@ -97,6 +238,23 @@ const STATE_RESOLVED: u32 = 2;
 const STATE_DISPUTED: u32 = 3;
 const STATE_CANCELLED: u32 = 4;
 
+/// Default time new winners get to claim after a dispute flips the outcome (7 days)
+const DEFAULT_DISPUTE_CLAIM_GRACE: u64 = 604800;
+/// Default stake a disputer locks (see `set_dispute_params`)
+const DEFAULT_DISPUTE_STAKE: i128 = 1000;
+/// Default dispute window after resolution_time: 7 days
+const DEFAULT_DISPUTE_WINDOW: u64 = 604800;
+
+/// How long after closing_time a mistakenly closed market may still be reopened (1 hour)
+const REOPEN_GRACE_PERIOD: u64 = 3600;
+
+/// Default protocol fee withheld from winning payouts, in basis points (10%)
+const PROTOCOL_FEE_BPS: i128 = 1000;
+/// Highest protocol fee a creator may configure, in basis points (30%)
+const MAX_FEE_BPS: u32 = 3000;
+/// Portion of the withheld fee attributed to the market creator (matches the Treasury's default split)
+const CREATOR_FEE_SHARE_PCT: i128 = 20;
+
 /// Error codes following Soroban best practices
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -126,6 +284,27 @@ pub enum MarketError {
     InvalidReveal = 11,
     /// User has already revealed their prediction
     DuplicateReveal = 12,
+    /// User is not permitted to take part in this market
+    Unauthorized = 13,
+    /// Market requires KYC approval the user does not have
+    KycNotApproved = 14,
+    /// Reveal attempted too soon after the commit (same ledger)
+    RevealTooEarly = 15,
+    /// Revealed amount differs from the committed amount (see `get_committed_amount`)
+    AmountMismatch = 16,
+    /// Pending (unrevealed) commitments are at the configured cap; retry after reveals
+    TooManyPending = 17,
+    /// Another commitment in this market already uses the same commit hash
+    DuplicateCommitHash = 18,
+}
+
+/// Protocol fee discount for holders of at least `min_balance` governance tokens
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeDiscountTier {
+    pub min_balance: i128,
+    /// Share of the protocol fee waived, in basis points
+    pub discount_bps: u32,
 }
 
 /// Commitment record for commit-reveal scheme
@@ -136,6 +315,10 @@ pub struct Commitment {
     pub commit_hash: BytesN<32>,
     pub amount: i128,
     pub timestamp: u64,
+    /// Reveal bond held on top of `amount`; returned on reveal, forfeited otherwise
+    pub bond: i128,
+    /// Ledger sequence the commit landed in
+    pub ledger_sequence: u32,
 }
 
 /// Dispute record
@@ -145,6 +328,8 @@ pub struct DisputeRecord {
     pub user: Address,
     pub reason: Symbol,
     pub evidence: Option<BytesN<32>>,
+    /// Stake held in the dispute bucket (never part of the prediction pools)
+    pub stake: i128,
     pub timestamp: u64,
 }
 
@@ -166,6 +351,12 @@ pub const PREDICTION_STATUS_REVEALED: u32 = 1;
 /// Sentinel for predicted_outcome when not yet revealed
 pub const PREDICTION_OUTCOME_NONE: u32 = 2;
 
+/// Winning outcome recorded for a market resolved as a tie (split payout)
+pub const OUTCOME_TIE: u32 = 3;
+
+/// Number of outcomes a market resolves between (0=NO, 1=YES)
+pub const OUTCOME_COUNT: u32 = 2;
+
 /// Single revealed prediction for paginated list (commit-phase privacy preserved)
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -214,104 +405,201 @@ pub struct MarketState {
     pub winning_outcome: Option<u32>,
 }
 
+/// Everything a frontend needs about a market in a single read
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketSummary {
+    /// Current market status: 0=OPEN, 1=CLOSED, 2=RESOLVED, 3=DISPUTED, 4=CANCELLED
+    pub status: u32,
+    pub closing_time: u64,
+    pub resolution_time: u64,
+    pub yes_pool: i128,
+    pub no_pool: i128,
+    pub total_volume: i128,
+    /// Users who have committed (revealed or not)
+    pub participant_count: u32,
+    /// Users who have revealed their prediction
+    pub revealed_count: u32,
+    /// Commitments still awaiting reveal
+    pub pending_count: u32,
+    /// Winning outcome (0=NO, 1=YES), None until resolved
+    pub winning_outcome: Option<u32>,
+    /// Protocol fee withheld from winning payouts (basis points)
+    pub protocol_fee_bps: u32,
+    /// Reveal bond charged on new commitments
+    pub reveal_bond: i128,
+    /// Whether the market is currently disputed
+    pub is_disputed: bool,
+    /// Disputer of the open dispute, if any
+    pub disputer: Option<Address>,
+    /// Dispute stake currently held by the market
+    pub dispute_stake_held: i128,
+}
+
 /// PREDICTION MARKET - Manages individual market logic
 #[contract]
 pub struct PredictionMarket;
 
-#[contractimpl]
-impl PredictionMarket {
-    /// Initialize a single market instance
-    #[allow(clippy::too_many_arguments)]
-    pub fn initialize(
-        env: Env,
-        market_id: BytesN<32>,
-        creator: Address,
-        factory: Address,
-        usdc_token: Address,
-        oracle: Address,
-        closing_time: u64,
-        resolution_time: u64,
-    ) {
-        // Verify creator signature
-        creator.require_auth();
+// `initialize` gets its own contractimpl so the lint allowance also covers the argument
+// helpers the macro generates for it, without silencing the rest of the contract
+#[allow(clippy::too_many_arguments)]
+mod init {
+    use super::*;
 
-        // Store market_id reference
-        env.storage()
-            .persistent()
-            .set(&Symbol::new(&env, MARKET_ID_KEY), &market_id);
+    #[contractimpl]
+    impl PredictionMarket {
+        /// Initialize a single market instance
+        ///
+        /// `allowed_bettors` makes the market invite-only; pass an empty list for a public market.
+        /// `creator_bond` is pulled from the creator and held until the market settles: it is
+        /// returned on resolution and slashed to the treasury if the creator cancels (0 = no bond).
+        /// `outcome_labels` names each outcome by index for frontends; pass an empty list to use
+        /// the default `["NO", "YES"]`.
+        pub fn initialize(
+            env: Env,
+            market_id: BytesN<32>,
+            creator: Address,
+            factory: Address,
+            usdc_token: Address,
+            oracle: Address,
+            closing_time: u64,
+            resolution_time: u64,
+            allowed_bettors: Vec<Address>,
+            creator_bond: i128,
+            outcome_labels: Vec<Symbol>,
+        ) {
+            // Verify creator signature
+            creator.require_auth();
+
+            if creator_bond < 0 {
+                panic!("Creator bond cannot be negative");
+            }
+            if !outcome_labels.is_empty() {
+                if outcome_labels.len() != OUTCOME_COUNT {
+                    panic!("Outcome label count must match outcome count");
+                }
+                env.storage()
+                    .persistent()
+                    .set(&Symbol::new(&env, OUTCOME_LABELS_KEY), &outcome_labels);
+            }
 
-        // Store creator address
-        env.storage()
-            .persistent()
-            .set(&Symbol::new(&env, CREATOR_KEY), &creator);
+            // Store market_id reference
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, MARKET_ID_KEY), &market_id);
 
-        env.storage()
-            .persistent()
-            .set(&Symbol::new(&env, FACTORY_KEY), &factory);
+            // Store creator address
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, CREATOR_KEY), &creator);
 
-        // Store USDC token address
-        env.storage()
-            .persistent()
-            .set(&Symbol::new(&env, USDC_KEY), &usdc_token);
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, FACTORY_KEY), &factory);
 
-        // Store oracle address
-        env.storage()
-            .persistent()
-            .set(&Symbol::new(&env, ORACLE_KEY), &oracle);
+            // Store USDC token address
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, USDC_KEY), &usdc_token);
 
-        // Store timing
-        env.storage()
-            .persistent()
-            .set(&Symbol::new(&env, CLOSING_TIME_KEY), &closing_time);
+            // Store oracle address
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, ORACLE_KEY), &oracle);
 
-        env.storage()
-            .persistent()
-            .set(&Symbol::new(&env, RESOLUTION_TIME_KEY), &resolution_time);
+            // Store timing
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, CLOSING_TIME_KEY), &closing_time);
 
-        env.storage()
-            .persistent()
-            .set(&Symbol::new(&env, MARKET_STATE_KEY), &STATE_OPEN);
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, RESOLUTION_TIME_KEY), &resolution_time);
 
-        // Initialize prediction pools
-        env.storage()
-            .persistent()
-            .set(&Symbol::new(&env, YES_POOL_KEY), &0i128);
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, MARKET_STATE_KEY), &STATE_OPEN);
 
-        env.storage()
-            .persistent()
-            .set(&Symbol::new(&env, NO_POOL_KEY), &0i128);
+            // Initialize prediction pools
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, YES_POOL_KEY), &0i128);
 
-        // Initialize total volume
-        env.storage()
-            .persistent()
-            .set(&Symbol::new(&env, TOTAL_VOLUME_KEY), &0i128);
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, NO_POOL_KEY), &0i128);
 
-        // Initialize pending count
-        env.storage()
-            .persistent()
-            .set(&Symbol::new(&env, PENDING_COUNT_KEY), &0u32);
+            // Initialize total volume
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, TOTAL_VOLUME_KEY), &0i128);
 
-        // Emit initialization event
-        MarketInitializedEvent {
-            market_id,
-            creator,
-            factory,
-            oracle,
-            closing_time,
-            resolution_time,
+            // Initialize pending count
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, PENDING_COUNT_KEY), &0u32);
+
+            // Protocol fee starts at the default 10%
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, FEE_BPS_KEY), &(PROTOCOL_FEE_BPS as u32));
+
+            // Private market: only allowlisted users may commit
+            if !allowed_bettors.is_empty() {
+                env.storage()
+                    .persistent()
+                    .set(&Symbol::new(&env, PRIVATE_MARKET_KEY), &true);
+                for bettor in allowed_bettors.iter() {
+                    env.storage()
+                        .persistent()
+                        .set(&Self::get_allowed_bettor_key(&env, &bettor), &true);
+                }
+            }
+
+            // Escrow the creator bond
+            if creator_bond > 0 {
+                token::TokenClient::new(&env, &usdc_token).transfer(
+                    &creator,
+                    env.current_contract_address(),
+                    &creator_bond,
+                );
+                env.storage()
+                    .persistent()
+                    .set(&Symbol::new(&env, CREATOR_BOND_KEY), &creator_bond);
+            }
+
+            // Emit initialization event
+            MarketInitializedEvent {
+                market_id,
+                creator,
+                factory,
+                oracle,
+                closing_time,
+                resolution_time,
+            }
+            .publish(&env);
         }
-        .publish(&env);
     }
+}
 
+#[contractimpl]
+impl PredictionMarket {
     /// Phase 1: User commits to a prediction (commit-reveal scheme for privacy)
     ///
+    /// `commit_hash` = sha256(market_id || user || outcome_be_bytes || salt), where `user`
+    /// is the committer's XDR-encoded address; `reveal_prediction` reconstructs it.
+    ///
     /// - Require user authentication
     /// - Validate market is in OPEN state
     /// - Validate current timestamp < closing_time
     /// - Validate amount > 0
+    /// - Reject users missing from the allowlist on private markets
+    /// - Reject users without KYC approval when KYC mode is enabled
     /// - Prevent user from committing twice (check existing commits)
-    /// - Transfer amount from user to market escrow
-    /// - Store commit record: { user, commit_hash, amount, timestamp }
+    /// - Reject a commit hash already used by any commitment in this market
+    /// - Reject while pending commitments are at `max_pending` (back-pressure)
+    /// - Transfer amount (plus any configured reveal bond) from user to market escrow
+    /// - Store commit record: { user, commit_hash, amount, timestamp, bond }
     /// - Emit CommitmentMade(user, market_id, amount)
     /// - Update pending_predictions count
     pub fn commit_prediction(
@@ -352,12 +640,40 @@ impl PredictionMarket {
             return Err(MarketError::InvalidAmount);
         }
 
+        // Enforce allowlist on private markets
+        if !Self::is_allowed_bettor(env.clone(), user.clone()) {
+            return Err(MarketError::Unauthorized);
+        }
+
+        // Enforce KYC approval for regulated deployments
+        if Self::is_kyc_required(env.clone()) && !Self::get_kyc_status(env.clone(), user.clone()) {
+            return Err(MarketError::KycNotApproved);
+        }
+
         // Check for duplicate commit per user
         let commit_key = Self::get_commit_key(&env, &user);
         if env.storage().persistent().has(&commit_key) {
             return Err(MarketError::DuplicateCommit);
         }
 
+        // Reject exact commit hash collisions across users
+        let used_hash_key = (
+            Symbol::new(&env, USED_COMMIT_HASH_PREFIX),
+            commit_hash.clone(),
+        );
+        if env.storage().persistent().has(&used_hash_key) {
+            return Err(MarketError::DuplicateCommitHash);
+        }
+
+        // Back-pressure: wait for reveals to drain the pending set
+        let max_pending = Self::get_max_pending(env.clone());
+        if max_pending > 0 && Self::get_pending_count(env.clone()) >= max_pending {
+            return Err(MarketError::TooManyPending);
+        }
+
+        // Factory-level exposure cap; panics when the user is at their limit
+        Self::report_position(&env, &user, true);
+
         // Get USDC token contract and market_id
         let usdc_token: Address = env
             .storage()
@@ -371,12 +687,18 @@ impl PredictionMarket {
             .get(&Symbol::new(&env, MARKET_ID_KEY))
             .ok_or(MarketError::NotInitialized)?;
 
+        let bond: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, REVEAL_BOND_KEY))
+            .unwrap_or(0);
+
         // Transfer USDC from user to market escrow (this contract)
         let token_client = token::TokenClient::new(&env, &usdc_token);
         let contract_address = env.current_contract_address();
 
         // Transfer tokens - will panic if insufficient balance or approval
-        token_client.transfer(&user, &contract_address, &amount);
+        token_client.transfer(&user, &contract_address, &(amount + bond));
 
         // Create and store commitment record
         let commitment = Commitment {
@@ -384,20 +706,25 @@ impl PredictionMarket {
             commit_hash: commit_hash.clone(),
             amount,
             timestamp: current_time,
+            bond,
+            ledger_sequence: env.ledger().sequence(),
         };
 
         env.storage().persistent().set(&commit_key, &commitment);
+        env.storage().persistent().set(&used_hash_key, &true);
 
-        // Add user to participants (for cancel refunds)
+        // Add user to participants (for cancel refunds and the leaderboard), once per user
         let mut participants: Vec<Address> = env
             .storage()
             .persistent()
             .get(&Symbol::new(&env, PARTICIPANTS_KEY))
             .unwrap_or_else(|| Vec::new(&env));
-        participants.push_back(user.clone());
-        env.storage()
-            .persistent()
-            .set(&Symbol::new(&env, PARTICIPANTS_KEY), &participants);
+        if !participants.contains(&user) {
+            participants.push_back(user.clone());
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, PARTICIPANTS_KEY), &participants);
+        }
 
         // Update pending count
         let pending_count: u32 = env
@@ -410,133 +737,1420 @@ impl PredictionMarket {
             .persistent()
             .set(&Symbol::new(&env, PENDING_COUNT_KEY), &(pending_count + 1));
 
-        // Emit CommitmentMade event
+        // Update commit count (basis for the reveal ratio; decremented on withdrawal)
+        let commit_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, COMMIT_COUNT_KEY))
+            .unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, COMMIT_COUNT_KEY), &(commit_count + 1));
+
+        // Emit CommitmentMade event (bucketed amount when configured)
         CommitmentMadeEvent {
             user,
             market_id,
-            amount,
+            amount: Self::bucketed_amount(&env, amount),
         }
         .publish(&env);
 
         Ok(())
     }
 
-    /// Helper: Generate storage key for user commitment
-    fn get_commit_key(env: &Env, user: &Address) -> (Symbol, Address) {
-        (Symbol::new(env, COMMIT_PREFIX), user.clone())
-    }
-
-    /// Helper: Generate storage key for user prediction
-    fn get_prediction_key(env: &Env, user: &Address) -> (Symbol, Address) {
-        (Symbol::new(env, PREDICTION_PREFIX), user.clone())
-    }
-
-    /// Helper: Storage key for refunded flag (prevents double-refund)
-    fn get_refunded_key(env: &Env, user: &Address) -> (Symbol, Address) {
-        (Symbol::new(env, REFUNDED_PREFIX), user.clone())
-    }
-
-    /// Helper: Get user commitment (for testing and reveal phase)
-    pub fn get_commitment(env: Env, user: Address) -> Option<Commitment> {
-        let commit_key = Self::get_commit_key(&env, &user);
-        env.storage().persistent().get(&commit_key)
-    }
-
-    /// Helper: Get pending commit count
-    pub fn get_pending_count(env: Env) -> u32 {
-        env.storage()
-            .persistent()
-            .get(&Symbol::new(&env, PENDING_COUNT_KEY))
-            .unwrap_or(0)
-    }
-
-    /// Helper: Get market state
-    pub fn get_market_state_value(env: Env) -> Option<u32> {
-        env.storage()
-            .persistent()
-            .get(&Symbol::new(&env, MARKET_STATE_KEY))
-    }
-
-    /// Phase 2: User reveals their committed prediction
+    /// User tops up their unrevealed commitment (same outcome and salt)
     ///
-    /// Verifies the commitment hash matches hash(user + market_id + outcome + salt),
-    /// transitions prediction from COMMITTED → REVEALED, updates pools,
-    /// and emits a PredictionRevealed event.
+    /// - Require user authentication
+    /// - Validate market is in OPEN state and current timestamp < closing_time
+    /// - Validate additional_amount > 0
+    /// - Transfer additional_amount into escrow and accumulate it on the commitment;
+    ///   the commit hash and reveal timing are unchanged
+    /// - Emit CommitmentIncreased(user, market_id, amount), bucketed like CommitmentMade
+    ///
+    /// The reveal must then carry the accumulated amount, which is what gets pooled and
+    /// counted towards total volume.
     ///
     /// # Errors
     /// - `NotInitialized` - Market not initialized
     /// - `InvalidMarketState` - Market not in OPEN state
     /// - `MarketClosed` - Current time >= closing time
+    /// - `InvalidAmount` - additional_amount <= 0
+    /// - `DuplicateReveal` - User already revealed; revealed stakes can't be increased
     /// - `NoPrediction` - No commitment found for this user
-    /// - `DuplicateReveal` - User already revealed (prediction record exists)
-    /// - `InvalidReveal` - Reconstructed hash doesn't match stored commit hash
-    /// - `InvalidAmount` - Revealed amount doesn't match committed amount
-    pub fn reveal_prediction(
+    pub fn add_to_commitment(
         env: Env,
         user: Address,
-        market_id: BytesN<32>,
-        outcome: u32,
-        amount: i128,
-        salt: BytesN<32>,
+        additional_amount: i128,
     ) -> Result<(), MarketError> {
-        // 1. Require user authentication
         user.require_auth();
 
-        // 2. Validate market is initialized and in OPEN state
         let market_state: u32 = env
             .storage()
             .persistent()
             .get(&Symbol::new(&env, MARKET_STATE_KEY))
             .ok_or(MarketError::NotInitialized)?;
-
         if market_state != STATE_OPEN {
             return Err(MarketError::InvalidMarketState);
         }
 
-        // 3. Validate current timestamp < closing_time
         let closing_time: u64 = env
             .storage()
             .persistent()
             .get(&Symbol::new(&env, CLOSING_TIME_KEY))
             .ok_or(MarketError::NotInitialized)?;
-
-        let current_time = env.ledger().timestamp();
-        if current_time >= closing_time {
+        if env.ledger().timestamp() >= closing_time {
             return Err(MarketError::MarketClosed);
         }
 
-        // 4. Check for duplicate reveal (prediction record already exists)
-        let prediction_key = Self::get_prediction_key(&env, &user);
-        if env.storage().persistent().has(&prediction_key) {
+        if additional_amount <= 0 {
+            return Err(MarketError::InvalidAmount);
+        }
+
+        if env
+            .storage()
+            .persistent()
+            .has(&Self::get_prediction_key(&env, &user))
+        {
             return Err(MarketError::DuplicateReveal);
         }
 
-        // 5. Validate user has a prior commitment
         let commit_key = Self::get_commit_key(&env, &user);
-        let commitment: Commitment = env
+        let mut commitment: Commitment = env
             .storage()
             .persistent()
             .get(&commit_key)
             .ok_or(MarketError::NoPrediction)?;
 
-        // 6. Validate the revealed amount matches the committed amount
-        if amount != commitment.amount {
-            return Err(MarketError::InvalidAmount);
-        }
-
-        // 7. Reconstruct commitment hash from revealed data: sha256(market_id + outcome + salt)
-        //    The user address is implicitly bound via the per-user commit storage key,
-        //    so it doesn't need to be included in the hash preimage.
-        let mut preimage = soroban_sdk::Bytes::new(&env);
-        preimage.extend_from_array(&market_id.to_array());
-        preimage.extend_from_array(&outcome.to_be_bytes());
-        preimage.extend_from_array(&salt.to_array());
-
-        let reconstructed_hash = env.crypto().sha256(&preimage);
-
-        // 8. Compare reconstructed hash with stored commit hash (convert Hash<32> -> BytesN<32>)
-        let reconstructed_bytes = BytesN::from_array(&env, &reconstructed_hash.to_array());
-        if reconstructed_bytes != commitment.commit_hash {
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .ok_or(MarketError::NotInitialized)?;
+        let market_id: BytesN<32> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_ID_KEY))
+            .ok_or(MarketError::NotInitialized)?;
+
+        token::TokenClient::new(&env, &usdc_token).transfer(
+            &user,
+            env.current_contract_address(),
+            &additional_amount,
+        );
+
+        commitment.amount += additional_amount;
+        env.storage().persistent().set(&commit_key, &commitment);
+
+        CommitmentIncreasedEvent {
+            user,
+            market_id,
+            amount: Self::bucketed_amount(&env, additional_amount),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// User withdraws an unrevealed commitment and reclaims their escrowed funds
+    ///
+    /// - Require user authentication
+    /// - Validate market is in OPEN state and current timestamp < closing_time
+    /// - Refund the committed amount plus any reveal bond from escrow
+    /// - Remove the commit record and the user from participants; decrement pending count
+    /// - Emit CommitmentWithdrawn(user, market_id, amount), bucketed like CommitmentMade
+    ///
+    /// The commit hash stays marked as used. The user may commit again afterwards.
+    ///
+    /// # Errors
+    /// - `NotInitialized` - Market not initialized
+    /// - `InvalidMarketState` - Market not in OPEN state
+    /// - `MarketClosed` - Current time >= closing time
+    /// - `DuplicateReveal` - User already revealed; revealed stakes can't be withdrawn
+    /// - `NoPrediction` - No commitment found for this user
+    pub fn withdraw_commitment(env: Env, user: Address) -> Result<(), MarketError> {
+        user.require_auth();
+
+        let market_state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .ok_or(MarketError::NotInitialized)?;
+        if market_state != STATE_OPEN {
+            return Err(MarketError::InvalidMarketState);
+        }
+
+        let closing_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CLOSING_TIME_KEY))
+            .ok_or(MarketError::NotInitialized)?;
+        if env.ledger().timestamp() >= closing_time {
+            return Err(MarketError::MarketClosed);
+        }
+
+        if env
+            .storage()
+            .persistent()
+            .has(&Self::get_prediction_key(&env, &user))
+        {
+            return Err(MarketError::DuplicateReveal);
+        }
+
+        let commit_key = Self::get_commit_key(&env, &user);
+        let commitment: Commitment = env
+            .storage()
+            .persistent()
+            .get(&commit_key)
+            .ok_or(MarketError::NoPrediction)?;
+
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .ok_or(MarketError::NotInitialized)?;
+        let market_id: BytesN<32> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_ID_KEY))
+            .ok_or(MarketError::NotInitialized)?;
+
+        env.storage().persistent().remove(&commit_key);
+
+        let mut participants: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, PARTICIPANTS_KEY))
+            .unwrap_or_else(|| Vec::new(&env));
+        if let Some(index) = participants.first_index_of(&user) {
+            participants.remove(index);
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, PARTICIPANTS_KEY), &participants);
+        }
+
+        let pending_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, PENDING_COUNT_KEY))
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &Symbol::new(&env, PENDING_COUNT_KEY),
+            &pending_count.saturating_sub(1),
+        );
+
+        // The reveal ratio only counts commitments that are still live
+        let commit_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, COMMIT_COUNT_KEY))
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &Symbol::new(&env, COMMIT_COUNT_KEY),
+            &commit_count.saturating_sub(1),
+        );
+
+        Self::report_position(&env, &user, false);
+
+        token::TokenClient::new(&env, &usdc_token).transfer(
+            &env.current_contract_address(),
+            &user,
+            &(commitment.amount + commitment.bond),
+        );
+
+        CommitmentWithdrawnEvent {
+            user,
+            market_id,
+            amount: Self::bucketed_amount(&env, commitment.amount),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Helper: Amount as published in commitment events, rounded down to the configured bucket
+    fn bucketed_amount(env: &Env, amount: i128) -> i128 {
+        let bucket = Self::get_commit_amount_bucket(env.clone());
+        if bucket > 0 {
+            amount / bucket * bucket
+        } else {
+            amount
+        }
+    }
+
+    /// Helper: Generate storage key for user commitment
+    fn get_commit_key(env: &Env, user: &Address) -> (Symbol, Address) {
+        (Symbol::new(env, COMMIT_PREFIX), user.clone())
+    }
+
+    /// Helper: Generate storage key for user prediction
+    fn get_prediction_key(env: &Env, user: &Address) -> (Symbol, Address) {
+        (Symbol::new(env, PREDICTION_PREFIX), user.clone())
+    }
+
+    /// Helper: Storage key for refunded flag (prevents double-refund)
+    fn get_refunded_key(env: &Env, user: &Address) -> (Symbol, Address) {
+        (Symbol::new(env, REFUNDED_PREFIX), user.clone())
+    }
+
+    /// Helper: Storage key for a refund push that failed and is awaiting retry
+    fn get_refund_failed_key(env: &Env, user: &Address) -> (Symbol, Address) {
+        (Symbol::new(env, REFUND_FAILED_PREFIX), user.clone())
+    }
+
+    /// Helper: Amount owed to a user on cancellation (committed or revealed stake, plus
+    /// any reveal bond still held)
+    fn get_refundable_amount(env: &Env, user: &Address) -> Option<i128> {
+        if let Some(commitment) = env
+            .storage()
+            .persistent()
+            .get::<_, Commitment>(&Self::get_commit_key(env, user))
+        {
+            return Some(commitment.amount + commitment.bond);
+        }
+        env.storage()
+            .persistent()
+            .get::<_, UserPrediction>(&Self::get_prediction_key(env, user))
+            .map(|pred| pred.amount)
+    }
+
+    /// Helper: Clear a user's position once their refund has been paid out
+    fn clear_refunded_position(env: &Env, user: &Address) {
+        env.storage()
+            .persistent()
+            .remove(&Self::get_commit_key(env, user));
+        env.storage()
+            .persistent()
+            .remove(&Self::get_prediction_key(env, user));
+        env.storage()
+            .persistent()
+            .remove(&Self::get_refund_failed_key(env, user));
+        env.storage()
+            .persistent()
+            .set(&Self::get_refunded_key(env, user), &true);
+
+        Self::report_position(env, user, false);
+    }
+
+    /// Helper: Close out a paid refund and emit RefundProcessedEvent
+    fn complete_refund(env: &Env, market_id: BytesN<32>, user: Address, amount: i128) {
+        Self::clear_refunded_position(env, &user);
+
+        RefundProcessedEvent {
+            market_id,
+            user,
+            amount,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(env);
+    }
+
+    /// Helper: Tell the factory a user opened or closed their position here, when the
+    /// factory has enrolled this market in per-user exposure tracking
+    fn report_position(env: &Env, user: &Address, opened: bool) {
+        let tracked: bool = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, POSITION_TRACKING_KEY))
+            .unwrap_or(false);
+        if !tracked {
+            return;
+        }
+
+        let factory: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, FACTORY_KEY))
+            .expect("Factory address not set");
+        let func = if opened {
+            "record_position_opened"
+        } else {
+            "record_position_closed"
+        };
+        env.invoke_contract::<()>(
+            &factory,
+            &Symbol::new(env, func),
+            (env.current_contract_address(), user.clone()).into_val(env),
+        );
+    }
+
+    /// Helper: Storage key for a user's allowlist entry on private markets
+    fn get_allowed_bettor_key(env: &Env, user: &Address) -> (Symbol, Address) {
+        (Symbol::new(env, ALLOWED_BETTOR_PREFIX), user.clone())
+    }
+
+    /// Helper: Publish the current YES/NO pools so indexers can track them without polling
+    fn publish_pool_update(env: &Env, market_id: &BytesN<32>) {
+        let yes_pool: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, YES_POOL_KEY))
+            .unwrap_or(0);
+        let no_pool: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, NO_POOL_KEY))
+            .unwrap_or(0);
+
+        PoolUpdatedEvent {
+            market_id: market_id.clone(),
+            yes_pool,
+            no_pool,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(env);
+    }
+
+    /// Helper: Move the market to `new_state` and emit the unified lifecycle event
+    fn set_market_state(env: &Env, new_state: u32) {
+        let old_state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, MARKET_STATE_KEY))
+            .unwrap_or(STATE_OPEN);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(env, MARKET_STATE_KEY), &new_state);
+
+        let market_id: BytesN<32> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, MARKET_ID_KEY))
+            .expect("Market not initialized");
+        MarketStateChangedEvent {
+            market_id,
+            old_state,
+            new_state,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(env);
+    }
+
+    /// Helper: Require the caller to be the market creator
+    fn require_creator(env: &Env, caller: &Address) {
+        caller.require_auth();
+
+        let creator: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, CREATOR_KEY))
+            .expect("Market not initialized");
+
+        if *caller != creator {
+            panic!("Unauthorized: only creator can manage this market");
+        }
+    }
+
+    /// Creator: allow a user to bet on this market (makes a public market private)
+    pub fn add_allowed_bettor(env: Env, creator: Address, user: Address) {
+        Self::require_creator(&env, &creator);
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, PRIVATE_MARKET_KEY), &true);
+        env.storage()
+            .persistent()
+            .set(&Self::get_allowed_bettor_key(&env, &user), &true);
+    }
+
+    /// Creator: remove a user from the allowlist (existing commitments are unaffected)
+    pub fn remove_allowed_bettor(env: Env, creator: Address, user: Address) {
+        Self::require_creator(&env, &creator);
+
+        env.storage()
+            .persistent()
+            .remove(&Self::get_allowed_bettor_key(&env, &user));
+    }
+
+    /// Get each outcome's label, indexed by outcome (`["NO", "YES"]` unless set at init)
+    pub fn get_outcome_labels(env: Env) -> Vec<Symbol> {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, OUTCOME_LABELS_KEY))
+            .unwrap_or_else(|| {
+                Vec::from_array(&env, [Symbol::new(&env, "NO"), Symbol::new(&env, "YES")])
+            })
+    }
+
+    /// Whether the market is restricted to allowlisted bettors
+    pub fn is_private(env: Env) -> bool {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, PRIVATE_MARKET_KEY))
+            .unwrap_or(false)
+    }
+
+    /// Whether a user may commit to this market (always true for public markets)
+    pub fn is_allowed_bettor(env: Env, user: Address) -> bool {
+        if !Self::is_private(env.clone()) {
+            return true;
+        }
+        env.storage()
+            .persistent()
+            .has(&Self::get_allowed_bettor_key(&env, &user))
+    }
+
+    /// Helper: Storage key for a user's KYC approval flag
+    fn get_kyc_key(env: &Env, user: &Address) -> (Symbol, Address) {
+        (Symbol::new(env, KYC_PREFIX), user.clone())
+    }
+
+    /// Admin (market creator): require KYC approval before users can commit
+    pub fn set_require_kyc(env: Env, admin: Address, enabled: bool) {
+        Self::require_creator(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, REQUIRE_KYC_KEY), &enabled);
+    }
+
+    /// Admin (market creator): record a user's KYC attestation
+    pub fn set_kyc_status(env: Env, admin: Address, user: Address, approved: bool) {
+        Self::require_creator(&env, &admin);
+
+        let key = Self::get_kyc_key(&env, &user);
+        if approved {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Whether KYC mode is enabled for this market
+    pub fn is_kyc_required(env: Env) -> bool {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, REQUIRE_KYC_KEY))
+            .unwrap_or(false)
+    }
+
+    /// Whether a user holds KYC approval
+    pub fn get_kyc_status(env: Env, user: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&Self::get_kyc_key(&env, &user))
+    }
+
+    /// Get the full stored commitment for a user
+    ///
+    /// Returns the commit hash, committed amount, and commit timestamp so users can
+    /// verify their commitment before revealing. Returns None if the user has no
+    /// outstanding commitment (never committed, already revealed, or refunded).
+    pub fn get_commitment_details(env: Env, user: Address) -> Option<Commitment> {
+        let commit_key = Self::get_commit_key(&env, &user);
+        env.storage().persistent().get(&commit_key)
+    }
+
+    /// Helper: Get user commitment (alias of get_commitment_details)
+    pub fn get_commitment(env: Env, user: Address) -> Option<Commitment> {
+        Self::get_commitment_details(env, user)
+    }
+
+    /// Get the amount a user committed, so a UI can pre-fill the exact reveal amount
+    pub fn get_committed_amount(env: Env, user: Address) -> Option<i128> {
+        Self::get_commitment_details(env, user).map(|commitment| commitment.amount)
+    }
+
+    /// Creator: cap the number of unrevealed commitments outstanding at once (0 disables)
+    pub fn set_max_pending(env: Env, creator: Address, max_pending: u32) {
+        Self::require_creator(&env, &creator);
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, MAX_PENDING_KEY), &max_pending);
+    }
+
+    /// Get the cap on unrevealed commitments (0 = unlimited)
+    pub fn get_max_pending(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, MAX_PENDING_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Creator: weight winning shares by sqrt(amount) instead of amount to damp whales
+    ///
+    /// Part of market setup: the mode is locked once the first prediction is committed.
+    pub fn set_quadratic_weighting(env: Env, creator: Address, enabled: bool) {
+        Self::require_creator(&env, &creator);
+
+        let participants: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, PARTICIPANTS_KEY))
+            .unwrap_or_else(|| Vec::new(&env));
+        if !participants.is_empty() {
+            panic!("Weighting mode is locked once predictions exist");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, QUADRATIC_WEIGHTING_KEY), &enabled);
+    }
+
+    /// Whether winning shares are weighted quadratically (sqrt of stake)
+    pub fn is_quadratic_weighting(env: Env) -> bool {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, QUADRATIC_WEIGHTING_KEY))
+            .unwrap_or(false)
+    }
+
+    /// Creator: set the protocol fee withheld from winning payouts (at most 3000 = 30%)
+    pub fn set_fee_bps(env: Env, caller: Address, new_bps: u32) {
+        Self::require_creator(&env, &caller);
+        Self::require_fee_params_open(&env);
+
+        if new_bps > MAX_FEE_BPS {
+            panic!("Fee exceeds maximum");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, FEE_BPS_KEY), &new_bps);
+    }
+
+    /// Helper: Fee settings are frozen once the market closes, as bounties are paid against them
+    fn require_fee_params_open(env: &Env) {
+        let state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, MARKET_STATE_KEY))
+            .expect("Market state not found");
+        if state != STATE_OPEN {
+            panic!("Fee parameters are frozen once the market closes");
+        }
+    }
+
+    /// Get the protocol fee withheld from winning payouts, in basis points
+    pub fn get_fee_bps(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, FEE_BPS_KEY))
+            .unwrap_or(PROTOCOL_FEE_BPS as u32)
+    }
+
+    /// Creator: guarantee winners at least their stake back
+    ///
+    /// A winner netting below principal first has the fee waived down to their stake, then
+    /// any remaining shortfall is drawn from the insurance fund as far as it reaches.
+    pub fn set_principal_protection(env: Env, creator: Address, enabled: bool) {
+        Self::require_creator(&env, &creator);
+        Self::require_fee_params_open(&env);
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, PRINCIPAL_PROTECTION_KEY), &enabled);
+    }
+
+    /// Whether winners are guaranteed at least their stake back
+    pub fn is_principal_protected(env: Env) -> bool {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, PRINCIPAL_PROTECTION_KEY))
+            .unwrap_or(false)
+    }
+
+    /// Helper: (user_weight, total_winner_weight) to feed `compute_payout`
+    ///
+    /// Linear mode uses the stake against winner_shares; quadratic mode uses isqrt(stake)
+    /// against the total stored by `record_winner_weight` at resolution.
+    fn payout_weights(env: &Env, amount: i128, winner_shares: i128) -> (i128, i128) {
+        if !Self::is_quadratic_weighting(env.clone()) {
+            return (amount, winner_shares);
+        }
+
+        let total_weight: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, WINNER_WEIGHT_KEY))
+            .expect("Winner weight not found");
+        (isqrt(amount as u128) as i128, total_weight)
+    }
+
+    /// Helper: In quadratic mode, store the summed isqrt of every revealed winning stake
+    /// once the winning outcome is set, so claims need not rescan the participants
+    fn record_winner_weight(env: &Env) {
+        if !Self::is_quadratic_weighting(env.clone()) {
+            return;
+        }
+
+        let winning_outcome: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, WINNING_OUTCOME_KEY))
+            .expect("Winning outcome not found");
+        let revealed: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, REVEALED_PARTICIPANTS_KEY))
+            .unwrap_or_else(|| Vec::new(env));
+
+        let mut total_weight: i128 = 0;
+        for user in revealed.iter() {
+            if let Some(prediction) = env
+                .storage()
+                .persistent()
+                .get::<_, UserPrediction>(&Self::get_prediction_key(env, &user))
+            {
+                if winning_outcome == OUTCOME_TIE || prediction.outcome == winning_outcome {
+                    total_weight += isqrt(prediction.amount as u128) as i128;
+                }
+            }
+        }
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(env, WINNER_WEIGHT_KEY), &total_weight);
+    }
+
+    /// Creator: round the amount in CommitmentMadeEvent down to a multiple of `bucket`
+    ///
+    /// Hides the exact stake from commit event watchers until reveal; the stored commitment
+    /// keeps the exact amount. The escrow token transfer itself is still visible. 0 emits the
+    /// exact amount.
+    pub fn set_commit_amount_bucket(env: Env, creator: Address, bucket: i128) {
+        Self::require_creator(&env, &creator);
+
+        if bucket < 0 {
+            panic!("Commit amount bucket cannot be negative");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, COMMIT_AMOUNT_BUCKET_KEY), &bucket);
+    }
+
+    /// Get the bucket size commit events round amounts to (0 = exact amounts)
+    pub fn get_commit_amount_bucket(env: Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, COMMIT_AMOUNT_BUCKET_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Creator: set the reveal bond charged on top of each new commitment (0 disables)
+    pub fn set_reveal_bond(env: Env, creator: Address, bond: i128) {
+        Self::require_creator(&env, &creator);
+
+        if bond < 0 {
+            panic!("Reveal bond cannot be negative");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, REVEAL_BOND_KEY), &bond);
+    }
+
+    /// Get the reveal bond charged on new commitments
+    pub fn get_reveal_bond(env: Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, REVEAL_BOND_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Creator: set how many ledgers must pass between a commit and its reveal (minimum 1)
+    pub fn set_min_reveal_delay(env: Env, creator: Address, ledgers: u32) {
+        Self::require_creator(&env, &creator);
+
+        if ledgers == 0 {
+            panic!("Reveal delay must be at least one ledger");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, MIN_REVEAL_DELAY_KEY), &ledgers);
+    }
+
+    /// Get the minimum number of ledgers between a commit and its reveal
+    pub fn get_min_reveal_delay(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, MIN_REVEAL_DELAY_KEY))
+            .unwrap_or(1)
+    }
+
+    /// Creator: require at least `ratio_bps` of commits to be revealed before resolution (0 disables)
+    pub fn set_min_reveal_ratio(env: Env, creator: Address, ratio_bps: u32) {
+        Self::require_creator(&env, &creator);
+
+        if ratio_bps > 10000 {
+            panic!("Reveal ratio cannot exceed 10000 bps");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, MIN_REVEAL_RATIO_KEY), &ratio_bps);
+    }
+
+    /// Get the minimum revealed/committed ratio (basis points) required to resolve
+    pub fn get_min_reveal_ratio(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, MIN_REVEAL_RATIO_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Creator: allow the market to be resolved as a tie, refunding both sides minus fee
+    pub fn set_split_on_tie(env: Env, creator: Address, enabled: bool) {
+        Self::require_creator(&env, &creator);
+
+        let state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market state not found");
+        if state != STATE_OPEN {
+            panic!("Tie mode can only be changed while market is open");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, SPLIT_ON_TIE_KEY), &enabled);
+    }
+
+    /// Whether the market may be resolved as a tie
+    pub fn is_split_on_tie(env: Env) -> bool {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, SPLIT_ON_TIE_KEY))
+            .unwrap_or(false)
+    }
+
+    /// Get `(revealed, committed)` counts, where `committed` excludes withdrawn commits
+    pub fn get_reveal_counts(env: Env) -> (u32, u32) {
+        let revealed = env
+            .storage()
+            .persistent()
+            .get::<_, Vec<Address>>(&Symbol::new(&env, REVEALED_PARTICIPANTS_KEY))
+            .map(|v| v.len())
+            .unwrap_or(0);
+        let committed: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, COMMIT_COUNT_KEY))
+            .unwrap_or(0);
+
+        (revealed, committed)
+    }
+
+    /// Helper: Whether enough commits were revealed to satisfy the minimum reveal ratio
+    fn meets_reveal_ratio(env: &Env) -> bool {
+        let ratio_bps = Self::get_min_reveal_ratio(env.clone());
+        let (revealed, committed) = Self::get_reveal_counts(env.clone());
+        committed == 0 || (revealed as u64) * 10000 >= (committed as u64) * (ratio_bps as u64)
+    }
+
+    /// Creator: waive the claim fee until `timestamp` (promotional window; 0 disables)
+    pub fn set_fee_free_until(env: Env, creator: Address, timestamp: u64) {
+        Self::require_creator(&env, &creator);
+        Self::require_fee_params_open(&env);
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, FEE_FREE_UNTIL_KEY), &timestamp);
+    }
+
+    /// Get the end of the fee-free promo window (claims before it pay no fee)
+    pub fn get_fee_free_until(env: Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, FEE_FREE_UNTIL_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Creator: set the bounty paid to whoever triggers close and resolution (0 disables)
+    ///
+    /// Each bounty is an advance on the protocol fee the market will withhold, so payouts
+    /// are capped at the fee every claim is guaranteed to pay: the fee at the deepest
+    /// governance discount, and nothing during a fee holiday or under principal protection.
+    pub fn set_resolution_bounty(env: Env, creator: Address, bounty: i128) {
+        Self::require_creator(&env, &creator);
+
+        if bounty < 0 {
+            panic!("Resolution bounty cannot be negative");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, RESOLUTION_BOUNTY_KEY), &bounty);
+    }
+
+    /// Get the bounty paid for triggering close and resolution
+    pub fn get_resolution_bounty(env: Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, RESOLUTION_BOUNTY_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Whether the bounty for `action` (`close` or `resolve`) has already been paid
+    pub fn is_bounty_paid(env: Env, action: Symbol) -> bool {
+        env.storage()
+            .persistent()
+            .has(&(Symbol::new(&env, BOUNTY_PAID_PREFIX), action))
+    }
+
+    /// Get the total paid out in close/resolution bounties
+    pub fn get_bounties_paid(env: Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, BOUNTIES_PAID_TOTAL_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Helper: Pay the close/resolution bounty to `caller` the first time `action` succeeds
+    fn pay_resolution_bounty(env: &Env, caller: &Address, market_id: &BytesN<32>, action: &str) {
+        let action = Symbol::new(env, action);
+        if Self::is_bounty_paid(env.clone(), action.clone()) {
+            return;
+        }
+
+        let bounty = Self::get_resolution_bounty(env.clone());
+        if bounty == 0 {
+            return;
+        }
+
+        // Funded from the least fee the market will withhold on its pools; the fee settings
+        // are frozen from close, so claims cannot withhold less than this
+        let fee_bps = if env.ledger().timestamp() < Self::get_fee_free_until(env.clone())
+            || Self::is_principal_protected(env.clone())
+        {
+            0
+        } else {
+            let max_discount_bps = Self::get_fee_discount(env.clone())
+                .map(|(_, tiers)| {
+                    tiers
+                        .iter()
+                        .map(|tier| tier.discount_bps)
+                        .max()
+                        .unwrap_or(0)
+                })
+                .unwrap_or(0) as i128;
+            Self::get_fee_bps(env.clone()) as i128 * (10000 - max_discount_bps) / 10000
+        };
+        let yes_pool: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, YES_POOL_KEY))
+            .unwrap_or(0);
+        let no_pool: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, NO_POOL_KEY))
+            .unwrap_or(0);
+        let paid_total = Self::get_bounties_paid(env.clone());
+        let fee_budget = (yes_pool + no_pool) * fee_bps / 10000 - paid_total;
+        let amount = bounty.min(fee_budget);
+        if amount <= 0 {
+            return;
+        }
+
+        env.storage().persistent().set(
+            &(Symbol::new(env, BOUNTY_PAID_PREFIX), action.clone()),
+            &true,
+        );
+        env.storage().persistent().set(
+            &Symbol::new(env, BOUNTIES_PAID_TOTAL_KEY),
+            &(paid_total + amount),
+        );
+
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, USDC_KEY))
+            .expect("USDC token not set");
+        token::TokenClient::new(env, &usdc_token).transfer(
+            &env.current_contract_address(),
+            caller,
+            &amount,
+        );
+
+        ResolutionBountyPaidEvent {
+            market_id: market_id.clone(),
+            caller: caller.clone(),
+            action,
+            amount,
+        }
+        .publish(env);
+    }
+
+    /// Creator: discount the claim fee for governance token holders (None disables)
+    ///
+    /// Tiers must be sorted by ascending `min_balance`; a claimant gets the discount of
+    /// the highest tier their balance reaches.
+    pub fn set_fee_discount(
+        env: Env,
+        creator: Address,
+        gov_token: Option<Address>,
+        tiers: Vec<FeeDiscountTier>,
+    ) {
+        Self::require_creator(&env, &creator);
+        Self::require_fee_params_open(&env);
+
+        let Some(gov_token) = gov_token else {
+            env.storage()
+                .persistent()
+                .remove(&Symbol::new(&env, GOV_TOKEN_KEY));
+            env.storage()
+                .persistent()
+                .remove(&Symbol::new(&env, FEE_DISCOUNT_TIERS_KEY));
+            return;
+        };
+
+        let mut previous_min: Option<i128> = None;
+        for tier in tiers.iter() {
+            if tier.discount_bps > 10000 {
+                panic!("Fee discount cannot exceed 10000 bps");
+            }
+            if previous_min.is_some_and(|min| tier.min_balance <= min) {
+                panic!("Fee discount tiers must be sorted by min_balance");
+            }
+            previous_min = Some(tier.min_balance);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, GOV_TOKEN_KEY), &gov_token);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, FEE_DISCOUNT_TIERS_KEY), &tiers);
+    }
+
+    /// Get the governance token and discount tiers, if a fee discount is configured
+    pub fn get_fee_discount(env: Env) -> Option<(Address, Vec<FeeDiscountTier>)> {
+        let gov_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, GOV_TOKEN_KEY))?;
+        let tiers: Vec<FeeDiscountTier> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, FEE_DISCOUNT_TIERS_KEY))
+            .unwrap_or_else(|| Vec::new(&env));
+        Some((gov_token, tiers))
+    }
+
+    /// Get the fee discount `user` currently qualifies for, in basis points
+    ///
+    /// A governance token whose balance lookup fails grants no discount rather than
+    /// blocking the claim.
+    pub fn get_fee_discount_bps(env: Env, user: Address) -> u32 {
+        let Some((gov_token, tiers)) = Self::get_fee_discount(env.clone()) else {
+            return 0;
+        };
+
+        let Ok(Ok(balance)) = token::TokenClient::new(&env, &gov_token).try_balance(&user) else {
+            return 0;
+        };
+        let mut discount_bps = 0;
+        for tier in tiers.iter() {
+            if balance >= tier.min_balance {
+                discount_bps = tier.discount_bps;
+            }
+        }
+        discount_bps
+    }
+
+    /// Forfeit the reveal bond of a commitment that was never revealed
+    ///
+    /// Callable by anyone once the market has passed its closing time. The bond is
+    /// deposited into the Treasury (resolved through the Factory); the committed
+    /// amount itself is untouched.
+    pub fn forfeit_reveal_bond(env: Env, user: Address) -> i128 {
+        let closing_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CLOSING_TIME_KEY))
+            .expect("Market not initialized");
+
+        if env.ledger().timestamp() < closing_time {
+            panic!("Reveal window still open");
+        }
+
+        let commit_key = Self::get_commit_key(&env, &user);
+        let mut commitment: Commitment = env
+            .storage()
+            .persistent()
+            .get(&commit_key)
+            .expect("No unrevealed commitment for user");
+
+        let bond = commitment.bond;
+        if bond <= 0 {
+            panic!("No reveal bond to forfeit");
+        }
+
+        commitment.bond = 0;
+        env.storage().persistent().set(&commit_key, &commitment);
+
+        let treasury = Self::route_to_treasury(&env, bond);
+
+        let market_id: BytesN<32> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_ID_KEY))
+            .expect("Market not initialized");
+
+        RevealBondForfeitedEvent {
+            user,
+            market_id,
+            bond,
+            treasury: treasury.clone(),
+        }
+        .publish(&env);
+
+        bond
+    }
+
+    /// Helper: Deposit `amount` from market escrow into the Treasury registered on the Factory
+    fn route_to_treasury(env: &Env, amount: i128) -> Address {
+        let factory: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, FACTORY_KEY))
+            .expect("Factory address not set");
+
+        // Cross-contract calls by address, as the Factory/Treasury modules are built separately
+        let treasury: Address =
+            env.invoke_contract(&factory, &Symbol::new(env, "get_treasury"), Vec::new(env));
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, USDC_KEY))
+            .expect("USDC token not set");
+
+        // The treasury pulls the funds, so authorize its nested transfer from this contract
+        env.authorize_as_current_contract(vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: usdc_token,
+                    fn_name: Symbol::new(env, "transfer"),
+                    args: (env.current_contract_address(), treasury.clone(), amount).into_val(env),
+                },
+                sub_invocations: Vec::new(env),
+            }),
+        ]);
+        env.invoke_contract::<()>(
+            &treasury,
+            &Symbol::new(env, "deposit_fees"),
+            (env.current_contract_address(), amount).into_val(env),
+        );
+
+        treasury
+    }
+
+    /// Helper: Release the escrowed creator bond, returning it to the creator or slashing it
+    fn settle_creator_bond(env: &Env, market_id: &BytesN<32>, slash: bool) {
+        let bond: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, CREATOR_BOND_KEY))
+            .unwrap_or(0);
+        if bond == 0 {
+            return;
+        }
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(env, CREATOR_BOND_KEY), &0i128);
+
+        let creator: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, CREATOR_KEY))
+            .expect("Market not initialized");
+
+        if slash {
+            Self::route_to_treasury(env, bond);
+        } else {
+            let usdc_token: Address = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(env, USDC_KEY))
+                .expect("USDC token not set");
+            token::TokenClient::new(env, &usdc_token).transfer(
+                &env.current_contract_address(),
+                &creator,
+                &bond,
+            );
+        }
+
+        CreatorBondSettledEvent {
+            market_id: market_id.clone(),
+            creator,
+            bond,
+            slashed: slash,
+        }
+        .publish(env);
+    }
+
+    /// Get the creator bond currently held in escrow
+    pub fn get_creator_bond(env: Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, CREATOR_BOND_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Factory: add or remove a keeper allowed to trigger close and resolution
+    pub fn set_keeper(env: Env, keeper: Address, enabled: bool) {
+        let factory: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, FACTORY_KEY))
+            .expect("Factory address not set");
+        factory.require_auth();
+
+        let keeper_key = (Symbol::new(&env, KEEPER_PREFIX), keeper);
+        if enabled {
+            env.storage().persistent().set(&keeper_key, &true);
+        } else {
+            env.storage().persistent().remove(&keeper_key);
+        }
+    }
+
+    /// Whether `keeper` may trigger close and resolution
+    pub fn is_keeper(env: Env, keeper: Address) -> bool {
+        let keeper_key = (Symbol::new(&env, KEEPER_PREFIX), keeper);
+        env.storage().persistent().has(&keeper_key)
+    }
+
+    /// Helper: Require `caller` to be a registered keeper or the market's oracle
+    fn require_keeper_or_oracle(env: &Env, caller: &Address) {
+        caller.require_auth();
+
+        let oracle: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, ORACLE_KEY))
+            .expect("Oracle address not found");
+        if *caller != oracle && !Self::is_keeper(env.clone(), caller.clone()) {
+            panic!("Unauthorized: only keeper or oracle");
+        }
+    }
+
+    /// Factory: enroll this market in per-user active market tracking
+    pub fn enable_position_tracking(env: Env) {
+        let factory: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, FACTORY_KEY))
+            .expect("Factory address not set");
+        factory.require_auth();
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, POSITION_TRACKING_KEY), &true);
+    }
+
+    /// Get the token predictions are escrowed and paid out in
+    pub fn get_settlement_token(env: Env) -> Address {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC token not found")
+    }
+
+    /// Get the settlement token balance currently held in the market's escrow
+    pub fn get_escrow_balance(env: Env) -> i128 {
+        let usdc_token = Self::get_settlement_token(env.clone());
+        token::TokenClient::new(&env, &usdc_token).balance(&env.current_contract_address())
+    }
+
+    /// Get the total the market still owes out of escrow
+    ///
+    /// Sums unrefunded commitments and stakes (with their reveal bonds), replaced by
+    /// unclaimed winning payouts once resolved (every stake on a tie), plus held dispute
+    /// stakes and the creator bond.
+    pub fn get_liabilities(env: Env) -> i128 {
+        let storage = env.storage().persistent();
+        let state: u32 = storage
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .unwrap_or(STATE_OPEN);
+        let winning_outcome: Option<u32> = storage.get(&Symbol::new(&env, WINNING_OUTCOME_KEY));
+        let winner_shares: i128 = storage
+            .get(&Symbol::new(&env, WINNER_SHARES_KEY))
+            .unwrap_or(0);
+        let loser_shares: i128 = storage
+            .get(&Symbol::new(&env, LOSER_SHARES_KEY))
+            .unwrap_or(0);
+
+        let participants: Vec<Address> = storage
+            .get(&Symbol::new(&env, PARTICIPANTS_KEY))
+            .unwrap_or_else(|| Vec::new(&env));
+
+        let mut owed: i128 = 0;
+        for user in participants.iter() {
+            let prediction: Option<UserPrediction> =
+                storage.get(&Self::get_prediction_key(&env, &user));
+            match (state, winning_outcome, prediction) {
+                (STATE_RESOLVED, Some(outcome), Some(prediction)) => {
+                    let won = outcome == OUTCOME_TIE || prediction.outcome == outcome;
+                    if !prediction.claimed && won && winner_shares > 0 {
+                        let (weight, total_weight) =
+                            Self::payout_weights(&env, prediction.amount, winner_shares);
+                        let (gross, _) =
+                            compute_payout(weight, total_weight, winner_shares + loser_shares, 0);
+                        owed += gross;
+                    }
+                }
+                _ => owed += Self::get_refundable_amount(&env, &user).unwrap_or(0),
+            }
+        }
+
+        let dispute_stakes: i128 = storage
+            .get(&Symbol::new(&env, DISPUTE_STAKE_HELD_KEY))
+            .unwrap_or(0);
+        owed + dispute_stakes + Self::get_creator_bond(env.clone())
+    }
+
+    /// Helper: Get pending commit count
+    pub fn get_pending_count(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, PENDING_COUNT_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Helper: Get market state
+    pub fn get_market_state_value(env: Env) -> Option<u32> {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+    }
+
+    /// Check whether `reveal_prediction` would accept these parameters, without changing state
+    ///
+    /// Reconstructs the commitment hash and compares it (and the amount) against the user's
+    /// stored commitment. Returns false for an invalid outcome, a missing commitment, or any
+    /// mismatch instead of erroring. Timing rules (closing time, reveal delay) are not checked.
+    pub fn verify_reveal(
+        env: Env,
+        user: Address,
+        market_id: BytesN<32>,
+        outcome: u32,
+        amount: i128,
+        salt: BytesN<32>,
+    ) -> bool {
+        if outcome > 1 {
+            return false;
+        }
+
+        let commitment: Option<Commitment> = env
+            .storage()
+            .persistent()
+            .get(&Self::get_commit_key(&env, &user));
+
+        match commitment {
+            Some(commitment) => {
+                commitment.amount == amount
+                    && Self::reveal_hash(&env, &market_id, &user, outcome, &salt)
+                        == commitment.commit_hash
+            }
+            None => false,
+        }
+    }
+
+    /// Commitment hash for a reveal: sha256(market_id || user || outcome_be_bytes || salt)
+    ///
+    /// `user` is the committer's XDR-encoded address, so identical outcome/salt choices by
+    /// different users never produce the same hash.
+    fn reveal_hash(
+        env: &Env,
+        market_id: &BytesN<32>,
+        user: &Address,
+        outcome: u32,
+        salt: &BytesN<32>,
+    ) -> BytesN<32> {
+        let mut preimage = soroban_sdk::Bytes::new(env);
+        preimage.extend_from_array(&market_id.to_array());
+        preimage.append(&user.clone().to_xdr(env));
+        preimage.extend_from_array(&outcome.to_be_bytes());
+        preimage.extend_from_array(&salt.to_array());
+
+        BytesN::from_array(env, &env.crypto().sha256(&preimage).to_array())
+    }
+
+    /// Phase 2: User reveals their committed prediction
+    ///
+    /// Verifies the commitment hash matches sha256(market_id || user || outcome || salt),
+    /// transitions prediction from COMMITTED → REVEALED, updates pools,
+    /// and emits a PredictionRevealed event.
+    ///
+    /// # Errors
+    /// - `NotInitialized` - Market not initialized
+    /// - `InvalidMarketState` - Market not in OPEN state
+    /// - `MarketClosed` - Current time >= closing time
+    /// - `NoPrediction` - No commitment found for this user
+    /// - `DuplicateReveal` - User already revealed (prediction record exists)
+    /// - `InvalidReveal` - Outcome is not 0/1, or reconstructed hash doesn't match stored commit hash
+    /// - `AmountMismatch` - Revealed amount doesn't match committed amount
+    /// - `RevealTooEarly` - Fewer than the minimum reveal delay ledgers since the commit
+    pub fn reveal_prediction(
+        env: Env,
+        user: Address,
+        market_id: BytesN<32>,
+        outcome: u32,
+        amount: i128,
+        salt: BytesN<32>,
+    ) -> Result<(), MarketError> {
+        // 1. Require user authentication
+        user.require_auth();
+
+        // 1b. Markets are binary: anything but NO (0) / YES (1) would be miscounted as NO
+        if outcome > 1 {
+            return Err(MarketError::InvalidReveal);
+        }
+
+        // 2. Validate market is initialized and in OPEN state
+        let market_state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .ok_or(MarketError::NotInitialized)?;
+
+        if market_state != STATE_OPEN {
+            return Err(MarketError::InvalidMarketState);
+        }
+
+        // 3. Validate current timestamp < closing_time
+        let closing_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CLOSING_TIME_KEY))
+            .ok_or(MarketError::NotInitialized)?;
+
+        let current_time = env.ledger().timestamp();
+        if current_time >= closing_time {
+            return Err(MarketError::MarketClosed);
+        }
+
+        // 4. Check for duplicate reveal (prediction record already exists)
+        let prediction_key = Self::get_prediction_key(&env, &user);
+        if env.storage().persistent().has(&prediction_key) {
+            return Err(MarketError::DuplicateReveal);
+        }
+
+        // 5. Validate user has a prior commitment
+        let commit_key = Self::get_commit_key(&env, &user);
+        let commitment: Commitment = env
+            .storage()
+            .persistent()
+            .get(&commit_key)
+            .ok_or(MarketError::NoPrediction)?;
+
+        // 6. Validate the revealed amount matches the committed amount
+        if amount != commitment.amount {
+            return Err(MarketError::AmountMismatch);
+        }
+
+        // 6b. Reject reveals that land too soon after the commit (never the same ledger)
+        let min_delay = Self::get_min_reveal_delay(env.clone());
+        if env.ledger().sequence() < commitment.ledger_sequence.saturating_add(min_delay) {
+            return Err(MarketError::RevealTooEarly);
+        }
+
+        // 7-8. Reconstruct the commitment hash from revealed data and compare to the stored one
+        if Self::reveal_hash(&env, &market_id, &user, outcome, &salt) != commitment.commit_hash {
             return Err(MarketError::InvalidReveal);
         }
 
@@ -561,7 +2175,15 @@ impl PredictionMarket {
             .persistent()
             .set(&Symbol::new(&env, REVEALED_PARTICIPANTS_KEY), &revealed);
 
-        // 10. Update prediction pools
+        // 10. Update prediction pools and per-outcome participant counts
+        let count_key = if outcome == 1 {
+            Symbol::new(&env, YES_COUNT_KEY)
+        } else {
+            Symbol::new(&env, NO_COUNT_KEY)
+        };
+        let count: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+        env.storage().persistent().set(&count_key, &(count + 1));
+
         if outcome == 1 {
             // YES outcome
             let yes_pool: i128 = env
@@ -583,6 +2205,7 @@ impl PredictionMarket {
                 .persistent()
                 .set(&Symbol::new(&env, NO_POOL_KEY), &(no_pool + amount));
         }
+        Self::publish_pool_update(&env, &market_id);
 
         // 11. Update total volume
         let total_volume: i128 = env
@@ -613,6 +2236,20 @@ impl PredictionMarket {
         // 13. Remove commitment record (prevents re-reveal)
         env.storage().persistent().remove(&commit_key);
 
+        // 13b. Return the reveal bond
+        if commitment.bond > 0 {
+            let usdc_token: Address = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, USDC_KEY))
+                .ok_or(MarketError::NotInitialized)?;
+            token::TokenClient::new(&env, &usdc_token).transfer(
+                &env.current_contract_address(),
+                &user,
+                &commitment.bond,
+            );
+        }
+
         // 14. Emit PredictionRevealed event with anonymized data
         PredictionRevealedEvent {
             user,
@@ -627,7 +2264,11 @@ impl PredictionMarket {
     }
 
     /// Close market for new predictions (auto-trigger at closing_time)
-    pub fn close_market(env: Env, market_id: BytesN<32>) {
+    ///
+    /// Triggered by a keeper or the oracle.
+    pub fn close_market(env: Env, caller: Address, market_id: BytesN<32>) {
+        Self::require_keeper_or_oracle(&env, &caller);
+
         // Get current timestamp
         let current_time = env.ledger().timestamp();
 
@@ -656,9 +2297,13 @@ impl PredictionMarket {
         }
 
         // Change market state to CLOSED
-        env.storage()
-            .persistent()
-            .set(&Symbol::new(&env, MARKET_STATE_KEY), &STATE_CLOSED);
+        Self::set_market_state(&env, STATE_CLOSED);
+
+        if Self::is_amm_settled_on_close(env.clone()) {
+            Self::settle_amm_to_pool_odds(&env, &market_id);
+        }
+
+        Self::pay_resolution_bounty(&env, &caller, &market_id, "close");
 
         // Emit MarketClosed Event
         MarketClosedEvent {
@@ -668,68 +2313,297 @@ impl PredictionMarket {
         .publish(&env);
     }
 
-    /// Resolve market based on oracle consensus result
-    ///
-    /// This function finalizes the market outcome based on oracle consensus.
-    /// It validates timing, checks oracle consensus, updates market state,
-    /// calculates winner/loser pools, and emits resolution event.
+    /// Re-open a mistakenly closed market (creator or keeper)
     ///
-    /// # Panics
-    /// * If current time < resolution_time
-    /// * If market state is not CLOSED
-    /// * If oracle consensus has not been reached
-    /// * If market is already RESOLVED
-    pub fn resolve_market(env: Env, market_id: BytesN<32>) {
-        // Get current timestamp
+    /// Moves CLOSED back to OPEN within `REOPEN_GRACE_PERIOD` after `closing_time`, and
+    /// moves `closing_time` to `new_closing_time` so commits and reveals are accepted
+    /// again. The new closing time must be in the future and before resolution.
+    pub fn reopen_market(env: Env, admin: Address, market_id: BytesN<32>, new_closing_time: u64) {
+        admin.require_auth();
+
+        let creator: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CREATOR_KEY))
+            .expect("Market not initialized");
+        if admin != creator && !Self::is_keeper(env.clone(), admin.clone()) {
+            panic!("Unauthorized: only creator or keeper");
+        }
+
+        let current_state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market state not found");
+        if current_state != STATE_CLOSED {
+            panic!("Market not in CLOSED state");
+        }
+
+        let closing_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CLOSING_TIME_KEY))
+            .expect("Closing time not found");
         let current_time = env.ledger().timestamp();
+        if current_time > closing_time.saturating_add(REOPEN_GRACE_PERIOD) {
+            panic!("Reopen grace period has passed");
+        }
 
-        // Load resolution time from storage
         let resolution_time: u64 = env
             .storage()
             .persistent()
             .get(&Symbol::new(&env, RESOLUTION_TIME_KEY))
             .expect("Resolution time not found");
+        if new_closing_time <= current_time || new_closing_time >= resolution_time {
+            panic!("New closing time must be in the future and before resolution");
+        }
 
-        // Validate: current timestamp >= resolution_time
-        if current_time < resolution_time {
-            panic!("Cannot resolve market before resolution time");
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, CLOSING_TIME_KEY), &new_closing_time);
+        Self::set_market_state(&env, STATE_OPEN);
+
+        MarketReopenedEvent {
+            market_id,
+            closing_time: new_closing_time,
+            timestamp: current_time,
         }
+        .publish(&env);
+    }
 
-        // Load current market state
-        let current_state: u32 = env
+    /// Creator: snap the linked AMM pool's price to the pari-mutuel odds when the market closes
+    ///
+    /// The AMM must have this contract set as the pool's market (`set_pool_market`).
+    pub fn configure_amm_close_settlement(env: Env, creator: Address, amm: Address, enabled: bool) {
+        Self::require_creator(&env, &creator);
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, AMM_KEY), &amm);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, SETTLE_AMM_ON_CLOSE_KEY), &enabled);
+    }
+
+    /// Whether closing the market settles the linked AMM pool to the pari-mutuel odds
+    pub fn is_amm_settled_on_close(env: Env) -> bool {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, SETTLE_AMM_ON_CLOSE_KEY))
+            .unwrap_or(false)
+    }
+
+    /// Helper: Rebalance the AMM pool to quote the YES share of the prediction pools
+    ///
+    /// Skipped when nothing was staked or the AMM has no pool for this market. One-sided
+    /// pools are clamped to 1/9999 bps, the closest a CPMM can get. Best effort, so a
+    /// failing AMM never blocks the close.
+    fn settle_amm_to_pool_odds(env: &Env, market_id: &BytesN<32>) {
+        let yes_pool: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, YES_POOL_KEY))
+            .unwrap_or(0);
+        let no_pool: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, NO_POOL_KEY))
+            .unwrap_or(0);
+        let total = yes_pool + no_pool;
+        if total <= 0 {
+            return;
+        }
+
+        let amm: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, AMM_KEY))
+            .expect("AMM not configured");
+        let Ok(Ok(pool_state)) = env
+            .try_invoke_contract::<(u128, u128, u128, u32, u32), soroban_sdk::Error>(
+                &amm,
+                &Symbol::new(env, "get_pool_state"),
+                (market_id.clone(),).into_val(env),
+            )
+        else {
+            return;
+        };
+        if pool_state.0 == 0 || pool_state.1 == 0 {
+            return;
+        }
+
+        let yes_odds = ((yes_pool * 10000 / total) as u32).clamp(1, 9999);
+        let _ = env.try_invoke_contract::<(u128, u128), soroban_sdk::Error>(
+            &amm,
+            &Symbol::new(env, "settle_pool_to_odds"),
+            (env.current_contract_address(), market_id.clone(), yes_odds).into_val(env),
+        );
+    }
+
+    /// Creator: choose the AMM and the share of prediction pools (bps) seeded into it at close
+    pub fn configure_amm_seeding(env: Env, creator: Address, amm: Address, seed_bps: u32) {
+        Self::require_creator(&env, &creator);
+
+        if seed_bps == 0 || seed_bps > 10000 {
+            panic!("Seed fraction must be between 1 and 10000 bps");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, AMM_KEY), &amm);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, AMM_SEED_BPS_KEY), &seed_bps);
+    }
+
+    /// Seed the configured AMM with a fraction of the closed market's prediction pools
+    ///
+    /// The seed is sized from the pools but paid by the creator, who becomes the liquidity
+    /// provider of the AMM pool for `market_id` (created if missing). Bettor principal and
+    /// the escrowed creator bond stay in the market, so winners remain fully covered and
+    /// the bond can still be slashed. Can only run once, while the market is CLOSED.
+    ///
+    /// # Returns
+    /// The USDC amount moved into the AMM
+    pub fn seed_amm_from_pools(env: Env, creator: Address, market_id: BytesN<32>) -> i128 {
+        Self::require_creator(&env, &creator);
+
+        let state: u32 = env
             .storage()
             .persistent()
             .get(&Symbol::new(&env, MARKET_STATE_KEY))
             .expect("Market state not found");
+        if state != STATE_CLOSED {
+            panic!("Market must be CLOSED to seed the AMM");
+        }
+        if env
+            .storage()
+            .persistent()
+            .has(&Symbol::new(&env, AMM_SEEDED_KEY))
+        {
+            panic!("AMM already seeded");
+        }
 
-        // Validate: market state is CLOSED (not OPEN or already RESOLVED)
-        if current_state == STATE_OPEN {
-            panic!("Cannot resolve market that is still OPEN");
+        let amm: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, AMM_KEY))
+            .expect("AMM seeding not configured");
+        let seed_bps: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, AMM_SEED_BPS_KEY))
+            .expect("AMM seeding not configured");
+
+        let yes_pool: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, YES_POOL_KEY))
+            .unwrap_or(0);
+        let no_pool: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, NO_POOL_KEY))
+            .unwrap_or(0);
+        let amount = (yes_pool + no_pool) * seed_bps as i128 / 10000;
+        if amount <= 0 {
+            panic!("No pool liquidity to seed");
         }
 
-        if current_state == STATE_RESOLVED {
-            panic!("Market already resolved");
+        // The AMM pulls the seed straight from the creator
+        let pool_state: (u128, u128, u128, u32, u32) = env.invoke_contract(
+            &amm,
+            &Symbol::new(&env, "get_pool_state"),
+            (market_id.clone(),).into_val(&env),
+        );
+        if pool_state.0 == 0 && pool_state.1 == 0 {
+            env.invoke_contract::<()>(
+                &amm,
+                &Symbol::new(&env, "create_pool"),
+                (creator.clone(), market_id.clone(), amount as u128).into_val(&env),
+            );
+        } else {
+            env.invoke_contract::<u128>(
+                &amm,
+                &Symbol::new(&env, "add_liquidity"),
+                (creator, market_id.clone(), amount as u128).into_val(&env),
+            );
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, AMM_SEEDED_KEY), &amount);
+
+        AmmSeededEvent {
+            market_id,
+            amm,
+            amount,
         }
+        .publish(&env);
+
+        amount
+    }
+
+    /// Resolve market based on oracle consensus result
+    ///
+    /// This function finalizes the market outcome based on oracle consensus.
+    /// It validates timing, queries the oracle's `check_consensus`, updates market state,
+    /// calculates winner/loser pools, and emits resolution event.
+    ///
+    /// # Panics
+    /// * If current time < resolution_time
+    /// * If market state is not CLOSED
+    /// * If oracle consensus has not been reached
+    /// * If market is already RESOLVED
+    pub fn resolve_market(env: Env, caller: Address, market_id: BytesN<32>) {
+        Self::require_keeper_or_oracle(&env, &caller);
+
+        let current_time = Self::require_resolvable(&env);
 
         // Load oracle address
-        let _oracle_address: Address = env
+        let oracle_address: Address = env
             .storage()
             .persistent()
             .get(&Symbol::new(&env, ORACLE_KEY))
             .expect("Oracle address not found");
 
-        // TODO: Cross-contract call to Oracle - requires Oracle contract to be deployed
-        // For now, using placeholder values since Oracle contract is built separately
-        // Uncomment when Oracle is deployed and address is available
-        // let oracle_client = crate::oracle::OracleManagerClient::new(&env, &oracle_address);
-        // let (consensus_reached, final_outcome) = oracle_client.check_consensus(&market_id);
-        // if !consensus_reached {
-        //     panic!("Oracle consensus not reached");
-        // }
+        let (consensus_reached, final_outcome): (bool, u32) = env.invoke_contract(
+            &oracle_address,
+            &Symbol::new(&env, "check_consensus"),
+            (market_id.clone(),).into_val(&env),
+        );
+        if !consensus_reached {
+            panic!("Oracle consensus not reached");
+        }
+
+        Self::apply_resolution(&env, &caller, market_id, final_outcome, current_time);
+    }
+
+    /// Oracle: resolve with the outcome it has just finalized
+    ///
+    /// Push counterpart of `resolve_market` for the oracle's `finalize_resolution`, which
+    /// cannot be called back for consensus while it is still executing.
+    pub fn resolve_with_oracle_outcome(env: Env, market_id: BytesN<32>, final_outcome: u32) {
+        let oracle: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_KEY))
+            .expect("Oracle address not found");
+        oracle.require_auth();
 
-        // TEMPORARY: Simulate oracle consensus for testing (outcome = 1 for YES)
-        let _consensus_reached = true;
-        let final_outcome = 1u32;
+        let current_time = Self::require_resolvable(&env);
+        Self::apply_resolution(&env, &oracle, market_id, final_outcome, current_time);
+    }
+
+    /// Helper: Record `final_outcome`, split the pools and settle bond and bounty
+    fn apply_resolution(
+        env: &Env,
+        caller: &Address,
+        market_id: BytesN<32>,
+        final_outcome: u32,
+        current_time: u64,
+    ) {
+        let env = env.clone();
 
         // Validate outcome is binary (0 or 1)
         if final_outcome > 1 {
@@ -740,6 +2614,7 @@ impl PredictionMarket {
         env.storage()
             .persistent()
             .set(&Symbol::new(&env, WINNING_OUTCOME_KEY), &final_outcome);
+        Self::record_winner_weight(&env);
 
         // Load pool sizes
         let yes_pool: i128 = env
@@ -763,36 +2638,201 @@ impl PredictionMarket {
             (no_pool, yes_pool)
         };
 
-        // Store winner and loser shares for payout calculations
-        env.storage()
+        // Store winner and loser shares for payout calculations
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, WINNER_SHARES_KEY), &winner_shares);
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, LOSER_SHARES_KEY), &loser_shares);
+
+        // Update market state to RESOLVED
+        Self::set_market_state(&env, STATE_RESOLVED);
+
+        // Clean resolution: return the creator bond
+        Self::settle_creator_bond(&env, &market_id, false);
+
+        Self::pay_resolution_bounty(&env, caller, &market_id, "resolve");
+
+        // Emit MarketResolved event
+        MarketResolvedEvent {
+            market_id,
+            final_outcome,
+            timestamp: current_time,
+        }
+        .publish(&env);
+    }
+
+    /// Keeper or oracle: resolve an undecidable market as a tie
+    ///
+    /// Only available when split mode was enabled while the market was open, and only
+    /// once the oracle reports the market's consensus deadline missed. Every revealed
+    /// predictor, YES or NO, then claims their own stake back minus the usual fee.
+    pub fn resolve_market_as_tie(env: Env, caller: Address, market_id: BytesN<32>) {
+        Self::require_keeper_or_oracle(&env, &caller);
+
+        if !Self::is_split_on_tie(env.clone()) {
+            panic!("Tie resolution not enabled");
+        }
+
+        let current_time = Self::require_resolvable(&env);
+
+        let oracle_address: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_KEY))
+            .expect("Oracle address not found");
+        let deadline_missed: bool = env.invoke_contract(
+            &oracle_address,
+            &Symbol::new(&env, "is_consensus_deadline_missed"),
+            (market_id.clone(),).into_val(&env),
+        );
+        if !deadline_missed {
+            panic!("Oracle consensus deadline not missed");
+        }
+
+        let yes_pool: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, YES_POOL_KEY))
+            .unwrap_or(0);
+        let no_pool: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, NO_POOL_KEY))
+            .unwrap_or(0);
+
+        // With every stake counted as a winning share, each claim grosses exactly its stake
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, WINNING_OUTCOME_KEY), &OUTCOME_TIE);
+        Self::record_winner_weight(&env);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, WINNER_SHARES_KEY), &(yes_pool + no_pool));
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, LOSER_SHARES_KEY), &0i128);
+
+        Self::set_market_state(&env, STATE_RESOLVED);
+
+        Self::settle_creator_bond(&env, &market_id, false);
+
+        MarketResolvedEvent {
+            market_id,
+            final_outcome: OUTCOME_TIE,
+            timestamp: current_time,
+        }
+        .publish(&env);
+    }
+
+    /// Shared preconditions for resolution; returns the current ledger time
+    fn require_resolvable(env: &Env) -> u64 {
+        // Get current timestamp
+        let current_time = env.ledger().timestamp();
+
+        // Load resolution time from storage
+        let resolution_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, RESOLUTION_TIME_KEY))
+            .expect("Resolution time not found");
+
+        // Validate: current timestamp >= resolution_time
+        if current_time < resolution_time {
+            panic!("Cannot resolve market before resolution time");
+        }
+
+        // Load current market state
+        let current_state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, MARKET_STATE_KEY))
+            .expect("Market state not found");
+
+        // Validate: market state is CLOSED (not OPEN or already RESOLVED)
+        if current_state == STATE_OPEN {
+            panic!("Cannot resolve market that is still OPEN");
+        }
+
+        if current_state == STATE_RESOLVED {
+            panic!("Market already resolved");
+        }
+
+        // Validate: enough commitments were revealed for the pools to be meaningful
+        if !Self::meets_reveal_ratio(env) {
+            panic!("Insufficient reveals to resolve market");
+        }
+
+        current_time
+    }
+
+    /// Dry run for `resolve_market`: report whether resolution can proceed now
+    ///
+    /// Read-only. Returns `(true, "ready")`, or `false` with the first blocking
+    /// reason: `before_resolution_time`, `not_closed`, `already_resolved`,
+    /// `insufficient_reveals` (below the minimum reveal ratio), `active_challenge` (an oracle attestation is under challenge) or
+    /// `no_consensus`.
+    pub fn can_resolve_detailed(env: Env, market_id: BytesN<32>) -> (bool, Symbol) {
+        let resolution_time: u64 = env
+            .storage()
             .persistent()
-            .set(&Symbol::new(&env, WINNER_SHARES_KEY), &winner_shares);
+            .get(&Symbol::new(&env, RESOLUTION_TIME_KEY))
+            .expect("Resolution time not found");
+        if env.ledger().timestamp() < resolution_time {
+            return (false, Symbol::new(&env, "before_resolution_time"));
+        }
 
-        env.storage()
+        let state: u32 = env
+            .storage()
             .persistent()
-            .set(&Symbol::new(&env, LOSER_SHARES_KEY), &loser_shares);
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market state not found");
+        if state == STATE_RESOLVED {
+            return (false, Symbol::new(&env, "already_resolved"));
+        }
+        if state != STATE_CLOSED {
+            return (false, Symbol::new(&env, "not_closed"));
+        }
+        if !Self::meets_reveal_ratio(&env) {
+            return (false, Symbol::new(&env, "insufficient_reveals"));
+        }
 
-        // Update market state to RESOLVED
-        env.storage()
+        let oracle: Address = env
+            .storage()
             .persistent()
-            .set(&Symbol::new(&env, MARKET_STATE_KEY), &STATE_RESOLVED);
+            .get(&Symbol::new(&env, ORACLE_KEY))
+            .expect("Oracle address not found");
 
-        // Emit MarketResolved event
-        MarketResolvedEvent {
-            market_id,
-            final_outcome,
-            timestamp: current_time,
+        let challenged: bool = env.invoke_contract(
+            &oracle,
+            &Symbol::new(&env, "has_active_challenge"),
+            (market_id.clone(),).into_val(&env),
+        );
+        if challenged {
+            return (false, Symbol::new(&env, "active_challenge"));
         }
-        .publish(&env);
+
+        let (consensus_reached, _outcome): (bool, u32) = env.invoke_contract(
+            &oracle,
+            &Symbol::new(&env, "check_consensus"),
+            (market_id,).into_val(&env),
+        );
+        if !consensus_reached {
+            return (false, Symbol::new(&env, "no_consensus"));
+        }
+
+        (true, Symbol::new(&env, "ready"))
     }
 
-    /// Dispute market resolution within 7-day window
+    /// Dispute market resolution within the dispute window
     ///
     /// - Require user authentication
     /// - Validate market state is RESOLVED
-    /// - Validate current timestamp < resolution_time + 7 days
-    /// - Require minimum stake (1000 tokens)
-    /// - Store dispute record: { user, reason, evidence, timestamp }
+    /// - Validate current timestamp < resolution_time + dispute window (default 7 days)
+    /// - Require the dispute stake (default 1000 tokens; see `set_dispute_params`)
+    /// - Store dispute record: { user, reason, evidence, stake, timestamp }
     /// - Change market state to DISPUTED
     /// - Freeze all payouts until dispute resolved
     /// - Emit MarketDisputed event
@@ -815,15 +2855,25 @@ impl PredictionMarket {
             panic!("Market not resolved");
         }
 
+        let winning_outcome: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, WINNING_OUTCOME_KEY))
+            .expect("Winning outcome not found");
+        if winning_outcome == OUTCOME_TIE {
+            panic!("Cannot dispute a tie resolution");
+        }
+
         let resolution_time: u64 = env
             .storage()
             .persistent()
             .get(&Symbol::new(&env, RESOLUTION_TIME_KEY))
             .expect("Resolution time not found");
 
+        let (dispute_stake_amount, dispute_window) = Self::get_dispute_params(env.clone());
+
         let current_time = env.ledger().timestamp();
-        // 7 days = 604800 seconds
-        if current_time >= resolution_time + 604800 {
+        if current_time >= resolution_time + dispute_window {
             panic!("Dispute window has closed");
         }
 
@@ -836,20 +2886,29 @@ impl PredictionMarket {
 
         let token_client = token::TokenClient::new(&env, &usdc_token);
         let contract_address = env.current_contract_address();
-        let dispute_stake_amount: i128 = 1000;
 
         token_client.transfer(&user, &contract_address, &dispute_stake_amount);
 
-        // Transition market status to DISPUTED
-        env.storage()
+        // Book the stake in its own bucket so it never mixes with pool/payout accounting
+        let held: i128 = env
+            .storage()
             .persistent()
-            .set(&Symbol::new(&env, MARKET_STATE_KEY), &STATE_DISPUTED);
+            .get(&Symbol::new(&env, DISPUTE_STAKE_HELD_KEY))
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &Symbol::new(&env, DISPUTE_STAKE_HELD_KEY),
+            &(held + dispute_stake_amount),
+        );
+
+        // Transition market status to DISPUTED
+        Self::set_market_state(&env, STATE_DISPUTED);
 
         // Store dispute record
         let dispute = DisputeRecord {
             user: user.clone(),
             reason: dispute_reason.clone(),
             evidence: evidence_hash,
+            stake: dispute_stake_amount,
             timestamp: current_time,
         };
         let dispute_key = (Symbol::new(&env, "dispute"), market_id.clone());
@@ -865,6 +2924,379 @@ impl PredictionMarket {
         .publish(&env);
     }
 
+    /// Settle an open dispute (oracle or creator only)
+    ///
+    /// - `upheld == true`: the dispute was valid; the outcome is flipped, winner/loser
+    ///   shares are swapped and the disputer's stake is returned. Claims already paid under
+    ///   the old outcome are covered from the insurance fund and new winners get a fresh
+    ///   claim window of `get_dispute_claim_grace` seconds
+    /// - `upheld == false`: the original outcome stands and the stake is forfeited to the
+    ///   Treasury
+    ///
+    /// Either way the market returns to RESOLVED so claims can proceed.
+    pub fn resolve_dispute(env: Env, resolver: Address, market_id: BytesN<32>, upheld: bool) {
+        resolver.require_auth();
+
+        let creator: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CREATOR_KEY))
+            .expect("Market not initialized");
+        let oracle: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_KEY))
+            .expect("Oracle address not found");
+
+        if resolver != creator && resolver != oracle {
+            panic!("Unauthorized: only oracle or creator can resolve disputes");
+        }
+
+        let state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market state not found");
+
+        if state != STATE_DISPUTED {
+            panic!("Market not disputed");
+        }
+
+        let dispute_key = (Symbol::new(&env, "dispute"), market_id.clone());
+        let dispute: DisputeRecord = env
+            .storage()
+            .persistent()
+            .get(&dispute_key)
+            .expect("Dispute record not found");
+
+        let mut final_outcome: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, WINNING_OUTCOME_KEY))
+            .expect("Winning outcome not found");
+
+        // Release the stake from the dispute bucket
+        let held: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, DISPUTE_STAKE_HELD_KEY))
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &Symbol::new(&env, DISPUTE_STAKE_HELD_KEY),
+            &(held - dispute.stake),
+        );
+
+        if upheld {
+            final_outcome = 1 - final_outcome;
+
+            let winner_shares: i128 = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, WINNER_SHARES_KEY))
+                .unwrap_or(0);
+            let loser_shares: i128 = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, LOSER_SHARES_KEY))
+                .unwrap_or(0);
+
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, WINNING_OUTCOME_KEY), &final_outcome);
+            Self::record_winner_weight(&env);
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, WINNER_SHARES_KEY), &loser_shares);
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, LOSER_SHARES_KEY), &winner_shares);
+
+            let usdc_token: Address = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, USDC_KEY))
+                .expect("USDC token not found");
+            token::TokenClient::new(&env, &usdc_token).transfer(
+                &env.current_contract_address(),
+                &dispute.user,
+                &dispute.stake,
+            );
+
+            Self::reopen_claims(&env, &market_id, final_outcome);
+        } else {
+            let forfeited: i128 = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, DISPUTE_STAKE_FORFEITED_KEY))
+                .unwrap_or(0);
+            env.storage().persistent().set(
+                &Symbol::new(&env, DISPUTE_STAKE_FORFEITED_KEY),
+                &(forfeited + dispute.stake),
+            );
+            Self::route_to_treasury(&env, dispute.stake);
+        }
+
+        env.storage().persistent().remove(&dispute_key);
+        Self::set_market_state(&env, STATE_RESOLVED);
+
+        DisputeResolvedEvent {
+            market_id,
+            disputer: dispute.user,
+            upheld,
+            final_outcome,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+    }
+
+    /// Helper: Release an unsettled dispute's stake from the held bucket to the disputer
+    fn return_dispute_stake(env: &Env, market_id: &BytesN<32>) {
+        let dispute_key = (Symbol::new(env, "dispute"), market_id.clone());
+        let dispute: DisputeRecord = env
+            .storage()
+            .persistent()
+            .get(&dispute_key)
+            .expect("Dispute record not found");
+
+        let held: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, DISPUTE_STAKE_HELD_KEY))
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &Symbol::new(env, DISPUTE_STAKE_HELD_KEY),
+            &(held - dispute.stake),
+        );
+
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, USDC_KEY))
+            .expect("USDC token not found");
+        token::TokenClient::new(env, &usdc_token).transfer(
+            &env.current_contract_address(),
+            &dispute.user,
+            &dispute.stake,
+        );
+    }
+
+    /// Helper: Start a fresh claim epoch after an upheld dispute flips the outcome
+    ///
+    /// Claims paid under the old outcome drew their gross payout from the pool (the fee
+    /// stays booked as collected), so the new winners' pool is short by that amount. The insurance fund covers as much of it as it can;
+    /// whatever it cannot cover is booked as an uncovered shortfall.
+    fn reopen_claims(env: &Env, market_id: &BytesN<32>, final_outcome: u32) {
+        let epoch: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, RESOLUTION_EPOCH_KEY))
+            .unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(env, RESOLUTION_EPOCH_KEY), &(epoch + 1));
+
+        let stale_payout: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, CLAIMED_GROSS_TOTAL_KEY))
+            .unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(env, CLAIMED_GROSS_TOTAL_KEY), &0i128);
+
+        let fund: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, INSURANCE_FUND_KEY))
+            .unwrap_or(0);
+        let insurance_drawn = fund.min(stale_payout);
+        env.storage().persistent().set(
+            &Symbol::new(env, INSURANCE_FUND_KEY),
+            &(fund - insurance_drawn),
+        );
+
+        let shortfall: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, UNCOVERED_SHORTFALL_KEY))
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &Symbol::new(env, UNCOVERED_SHORTFALL_KEY),
+            &(shortfall + stale_payout - insurance_drawn),
+        );
+
+        let claim_window_end =
+            env.ledger().timestamp() + Self::get_dispute_claim_grace(env.clone());
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(env, CLAIM_WINDOW_END_KEY), &claim_window_end);
+
+        ClaimsReopenedEvent {
+            market_id: market_id.clone(),
+            final_outcome,
+            stale_payout,
+            insurance_drawn,
+            claim_window_end,
+        }
+        .publish(env);
+    }
+
+    /// Deposit into the market's insurance fund (anyone may contribute)
+    ///
+    /// The fund backs new winners when an upheld dispute flips the outcome after
+    /// claims were already paid under the old one, and tops up principal-protected winners.
+    pub fn fund_insurance(env: Env, funder: Address, amount: i128) {
+        funder.require_auth();
+
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC token not found");
+        let contract_address = env.current_contract_address();
+        token::TokenClient::new(&env, &usdc_token).transfer(&funder, &contract_address, &amount);
+
+        let fund: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, INSURANCE_FUND_KEY))
+            .unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, INSURANCE_FUND_KEY), &(fund + amount));
+    }
+
+    /// Get the insurance fund balance not yet drawn by a dispute
+    pub fn get_insurance_fund(env: Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, INSURANCE_FUND_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Get the stale payout the insurance fund could not cover after a flipped outcome
+    pub fn get_uncovered_shortfall(env: Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, UNCOVERED_SHORTFALL_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Set the dispute stake and the window after resolution_time in which disputes are
+    /// accepted (creator only, before the market resolves)
+    pub fn set_dispute_params(env: Env, creator: Address, stake: i128, window: u64) {
+        Self::require_creator(&env, &creator);
+
+        if stake <= 0 {
+            panic!("Dispute stake must be positive");
+        }
+        if window == 0 {
+            panic!("Dispute window must be positive");
+        }
+
+        let state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market state not found");
+        if state != STATE_OPEN && state != STATE_CLOSED {
+            panic!("Dispute params are locked once the market resolves");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, DISPUTE_STAKE_KEY), &stake);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, DISPUTE_WINDOW_KEY), &window);
+    }
+
+    /// Get the (stake, window seconds) used by `dispute_market`; defaults to (1000, 7 days)
+    pub fn get_dispute_params(env: Env) -> (i128, u64) {
+        let stake: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, DISPUTE_STAKE_KEY))
+            .unwrap_or(DEFAULT_DISPUTE_STAKE);
+        let window: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, DISPUTE_WINDOW_KEY))
+            .unwrap_or(DEFAULT_DISPUTE_WINDOW);
+        (stake, window)
+    }
+
+    /// Set how long new winners have to claim after a dispute flips the outcome (creator only)
+    pub fn set_dispute_claim_grace(env: Env, creator: Address, seconds: u64) {
+        Self::require_creator(&env, &creator);
+
+        if seconds == 0 {
+            panic!("Grace must be positive");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, DISPUTE_CLAIM_GRACE_KEY), &seconds);
+    }
+
+    /// Get the post-dispute claim grace in seconds (defaults to 7 days)
+    pub fn get_dispute_claim_grace(env: Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, DISPUTE_CLAIM_GRACE_KEY))
+            .unwrap_or(DEFAULT_DISPUTE_CLAIM_GRACE)
+    }
+
+    /// Get the end of the post-dispute claim window (0 = outcome never flipped, no deadline)
+    pub fn get_claim_window_end(env: Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, CLAIM_WINDOW_END_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Whether `user` claimed under an outcome later overturned by a dispute
+    pub fn is_stale_claim(env: Env, user: Address) -> bool {
+        let claim_epoch: Option<u32> = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, CLAIM_EPOCH_PREFIX), user));
+        let epoch: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, RESOLUTION_EPOCH_KEY))
+            .unwrap_or(0);
+        matches!(claim_epoch, Some(e) if e < epoch)
+    }
+
+    /// Get the open dispute on a market (disputer, reason, evidence, stake held), if any
+    ///
+    /// The record is removed once `resolve_dispute` settles it or the market is cancelled.
+    pub fn get_dispute(env: Env, market_id: BytesN<32>) -> Option<DisputeRecord> {
+        let dispute_key = (Symbol::new(&env, "dispute"), market_id);
+        env.storage().persistent().get(&dispute_key)
+    }
+
+    /// Get the dispute stake currently held in escrow (outside the prediction pools)
+    pub fn get_dispute_stake_held(env: Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, DISPUTE_STAKE_HELD_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Get the total dispute stake forfeited by rejected disputes
+    pub fn get_dispute_stake_forfeited(env: Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, DISPUTE_STAKE_FORFEITED_KEY))
+            .unwrap_or(0)
+    }
+
     /// Claim winnings after market resolution
     ///
     /// This function allows users to claim their winnings after a market has been resolved.
@@ -876,10 +3308,14 @@ impl PredictionMarket {
     ///
     /// # Payout Calculation
     /// - Payout = (user_amount / winner_shares) * total_pool
-    /// - 10% protocol fee is deducted from the gross payout
+    /// - Under quadratic weighting: (sqrt(user_amount) / sum of winners' sqrt(amount)) * total_pool
+    /// - The protocol fee (`get_fee_bps`, 10% by default, rounded up) is deducted from the
+    ///   gross payout
+    /// - Under principal protection the net payout is floored at user_amount (see
+    ///   `set_principal_protection`)
     ///
     /// # Events
-    /// - Emits WinningsClaimed(user, market_id, amount)
+    /// - Emits WinningsClaimed(user, market_id, gross, protocol_fee, creator_fee, net)
     ///
     /// # Panics
     /// * If market is not resolved
@@ -887,6 +3323,20 @@ impl PredictionMarket {
     /// * If user already claimed
     /// * If user did not predict winning outcome
     pub fn claim_winnings(env: Env, user: Address, market_id: BytesN<32>) -> i128 {
+        Self::claim_winnings_verbose(env, user, market_id).3
+    }
+
+    /// Claim winnings and return the full receipt
+    ///
+    /// Same as `claim_winnings`, but returns `(gross, protocol_fee, creator_fee, net)`.
+    /// The withheld fee is split between protocol and creator, so
+    /// `protocol_fee + creator_fee + net == gross` (gross includes any insurance top-up
+    /// paid under principal protection).
+    pub fn claim_winnings_verbose(
+        env: Env,
+        user: Address,
+        market_id: BytesN<32>,
+    ) -> (i128, i128, i128, i128) {
         // Require user authentication
         user.require_auth();
 
@@ -901,6 +3351,12 @@ impl PredictionMarket {
             panic!("Market not resolved");
         }
 
+        // After a flipped outcome, new winners must claim within the grace window
+        let claim_window_end = Self::get_claim_window_end(env.clone());
+        if claim_window_end != 0 && env.ledger().timestamp() > claim_window_end {
+            panic!("Claim window closed");
+        }
+
         // 2. Get User Prediction
         let prediction_key = (Symbol::new(&env, PREDICTION_PREFIX), user.clone());
         let mut prediction: UserPrediction = env
@@ -921,13 +3377,14 @@ impl PredictionMarket {
             .get(&Symbol::new(&env, WINNING_OUTCOME_KEY))
             .expect("Winning outcome not found");
 
-        if prediction.outcome != winning_outcome {
+        // On a tie both sides are winners and recover their own stake
+        if winning_outcome != OUTCOME_TIE && prediction.outcome != winning_outcome {
             panic!("User did not predict winning outcome");
         }
 
         // 5. Calculate Payout
         // Payout = (UserAmount / WinnerPool) * TotalPool
-        // Apply 10% Protocol Fee
+        // Apply the configured protocol fee
         let winner_shares: i128 = env
             .storage()
             .persistent()
@@ -946,18 +3403,38 @@ impl PredictionMarket {
             panic!("No winners to claim");
         }
 
-        // Calculate gross payout using integer arithmetic
-        // (amount * total_pool) / winner_shares
-        let gross_payout = prediction
-            .amount
-            .checked_mul(total_pool)
-            .expect("Overflow in payout calculation")
-            .checked_div(winner_shares)
-            .expect("Division by zero in payout calculation");
+        // Protocol fee (waived during a promo window, discounted for governance token holders),
+        // of which the creator's share is carved out
+        let fee_bps = if env.ledger().timestamp() < Self::get_fee_free_until(env.clone()) {
+            0
+        } else {
+            let discount_bps = Self::get_fee_discount_bps(env.clone(), user.clone()) as i128;
+            Self::get_fee_bps(env.clone()) as i128 * (10000 - discount_bps) / 10000
+        };
+        let (weight, total_weight) = Self::payout_weights(&env, prediction.amount, winner_shares);
+        let (mut net_payout, mut fee) = compute_payout(weight, total_weight, total_pool, fee_bps);
+        let pool_payout = net_payout + fee;
+
+        // Principal protection: waive fee down to the stake, then top up from insurance
+        if net_payout < prediction.amount && Self::is_principal_protected(env.clone()) {
+            let fee_waived = fee.min(prediction.amount - net_payout);
+            fee -= fee_waived;
+            net_payout += fee_waived;
+
+            let fund = Self::get_insurance_fund(env.clone());
+            let insurance_drawn = fund.min(prediction.amount - net_payout);
+            if insurance_drawn > 0 {
+                env.storage().persistent().set(
+                    &Symbol::new(&env, INSURANCE_FUND_KEY),
+                    &(fund - insurance_drawn),
+                );
+                net_payout += insurance_drawn;
+            }
+        }
 
-        // 10% Fee
-        let fee = gross_payout / 10;
-        let net_payout = gross_payout - fee;
+        let gross_payout = net_payout + fee;
+        let creator_fee = fee * CREATOR_FEE_SHARE_PCT / 100;
+        let protocol_fee = fee - creator_fee;
 
         if net_payout == 0 {
             panic!("Payout amount is zero");
@@ -999,16 +3476,145 @@ impl PredictionMarket {
         // 8. Mark as claimed (idempotent - prevents double-claim)
         prediction.claimed = true;
         env.storage().persistent().set(&prediction_key, &prediction);
+        env.storage().persistent().set(
+            &(Symbol::new(&env, CLAIMED_PAYOUT_PREFIX), user.clone()),
+            &net_payout,
+        );
+        Self::report_position(&env, &user, false);
+
+        // Tag the claim with the current resolution epoch so a later flip can spot it
+        let epoch: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, RESOLUTION_EPOCH_KEY))
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &(Symbol::new(&env, CLAIM_EPOCH_PREFIX), user.clone()),
+            &epoch,
+        );
+        let claimed_gross_total: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CLAIMED_GROSS_TOTAL_KEY))
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &Symbol::new(&env, CLAIMED_GROSS_TOTAL_KEY),
+            &(claimed_gross_total + pool_payout),
+        );
+
+        // Track protocol fees withheld from claims
+        let fees_collected: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, FEES_COLLECTED_KEY))
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &Symbol::new(&env, FEES_COLLECTED_KEY),
+            &(fees_collected + fee),
+        );
 
         // 9. Emit WinningsClaimed Event
         WinningsClaimedEvent {
             user,
             market_id: market_id.clone(),
+            gross_payout,
+            protocol_fee,
+            creator_fee,
             net_payout,
         }
         .publish(&env);
 
-        net_payout
+        (gross_payout, protocol_fee, creator_fee, net_payout)
+    }
+
+    /// Close out a losing or never-revealed position once the market is resolved
+    ///
+    /// Winners close their position by claiming; losers have nothing to claim, so anyone
+    /// may call this to stop the factory counting the position as active.
+    pub fn close_losing_position(env: Env, user: Address) {
+        let state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market not initialized");
+        if state != STATE_RESOLVED {
+            panic!("Market not resolved");
+        }
+
+        let unrevealed = env
+            .storage()
+            .persistent()
+            .has(&Self::get_commit_key(&env, &user));
+        if !unrevealed {
+            let prediction: UserPrediction = env
+                .storage()
+                .persistent()
+                .get(&Self::get_prediction_key(&env, &user))
+                .expect("No prediction found for user");
+            let winning_outcome: u32 = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, WINNING_OUTCOME_KEY))
+                .expect("Winning outcome not found");
+            if winning_outcome == OUTCOME_TIE || prediction.outcome == winning_outcome {
+                panic!("Winning positions close by claiming");
+            }
+        }
+
+        Self::report_position(&env, &user, false);
+    }
+
+    /// Get post-resolution pool statistics for fee-model analysis
+    ///
+    /// # Returns
+    /// `(winner_shares, loser_shares, total_fee_collected, payout_multiplier_bps)` where
+    /// `payout_multiplier_bps` is the gross payout per unit staked on the winning side
+    /// (10000 = 1x, i.e. total_pool / winner_shares) and `total_fee_collected` is the
+    /// protocol fee withheld from claims so far.
+    ///
+    /// # Panics
+    /// * If market is not resolved
+    pub fn get_resolution_stats(env: Env, _market_id: BytesN<32>) -> (i128, i128, i128, u32) {
+        let state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market not initialized");
+
+        if state != STATE_RESOLVED {
+            panic!("Market not resolved");
+        }
+
+        let winner_shares: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, WINNER_SHARES_KEY))
+            .unwrap_or(0);
+
+        let loser_shares: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, LOSER_SHARES_KEY))
+            .unwrap_or(0);
+
+        let total_fee_collected: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, FEES_COLLECTED_KEY))
+            .unwrap_or(0);
+
+        let payout_multiplier_bps = if winner_shares == 0 {
+            0
+        } else {
+            ((winner_shares + loser_shares) * 10000 / winner_shares) as u32
+        };
+
+        (
+            winner_shares,
+            loser_shares,
+            total_fee_collected,
+            payout_multiplier_bps,
+        )
     }
 
     /// Refund users if their prediction failed (optional opt-in)
@@ -1093,6 +3699,185 @@ impl PredictionMarket {
         }
     }
 
+    /// Get seconds remaining until close and until resolution, by ledger time
+    ///
+    /// # Returns
+    /// `(until_close, until_resolution)`; a value is negative once that time has passed.
+    pub fn get_time_remaining(env: Env, _market_id: BytesN<32>) -> (i64, i64) {
+        let closing_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CLOSING_TIME_KEY))
+            .expect("Market not initialized");
+        let resolution_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, RESOLUTION_TIME_KEY))
+            .expect("Market not initialized");
+        let now = env.ledger().timestamp() as i64;
+
+        (closing_time as i64 - now, resolution_time as i64 - now)
+    }
+
+    /// Get a full market summary (status, timing, pools, participation, fees, dispute)
+    ///
+    /// Bundles what would otherwise take several calls into one read for frontends.
+    pub fn get_market_summary(env: Env, market_id: BytesN<32>) -> MarketSummary {
+        let storage = env.storage().persistent();
+
+        let status: u32 = storage
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .unwrap_or(STATE_OPEN);
+        let closing_time: u64 = storage
+            .get(&Symbol::new(&env, CLOSING_TIME_KEY))
+            .unwrap_or(0);
+        let resolution_time: u64 = storage
+            .get(&Symbol::new(&env, RESOLUTION_TIME_KEY))
+            .unwrap_or(0);
+
+        let yes_pool: i128 = storage.get(&Symbol::new(&env, YES_POOL_KEY)).unwrap_or(0);
+        let no_pool: i128 = storage.get(&Symbol::new(&env, NO_POOL_KEY)).unwrap_or(0);
+        let total_volume: i128 = storage
+            .get(&Symbol::new(&env, TOTAL_VOLUME_KEY))
+            .unwrap_or(0);
+
+        let participant_count = storage
+            .get::<_, Vec<Address>>(&Symbol::new(&env, PARTICIPANTS_KEY))
+            .map(|participants| participants.len())
+            .unwrap_or(0);
+        let revealed_count = storage
+            .get::<_, Vec<Address>>(&Symbol::new(&env, REVEALED_PARTICIPANTS_KEY))
+            .map(|revealed| revealed.len())
+            .unwrap_or(0);
+        let pending_count: u32 = storage
+            .get(&Symbol::new(&env, PENDING_COUNT_KEY))
+            .unwrap_or(0);
+
+        let winning_outcome: Option<u32> = if status == STATE_RESOLVED || status == STATE_DISPUTED {
+            storage.get(&Symbol::new(&env, WINNING_OUTCOME_KEY))
+        } else {
+            None
+        };
+
+        let is_disputed = status == STATE_DISPUTED;
+        let disputer = if is_disputed {
+            storage
+                .get::<_, DisputeRecord>(&(Symbol::new(&env, "dispute"), market_id))
+                .map(|dispute| dispute.user)
+        } else {
+            None
+        };
+
+        MarketSummary {
+            status,
+            closing_time,
+            resolution_time,
+            yes_pool,
+            no_pool,
+            total_volume,
+            participant_count,
+            revealed_count,
+            pending_count,
+            winning_outcome,
+            protocol_fee_bps: Self::get_fee_bps(env.clone()),
+            reveal_bond: Self::get_reveal_bond(env.clone()),
+            is_disputed,
+            disputer,
+            dispute_stake_held: Self::get_dispute_stake_held(env.clone()),
+        }
+    }
+
+    /// Number of distinct users who revealed each outcome
+    ///
+    /// # Returns
+    /// * `(yes_count, no_count)`
+    pub fn get_outcome_participation(env: Env, _market_id: BytesN<32>) -> (u32, u32) {
+        let yes_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, YES_COUNT_KEY))
+            .unwrap_or(0);
+        let no_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, NO_COUNT_KEY))
+            .unwrap_or(0);
+
+        (yes_count, no_count)
+    }
+
+    /// Project final odds assuming pending commitments reveal in a given split
+    ///
+    /// Committed outcomes are hidden, so `assumed_yes_fraction_bps` of the pending
+    /// (unrevealed) stake is added to the YES pool and the rest to the NO pool.
+    ///
+    /// # Returns
+    /// `(yes_odds, no_odds)` in basis points of the projected total stake
+    /// (5000/5000 when nothing has been staked).
+    pub fn project_final_odds(
+        env: Env,
+        _market_id: BytesN<32>,
+        assumed_yes_fraction_bps: u32,
+    ) -> (u32, u32) {
+        if assumed_yes_fraction_bps > 10000 {
+            panic!("Assumed YES fraction cannot exceed 10000 bps");
+        }
+
+        let participants: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, PARTICIPANTS_KEY))
+            .unwrap_or_else(|| Vec::new(&env));
+
+        let mut seen: Vec<Address> = Vec::new(&env);
+        let mut pending_amount: i128 = 0;
+        for user in participants.iter() {
+            if seen.contains(&user) {
+                continue;
+            }
+            seen.push_back(user.clone());
+
+            if let Some(commitment) = env
+                .storage()
+                .persistent()
+                .get::<_, Commitment>(&Self::get_commit_key(&env, &user))
+            {
+                pending_amount += commitment.amount;
+            }
+        }
+
+        let yes_pool: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, YES_POOL_KEY))
+            .unwrap_or(0);
+        let no_pool: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, NO_POOL_KEY))
+            .unwrap_or(0);
+
+        let pending_yes = pending_amount * assumed_yes_fraction_bps as i128 / 10000;
+        let projected_yes = yes_pool + pending_yes;
+        let projected_total = yes_pool + no_pool + pending_amount;
+
+        if projected_total == 0 {
+            return (5000, 5000);
+        }
+
+        let yes_odds = (projected_yes * 10000 / projected_total) as u32;
+        (yes_odds, 10000 - yes_odds)
+    }
+
+    /// Whether a user has already claimed winnings in this market
+    pub fn has_claimed(env: Env, user: Address, _market_id: BytesN<32>) -> bool {
+        env.storage()
+            .persistent()
+            .get::<_, UserPrediction>(&Self::get_prediction_key(&env, &user))
+            .map(|pred| pred.claimed)
+            .unwrap_or(false)
+    }
+
     /// Get prediction records for a user in this market
     ///
     /// Returns commitment_hash, amount, status, predicted_outcome (if revealed).
@@ -1131,6 +3916,87 @@ impl PredictionMarket {
         None
     }
 
+    /// Creator/factory: get every participant's position for settlement dashboards
+    ///
+    /// Iterates the participants list once per user and returns the same record as
+    /// `get_user_prediction`. Users with neither a commitment nor a prediction (e.g.
+    /// refunded positions) are omitted.
+    pub fn get_all_positions(
+        env: Env,
+        admin: Address,
+        market_id: BytesN<32>,
+    ) -> Vec<UserPredictionResult> {
+        admin.require_auth();
+
+        let creator: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CREATOR_KEY))
+            .expect("Market not initialized");
+        let factory: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, FACTORY_KEY))
+            .expect("Market not initialized");
+        if admin != creator && admin != factory {
+            panic!("Unauthorized: only creator or factory");
+        }
+
+        let participants: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, PARTICIPANTS_KEY))
+            .unwrap_or_else(|| Vec::new(&env));
+
+        let mut seen: Vec<Address> = Vec::new(&env);
+        let mut positions: Vec<UserPredictionResult> = Vec::new(&env);
+        for user in participants.iter() {
+            if seen.contains(&user) {
+                continue;
+            }
+            seen.push_back(user.clone());
+
+            if let Some(position) = Self::get_user_prediction(env.clone(), user, market_id.clone())
+            {
+                positions.push_back(position);
+            }
+        }
+
+        positions
+    }
+
+    /// Get a user's settled result in this market for ROI reporting
+    ///
+    /// # Returns
+    /// `(staked, net_returned)` where `staked` is the revealed stake and `net_returned`
+    /// is the net payout claimed so far (0 for a loss or an unclaimed win).
+    /// Returns `(0, 0)` until the market is resolved or if the user never revealed.
+    pub fn get_user_result(env: Env, user: Address) -> (i128, i128) {
+        let state: Option<u32> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY));
+        if state != Some(STATE_RESOLVED) {
+            return (0, 0);
+        }
+
+        let prediction: Option<UserPrediction> = env
+            .storage()
+            .persistent()
+            .get(&Self::get_prediction_key(&env, &user));
+        let Some(prediction) = prediction else {
+            return (0, 0);
+        };
+
+        let net_returned: i128 = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, CLAIMED_PAYOUT_PREFIX), user))
+            .unwrap_or(0);
+
+        (prediction.amount, net_returned)
+    }
+
     /// Return paginated list of all revealed predictions for this market.
     ///
     /// Only includes predictions that have been revealed (commit-phase privacy preserved).
@@ -1199,13 +4065,15 @@ impl PredictionMarket {
     ///
     /// # Requirements
     /// - Market must be in RESOLVED state
+    /// - Walks the stored participants list; committers who never revealed are skipped
     /// - Only returns users who predicted the winning outcome
-    /// - Payouts are calculated with 10% protocol fee deducted
+    /// - Payouts are calculated with the configured protocol fee (`get_fee_bps`) deducted,
+    ///   using the market's linear or quadratic weighting
     ///
     /// # Edge Cases
     /// - If N exceeds total winners, returns all winners
     /// - If N is 0, returns empty vector
-    /// - Handles ties in payout amounts (maintains deterministic order)
+    /// - Ties in payout amounts are ordered by address, so output is deterministic
     /// - Returns empty vector if no winners exist
     ///
     /// # Panics
@@ -1215,93 +4083,169 @@ impl PredictionMarket {
         _market_id: BytesN<32>,
         limit: u32,
     ) -> Vec<(Address, i128)> {
-        // 1. Validate market state is RESOLVED
+        // Every committer; only those with a revealed prediction can be winners
+        let participants: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, PARTICIPANTS_KEY))
+            .unwrap_or_else(|| Vec::new(&env));
+
+        Self::collect_leaderboard(&env, participants, limit)
+    }
+
+    /// Helper: Top `limit` winners among `users` with their net payouts
+    fn collect_leaderboard(env: &Env, users: Vec<Address>, limit: u32) -> Vec<(Address, i128)> {
+        // Validate market state is RESOLVED
         let state: u32 = env
             .storage()
             .persistent()
-            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .get(&Symbol::new(env, MARKET_STATE_KEY))
             .expect("Market not initialized");
 
         if state != STATE_RESOLVED {
             panic!("Market not resolved");
         }
 
-        // 2. Handle edge case: limit is 0
         if limit == 0 {
-            return Vec::new(&env);
+            return Vec::new(env);
         }
 
-        // 3. Get winning outcome and pool information
-        let _winning_outcome: u32 = env
+        let winning_outcome: u32 = env
             .storage()
             .persistent()
-            .get(&Symbol::new(&env, WINNING_OUTCOME_KEY))
+            .get(&Symbol::new(env, WINNING_OUTCOME_KEY))
             .expect("Winning outcome not found");
 
         let winner_shares: i128 = env
             .storage()
             .persistent()
-            .get(&Symbol::new(&env, WINNER_SHARES_KEY))
+            .get(&Symbol::new(env, WINNER_SHARES_KEY))
             .expect("Winner shares not found");
 
         let loser_shares: i128 = env
             .storage()
             .persistent()
-            .get(&Symbol::new(&env, LOSER_SHARES_KEY))
+            .get(&Symbol::new(env, LOSER_SHARES_KEY))
             .unwrap_or(0);
 
-        let _total_pool = winner_shares + loser_shares;
+        let total_pool = winner_shares + loser_shares;
+
+        if winner_shares == 0 {
+            return Vec::new(env);
+        }
+
+        let fee_bps = Self::get_fee_bps(env.clone()) as i128;
 
-        // 4. Handle edge case: no winners
-        if winner_shares == 0 {
-            return Vec::new(&env);
+        // Collect winners from the given user list
+        let mut winners: Vec<(Address, i128)> = Vec::new(env);
+
+        for user in users.iter() {
+            if let Some(prediction) = env
+                .storage()
+                .persistent()
+                .get::<_, UserPrediction>(&Self::get_prediction_key(env, &user))
+            {
+                if winning_outcome == OUTCOME_TIE || prediction.outcome == winning_outcome {
+                    let (weight, total_weight) =
+                        Self::payout_weights(env, prediction.amount, winner_shares);
+                    let (net_payout, _) = compute_payout(weight, total_weight, total_pool, fee_bps);
+                    winners.push_back((user, net_payout));
+                }
+            }
         }
 
-        // 5. Collect all winners with their payouts
-        // Note: This implementation uses a test helper approach
-        // In production, you would maintain a list of all participants during prediction phase
-        let mut winners: Vec<(Address, i128)> = Vec::new(&env);
+        // Sort by payout descending (ties broken by address)
+        Self::sort_leaderboard(&mut winners);
+        let len = winners.len();
 
-        // Since Soroban doesn't provide iteration over storage keys,
-        // we rely on the test infrastructure to set up predictions
-        // The actual collection would happen through a maintained participant list
+        // Return top N
+        let result_len = if limit < len { limit } else { len };
+        let mut result: Vec<(Address, i128)> = Vec::new(env);
 
-        // For each participant (in production, iterate through stored participant list):
-        // - Check if they have a prediction
-        // - If prediction.outcome == winning_outcome, calculate payout
-        // - Add to winners vector
+        for i in 0..result_len {
+            result.push_back(winners.get(i).unwrap());
+        }
 
-        // This is intentionally left as a framework that works with test helpers
-        // Production implementation would require maintaining a participants list
+        result
+    }
 
-        // 6. Sort winners by payout descending using bubble sort
+    /// Helper: Stable bubble sort of (user, payout) by payout descending, then address
+    /// ascending, so equal payouts come out in the same order whatever the input order
+    fn sort_leaderboard(winners: &mut Vec<(Address, i128)>) {
         // Soroban Vec doesn't have built-in sort
         let len = winners.len();
-        if len > 1 {
-            for i in 0..len {
-                for j in 0..(len - i - 1) {
-                    let current = winners.get(j).unwrap();
-                    let next = winners.get(j + 1).unwrap();
-
-                    // Sort by payout descending
-                    if current.1 < next.1 {
-                        let temp = current.clone();
-                        winners.set(j, next);
-                        winners.set(j + 1, temp);
-                    }
+        if len < 2 {
+            return;
+        }
+        for i in 0..len {
+            for j in 0..(len - i - 1) {
+                let current = winners.get(j).unwrap();
+                let next = winners.get(j + 1).unwrap();
+
+                // Only swap when strictly out of order; equal entries keep their position
+                let out_of_order =
+                    current.1 < next.1 || (current.1 == next.1 && current.0 > next.0);
+                if out_of_order {
+                    winners.set(j, next);
+                    winners.set(j + 1, current);
                 }
             }
         }
+    }
 
-        // 7. Return top N winners
-        let result_len = if limit < len { limit } else { len };
-        let mut result: Vec<(Address, i128)> = Vec::new(&env);
+    /// Admin (market creator): rebuild pool totals from stored predictions
+    ///
+    /// Walks the participants list, sums every revealed prediction by outcome and
+    /// overwrites the YES/NO pools and total volume. Recovery tool for accounting
+    /// that has drifted from the underlying predictions (e.g. after a migration).
+    ///
+    /// # Returns
+    /// * `(yes_pool, no_pool, total_volume)` - The repaired values
+    pub fn recompute_pools(env: Env, admin: Address, market_id: BytesN<32>) -> (i128, i128, i128) {
+        Self::require_creator(&env, &admin);
 
-        for i in 0..result_len {
-            result.push_back(winners.get(i).unwrap());
+        let participants: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, PARTICIPANTS_KEY))
+            .unwrap_or_else(|| Vec::new(&env));
+
+        let mut seen: Vec<Address> = Vec::new(&env);
+        let mut yes_pool: i128 = 0;
+        let mut no_pool: i128 = 0;
+
+        for user in participants.iter() {
+            if seen.contains(&user) {
+                continue;
+            }
+            seen.push_back(user.clone());
+
+            if let Some(prediction) = env
+                .storage()
+                .persistent()
+                .get::<_, UserPrediction>(&Self::get_prediction_key(&env, &user))
+            {
+                if prediction.outcome == 1 {
+                    yes_pool += prediction.amount;
+                } else {
+                    no_pool += prediction.amount;
+                }
+            }
         }
 
-        result
+        let total_volume = yes_pool + no_pool;
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, YES_POOL_KEY), &yes_pool);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, NO_POOL_KEY), &no_pool);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, TOTAL_VOLUME_KEY), &total_volume);
+        Self::publish_pool_update(&env, &market_id);
+
+        (yes_pool, no_pool, total_volume)
     }
 
     /// Query current YES/NO liquidity from AMM pool
@@ -1394,8 +4338,12 @@ impl PredictionMarket {
     ///
     /// - Require creator authentication
     /// - Validate market state is OPEN or CLOSED (not resolved)
+    /// - With multi-sig cancellation enabled, this only records the creator's approval;
+    ///   the cancel executes once `approve_cancel` approvals reach the threshold
     /// - Set market state to CANCELLED; participants claim refunds via claim_refund
-    /// - Emit MarketCancelled(market_id, creator, timestamp)
+    ///   (or have them pushed via retry_refund, which tolerates per-user transfer failures)
+    /// - Emit MarketCancelled(market_id, creator, total_refundable, timestamp), where
+    ///   total_refundable sums `get_refund_queue` at cancellation
     pub fn cancel_market(env: Env, creator: Address, market_id: BytesN<32>) {
         creator.require_auth();
 
@@ -1409,10 +4357,107 @@ impl PredictionMarket {
             panic!("Unauthorized: only creator can cancel");
         }
 
+        let state = Self::require_cancellable_state(&env);
+
+        if Self::get_cancel_multisig(env.clone()).1 > 0 {
+            Self::record_cancel_approval(&env, &creator, &market_id, state);
+            return;
+        }
+
+        Self::execute_cancel(&env, creator, market_id, state);
+    }
+
+    /// Factory: require M-of-N approvals before a cancel executes
+    ///
+    /// `signers` (e.g. the factory admins) may approve alongside the creator, who is always
+    /// an eligible signer. A `threshold` of 0 turns multi-sig off and restores single-creator
+    /// cancels. Changing the configuration clears any approvals recorded so far.
+    pub fn set_cancel_multisig(env: Env, signers: Vec<Address>, threshold: u32) {
+        let factory: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, FACTORY_KEY))
+            .expect("Factory address not set");
+        factory.require_auth();
+
+        let creator: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CREATOR_KEY))
+            .expect("Market not initialized");
+        let mut eligible = signers.len();
+        if !signers.contains(&creator) {
+            eligible += 1;
+        }
+        if threshold > eligible {
+            panic!("Threshold exceeds number of signers");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, CANCEL_SIGNERS_KEY), &signers);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, CANCEL_THRESHOLD_KEY), &threshold);
+        env.storage()
+            .persistent()
+            .remove(&Symbol::new(&env, CANCEL_APPROVALS_KEY));
+    }
+
+    /// Get the multi-sig cancellation signers and threshold (threshold 0 = disabled)
+    pub fn get_cancel_multisig(env: Env) -> (Vec<Address>, u32) {
+        let signers: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CANCEL_SIGNERS_KEY))
+            .unwrap_or_else(|| Vec::new(&env));
+        let threshold: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CANCEL_THRESHOLD_KEY))
+            .unwrap_or(0);
+        (signers, threshold)
+    }
+
+    /// Get the addresses that have approved cancelling this market, in approval order
+    pub fn get_cancel_approvals(env: Env) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, CANCEL_APPROVALS_KEY))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Signer or creator: approve cancelling this market under multi-sig mode
+    ///
+    /// Records the approval and emits CancelApproved. The approval that reaches the
+    /// threshold executes the cancel (same effects as `cancel_market`).
+    pub fn approve_cancel(env: Env, approver: Address, market_id: BytesN<32>) {
+        approver.require_auth();
+
+        let (signers, threshold) = Self::get_cancel_multisig(env.clone());
+        if threshold == 0 {
+            panic!("Multi-sig cancellation not enabled");
+        }
+
+        let creator: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CREATOR_KEY))
+            .expect("Market not initialized");
+        if approver != creator && !signers.contains(&approver) {
+            panic!("Unauthorized: not a cancellation signer");
+        }
+
+        let state = Self::require_cancellable_state(&env);
+        Self::record_cancel_approval(&env, &approver, &market_id, state);
+    }
+
+    /// Helper: Panic unless the market can still be cancelled; returns the current state
+    fn require_cancellable_state(env: &Env) -> u32 {
         let state: u32 = env
             .storage()
             .persistent()
-            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .get(&Symbol::new(env, MARKET_STATE_KEY))
             .expect("Market state not found");
 
         if state == STATE_RESOLVED {
@@ -1421,27 +4466,95 @@ impl PredictionMarket {
         if state == STATE_CANCELLED {
             panic!("Market already cancelled");
         }
+        state
+    }
 
-        // Set state to CANCELLED; participants claim refunds via claim_refund (only callable when CANCELLED)
+    /// Helper: Record a multi-sig cancel approval, executing the cancel at quorum
+    fn record_cancel_approval(env: &Env, approver: &Address, market_id: &BytesN<32>, state: u32) {
+        let mut approvals = Self::get_cancel_approvals(env.clone());
+        if approvals.contains(approver) {
+            panic!("Cancellation already approved");
+        }
+        approvals.push_back(approver.clone());
         env.storage()
             .persistent()
-            .set(&Symbol::new(&env, MARKET_STATE_KEY), &STATE_CANCELLED);
+            .set(&Symbol::new(env, CANCEL_APPROVALS_KEY), &approvals);
+
+        let threshold = Self::get_cancel_multisig(env.clone()).1;
+
+        #[contractevent]
+        pub struct CancelApprovedEvent {
+            pub market_id: BytesN<32>,
+            pub approver: Address,
+            pub approvals: u32,
+            pub threshold: u32,
+        }
+
+        CancelApprovedEvent {
+            market_id: market_id.clone(),
+            approver: approver.clone(),
+            approvals: approvals.len(),
+            threshold,
+        }
+        .publish(env);
+
+        if approvals.len() >= threshold {
+            let creator: Address = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(env, CREATOR_KEY))
+                .expect("Market not initialized");
+            Self::execute_cancel(env, creator, market_id.clone(), state);
+        }
+    }
+
+    /// Helper: Cancel the market from `state` once authorization has been settled
+    fn execute_cancel(env: &Env, creator: Address, market_id: BytesN<32>, state: u32) {
+        // Set state to CANCELLED; participants claim refunds via claim_refund (only callable when CANCELLED)
+        Self::set_market_state(env, STATE_CANCELLED);
+
+        // Creator-initiated cancel: the bond is slashed to the treasury
+        Self::settle_creator_bond(env, &market_id, true);
+
+        // An open dispute can no longer be settled; hand the disputer's stake back
+        if state == STATE_DISPUTED {
+            Self::return_dispute_stake(env, &market_id);
+        }
+
+        // Let the oracle release state held for this market; best effort, so an
+        // unreachable oracle never blocks the cancel
+        let oracle: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, ORACLE_KEY))
+            .expect("Oracle address not found");
+        let _ = env.try_invoke_contract::<(), soroban_sdk::Error>(
+            &oracle,
+            &Symbol::new(env, "abandon_market"),
+            (market_id.clone(),).into_val(env),
+        );
 
         let timestamp = env.ledger().timestamp();
+        let mut total_refundable: i128 = 0;
+        for (_, amount) in Self::get_refund_queue(env.clone()).iter() {
+            total_refundable += amount;
+        }
 
         #[contractevent]
         pub struct MarketCancelledEvent {
             pub market_id: BytesN<32>,
             pub creator: Address,
+            pub total_refundable: i128,
             pub timestamp: u64,
         }
 
         MarketCancelledEvent {
             market_id,
             creator,
+            total_refundable,
             timestamp,
         }
-        .publish(&env);
+        .publish(env);
     }
 
     /// Refund committed USDC to a participant. Only callable when market is CANCELLED.
@@ -1449,7 +4562,7 @@ impl PredictionMarket {
     /// - Requires market state is CANCELLED
     /// - Refunds exact committed/revealed amount (from commitment or prediction)
     /// - Tracks refund status to prevent double-refunds
-    /// - Emits RefundedEvent
+    /// - Emits RefundProcessedEvent
     pub fn claim_refund(env: Env, user: Address, market_id: BytesN<32>) {
         user.require_auth();
 
@@ -1476,18 +4589,8 @@ impl PredictionMarket {
         let token_client = token::TokenClient::new(&env, &usdc);
         let contract = env.current_contract_address();
 
-        let amount = if let Some(commitment) = Self::get_commitment(env.clone(), user.clone()) {
-            env.storage()
-                .persistent()
-                .remove(&Self::get_commit_key(&env, &user));
-            commitment.amount
-        } else if let Some(pred) = Self::test_get_prediction(env.clone(), user.clone()) {
-            let pred_key = Self::get_prediction_key(&env, &user);
-            env.storage().persistent().remove(&pred_key);
-            pred.amount
-        } else {
-            panic!("No commitment or prediction found for user");
-        };
+        let amount = Self::get_refundable_amount(&env, &user)
+            .unwrap_or_else(|| panic!("No commitment or prediction found for user"));
 
         if amount <= 0 {
             panic!("No amount to refund");
@@ -1495,15 +4598,101 @@ impl PredictionMarket {
 
         token_client.transfer(&contract, &user, &amount);
 
-        env.storage().persistent().set(&refunded_key, &true);
+        Self::complete_refund(&env, market_id, user, amount);
+    }
 
-        RefundedEvent {
-            user: user.clone(),
-            market_id,
-            amount,
-            timestamp: env.ledger().timestamp(),
+    /// Push a refund to a participant of a cancelled market on their behalf.
+    ///
+    /// Callable by anyone (e.g. an operator sweeping refunds). The transfer is
+    /// attempted with `try_transfer`, so a recipient that cannot receive funds
+    /// (frozen or deauthorized account) does not abort the caller's sweep:
+    /// the failure is recorded and the position stays refundable for a later retry.
+    ///
+    /// Returns true if the refund was paid, false if the transfer failed.
+    pub fn retry_refund(env: Env, market_id: BytesN<32>, user: Address) -> bool {
+        let state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market not initialized");
+
+        if state != STATE_CANCELLED {
+            panic!("Refunds only available for cancelled markets");
         }
-        .publish(&env);
+
+        if env
+            .storage()
+            .persistent()
+            .has(&Self::get_refunded_key(&env, &user))
+        {
+            panic!("Already refunded");
+        }
+
+        let amount = Self::get_refundable_amount(&env, &user)
+            .unwrap_or_else(|| panic!("No commitment or prediction found for user"));
+
+        if amount <= 0 {
+            panic!("No amount to refund");
+        }
+
+        let usdc: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC token not found");
+        let token_client = token::TokenClient::new(&env, &usdc);
+        let contract = env.current_contract_address();
+
+        let paid = matches!(
+            token_client.try_transfer(&contract, &user, &amount),
+            Ok(Ok(()))
+        );
+
+        if !paid {
+            env.storage()
+                .persistent()
+                .set(&Self::get_refund_failed_key(&env, &user), &amount);
+            return false;
+        }
+
+        Self::complete_refund(&env, market_id, user, amount);
+
+        true
+    }
+
+    /// Outstanding refunds as (user, amount), in participant-insertion order
+    ///
+    /// The canonical order for sweeping a cancelled market with `retry_refund`.
+    pub fn get_refund_queue(env: Env) -> Vec<(Address, i128)> {
+        let participants: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, PARTICIPANTS_KEY))
+            .unwrap_or_else(|| Vec::new(&env));
+
+        let mut queue = Vec::new(&env);
+        for user in participants.iter() {
+            if env
+                .storage()
+                .persistent()
+                .has(&Self::get_refunded_key(&env, &user))
+            {
+                continue;
+            }
+            if let Some(amount) = Self::get_refundable_amount(&env, &user) {
+                if amount > 0 {
+                    queue.push_back((user, amount));
+                }
+            }
+        }
+        queue
+    }
+
+    /// Amount of a failed refund push awaiting retry, if any
+    pub fn get_failed_refund(env: Env, user: Address) -> Option<i128> {
+        env.storage()
+            .persistent()
+            .get(&Self::get_refund_failed_key(&env, &user))
     }
 
     // --- TEST HELPERS (Not for production use, but exposed for integration tests) ---
@@ -1559,6 +4748,7 @@ impl PredictionMarket {
         env.storage()
             .persistent()
             .set(&Symbol::new(&env, WINNING_OUTCOME_KEY), &outcome);
+        Self::record_winner_weight(&env);
         env.storage()
             .persistent()
             .set(&Symbol::new(&env, WINNER_SHARES_KEY), &winner_shares);
@@ -1588,97 +4778,7 @@ impl PredictionMarket {
         limit: u32,
         users: Vec<Address>,
     ) -> Vec<(Address, i128)> {
-        // Validate market state is RESOLVED
-        let state: u32 = env
-            .storage()
-            .persistent()
-            .get(&Symbol::new(&env, MARKET_STATE_KEY))
-            .expect("Market not initialized");
-
-        if state != STATE_RESOLVED {
-            panic!("Market not resolved");
-        }
-
-        if limit == 0 {
-            return Vec::new(&env);
-        }
-
-        let winning_outcome: u32 = env
-            .storage()
-            .persistent()
-            .get(&Symbol::new(&env, WINNING_OUTCOME_KEY))
-            .expect("Winning outcome not found");
-
-        let winner_shares: i128 = env
-            .storage()
-            .persistent()
-            .get(&Symbol::new(&env, WINNER_SHARES_KEY))
-            .expect("Winner shares not found");
-
-        let loser_shares: i128 = env
-            .storage()
-            .persistent()
-            .get(&Symbol::new(&env, LOSER_SHARES_KEY))
-            .unwrap_or(0);
-
-        let total_pool = winner_shares + loser_shares;
-
-        if winner_shares == 0 {
-            return Vec::new(&env);
-        }
-
-        // Collect winners from provided user list
-        let mut winners: Vec<(Address, i128)> = Vec::new(&env);
-
-        for i in 0..users.len() {
-            let user = users.get(i).unwrap();
-            let prediction_key = (Symbol::new(&env, PREDICTION_PREFIX), user.clone());
-
-            if let Some(prediction) = env
-                .storage()
-                .persistent()
-                .get::<_, UserPrediction>(&prediction_key)
-            {
-                if prediction.outcome == winning_outcome {
-                    let gross_payout = prediction
-                        .amount
-                        .checked_mul(total_pool)
-                        .expect("Overflow in payout calculation")
-                        .checked_div(winner_shares)
-                        .expect("Division by zero in payout calculation");
-                    let fee = gross_payout / 10;
-                    let net_payout = gross_payout - fee;
-                    winners.push_back((user, net_payout));
-                }
-            }
-        }
-
-        // Sort by payout descending
-        let len = winners.len();
-        if len > 1 {
-            for i in 0..len {
-                for j in 0..(len - i - 1) {
-                    let current = winners.get(j).unwrap();
-                    let next = winners.get(j + 1).unwrap();
-
-                    if current.1 < next.1 {
-                        let temp = current.clone();
-                        winners.set(j, next);
-                        winners.set(j + 1, temp);
-                    }
-                }
-            }
-        }
-
-        // Return top N
-        let result_len = if limit < len { limit } else { len };
-        let mut result: Vec<(Address, i128)> = Vec::new(&env);
-
-        for i in 0..result_len {
-            result.push_back(winners.get(i).unwrap());
-        }
-
-        result
+        Self::collect_leaderboard(&env, users, limit)
     }
 }
 
@@ -1686,8 +4786,8 @@ impl PredictionMarket {
 mod tests {
     use super::*;
     use soroban_sdk::{
-        testutils::{Address as _, Ledger},
-        Address, BytesN, Env,
+        testutils::{Address as _, Events, Ledger},
+        Address, BytesN, Env, Map, TryFromVal, Val,
     };
 
     // Mock Oracle for testing
@@ -1731,6 +4831,32 @@ mod tests {
                 .instance()
                 .set(&Symbol::new(&env, "outcome"), &outcome);
         }
+
+        pub fn has_active_challenge(env: Env, _market_id: BytesN<32>) -> bool {
+            env.storage()
+                .instance()
+                .get(&Symbol::new(&env, "challenged"))
+                .unwrap_or(false)
+        }
+
+        pub fn set_active_challenge(env: Env, challenged: bool) {
+            env.storage()
+                .instance()
+                .set(&Symbol::new(&env, "challenged"), &challenged);
+        }
+
+        pub fn is_consensus_deadline_missed(env: Env, _market_id: BytesN<32>) -> bool {
+            env.storage()
+                .instance()
+                .get(&Symbol::new(&env, "deadline_missed"))
+                .unwrap_or(false)
+        }
+
+        pub fn set_deadline_missed(env: Env, missed: bool) {
+            env.storage()
+                .instance()
+                .set(&Symbol::new(&env, "deadline_missed"), &missed);
+        }
     }
 
     // Helper to create token contract for tests
@@ -1770,6 +4896,9 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         // Mint USDC to contract to simulate pot
@@ -1790,7 +4919,9 @@ mod tests {
         );
 
         // Claim
+        assert!(!market_client.has_claimed(&user, &market_id_bytes));
         let payout = market_client.claim_winnings(&user, &market_id_bytes);
+        assert!(market_client.has_claimed(&user, &market_id_bytes));
 
         // Expect 900 (1000 - 10% fee)
         assert_eq!(payout, 900);
@@ -1799,6 +4930,292 @@ mod tests {
         assert_eq!(usdc_client.balance(&user), 900);
     }
 
+    #[test]
+    #[should_panic(expected = "Unauthorized: only keeper or oracle")]
+    fn test_close_market_rejects_non_keeper() {
+        let (env, market_id, market_client, _usdc_client, user) = setup_reveal_test();
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        market_client.close_market(&user, &market_id);
+    }
+
+    #[test]
+    fn test_reopen_mistakenly_closed_market_resumes_commits() {
+        let (env, market_id, market_client, usdc_client, user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        let oracle = stored_oracle(&env, &market_client).address;
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        market_client.close_market(&oracle, &market_id);
+        let hash = BytesN::from_array(&env, &[1u8; 32]);
+        assert_eq!(
+            market_client.try_commit_prediction(&user, &hash, &100),
+            Err(Ok(MarketError::InvalidMarketState))
+        );
+
+        // Reopening past the old closing time pushes it out so commits land again
+        env.ledger().with_mut(|li| li.timestamp = 2100);
+        market_client.reopen_market(&creator, &market_id, &2500);
+        assert_eq!(last_state_change(&env), (STATE_CLOSED, STATE_OPEN));
+        assert_eq!(market_client.get_market_state_value(), Some(STATE_OPEN));
+
+        market_client.commit_prediction(&user, &hash, &100);
+        let other = Address::generate(&env);
+        usdc_client.mint(&other, &100);
+        market_client.commit_prediction(&other, &BytesN::from_array(&env, &[2u8; 32]), &100);
+        assert_eq!(market_client.get_pending_count(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "New closing time must be in the future and before resolution")]
+    fn test_reopen_rejects_closing_time_at_resolution() {
+        let (env, market_id, market_client, _usdc_client, _user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        let oracle = stored_oracle(&env, &market_client).address;
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        market_client.close_market(&oracle, &market_id);
+        market_client.reopen_market(&creator, &market_id, &3000);
+    }
+
+    #[test]
+    fn test_keeper_can_reopen_within_grace() {
+        let (env, market_id, market_client, _usdc_client, _user) = setup_reveal_test();
+        let keeper = Address::generate(&env);
+        market_client.set_keeper(&keeper, &true);
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        market_client.close_market(&keeper, &market_id);
+
+        env.ledger().with_mut(|li| li.timestamp = 2500);
+        market_client.reopen_market(&keeper, &market_id, &2900);
+        assert_eq!(market_client.get_market_state_value(), Some(STATE_OPEN));
+    }
+
+    #[test]
+    #[should_panic(expected = "Reopen grace period has passed")]
+    fn test_reopen_rejected_after_grace() {
+        let (env, market_id, market_client, _usdc_client, _user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        let oracle = stored_oracle(&env, &market_client).address;
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        market_client.close_market(&oracle, &market_id);
+
+        env.ledger()
+            .with_mut(|li| li.timestamp = 2000 + REOPEN_GRACE_PERIOD + 1);
+        market_client.reopen_market(&creator, &market_id, &2900);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only creator or keeper")]
+    fn test_reopen_rejects_non_creator() {
+        let (env, market_id, market_client, _usdc_client, user) = setup_reveal_test();
+        let oracle = stored_oracle(&env, &market_client).address;
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        market_client.close_market(&oracle, &market_id);
+        market_client.reopen_market(&user, &market_id, &2500);
+    }
+
+    #[test]
+    fn test_resolution_bounty_paid_once_per_trigger() {
+        let (env, market_id, market_client, usdc_client, user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        let keeper = Address::generate(&env);
+        market_client.set_keeper(&keeper, &true);
+        market_client.set_resolution_bounty(&creator, &40);
+
+        let salt = BytesN::from_array(&env, &[5; 32]);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, 1, &salt);
+        market_client.commit_prediction(&user, &commit_hash, &1000);
+        next_ledger(&env);
+        market_client.reveal_prediction(&user, &market_id, &1, &1000, &salt);
+
+        let token = token::TokenClient::new(&env, &usdc_client.address);
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        market_client.close_market(&keeper, &market_id);
+        assert_eq!(token.balance(&keeper), 40);
+        assert!(market_client.is_bounty_paid(&Symbol::new(&env, "close")));
+
+        // Closing again after a reopen does not pay a second time
+        market_client.reopen_market(&creator, &market_id, &2500);
+        env.ledger().with_mut(|li| li.timestamp = 2500);
+        market_client.close_market(&keeper, &market_id);
+        assert_eq!(token.balance(&keeper), 40);
+
+        // The resolution bounty is capped by what is left of the 100 fee budget
+        market_client.set_resolution_bounty(&creator, &80);
+        env.ledger().with_mut(|li| li.timestamp = 3000);
+        market_client.resolve_market(&keeper, &market_id);
+        assert_eq!(token.balance(&keeper), 100);
+        assert_eq!(market_client.get_bounties_paid(), 100);
+        assert!(market_client.is_bounty_paid(&Symbol::new(&env, "resolve")));
+        assert!(market_client
+            .try_resolve_market(&keeper, &market_id)
+            .is_err());
+        assert_eq!(token.balance(&keeper), 100);
+    }
+
+    #[test]
+    fn test_resolution_bounty_not_paid_without_fee_budget() {
+        let (env, market_id, market_client, usdc_client, _user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        let keeper = Address::generate(&env);
+        market_client.set_keeper(&keeper, &true);
+        market_client.set_resolution_bounty(&creator, &40);
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        market_client.close_market(&keeper, &market_id);
+
+        let token = token::TokenClient::new(&env, &usdc_client.address);
+        assert_eq!(token.balance(&keeper), 0);
+        assert!(!market_client.is_bounty_paid(&Symbol::new(&env, "close")));
+    }
+
+    #[test]
+    fn test_resolution_bounty_budgeted_at_deepest_fee_discount() {
+        let (env, market_id, market_client, usdc_client, user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        let keeper = Address::generate(&env);
+        market_client.set_keeper(&keeper, &true);
+        market_client.set_resolution_bounty(&creator, &80);
+        let tiers = Vec::from_array(
+            &env,
+            [FeeDiscountTier {
+                min_balance: 1,
+                discount_bps: 5000,
+            }],
+        );
+        market_client.set_fee_discount(&creator, &Some(Address::generate(&env)), &tiers);
+
+        let salt = BytesN::from_array(&env, &[5; 32]);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, 1, &salt);
+        market_client.commit_prediction(&user, &commit_hash, &1000);
+        next_ledger(&env);
+        market_client.reveal_prediction(&user, &market_id, &1, &1000, &salt);
+
+        // Half the 10% fee may be discounted away, so only 50 can be advanced
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        market_client.close_market(&keeper, &market_id);
+        let token = token::TokenClient::new(&env, &usdc_client.address);
+        assert_eq!(token.balance(&keeper), 50);
+    }
+
+    #[test]
+    #[should_panic(expected = "Fee parameters are frozen once the market closes")]
+    fn test_fee_holiday_cannot_be_set_after_close() {
+        let (env, market_id, market_client, _usdc_client, _user) = setup_reveal_test();
+        let oracle = stored_oracle(&env, &market_client).address;
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        market_client.close_market(&oracle, &market_id);
+        market_client.set_fee_free_until(&stored_creator(&env, &market_client), &5000);
+    }
+
+    #[test]
+    fn test_removed_keeper_cannot_resolve() {
+        let (env, market_id, market_client, _usdc_client, _user) = setup_reveal_test();
+        let keeper = Address::generate(&env);
+        market_client.set_keeper(&keeper, &true);
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        market_client.close_market(&keeper, &market_id);
+
+        market_client.set_keeper(&keeper, &false);
+        env.ledger().with_mut(|li| li.timestamp = 3000);
+        assert!(market_client
+            .try_resolve_market(&keeper, &market_id)
+            .is_err());
+    }
+
+    #[test]
+    fn test_close_market_survives_failing_amm_settlement() {
+        let (env, market_id, market_client, _usdc_client, user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+
+        market_client.test_add_participant(&user);
+        market_client.test_set_prediction(&user, &1u32, &600i128);
+        market_client.recompute_pools(&creator, &market_id);
+
+        // Not an AMM contract at all: settlement fails, the close still goes through
+        market_client.configure_amm_close_settlement(&creator, &Address::generate(&env), &true);
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        let oracle = stored_oracle(&env, &market_client).address;
+        market_client.close_market(&oracle, &market_id);
+        assert_eq!(market_client.get_market_state_value(), Some(STATE_CLOSED));
+    }
+
+    #[test]
+    fn test_tie_resolution_refunds_both_sides_minus_fee() {
+        let (env, market_id, market_client, usdc_client, _user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        market_client.set_split_on_tie(&creator, &true);
+
+        let (yes_user, no_user) = (Address::generate(&env), Address::generate(&env));
+        market_client.test_add_participant(&yes_user);
+        market_client.test_set_prediction(&yes_user, &1u32, &600i128);
+        market_client.test_add_participant(&no_user);
+        market_client.test_set_prediction(&no_user, &0u32, &400i128);
+        market_client.recompute_pools(&creator, &market_id);
+        usdc_client.mint(&market_client.address, &1000);
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        let oracle = stored_oracle(&env, &market_client);
+        market_client.close_market(&oracle.address, &market_id);
+        env.ledger().with_mut(|li| li.timestamp = 3000);
+        oracle.set_deadline_missed(&true);
+        market_client.resolve_market_as_tie(&oracle.address, &market_id);
+
+        assert_eq!(market_client.test_get_winning_outcome(), Some(OUTCOME_TIE));
+        // Both sides are owed their stake back
+        assert_eq!(market_client.get_liabilities(), 1000);
+        assert_eq!(market_client.claim_winnings(&yes_user, &market_id), 540);
+        assert_eq!(market_client.get_liabilities(), 400);
+        assert_eq!(market_client.claim_winnings(&no_user, &market_id), 360);
+        assert_eq!(market_client.get_liabilities(), 0);
+        assert_eq!(usdc_client.balance(&market_client.address), 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "Tie resolution not enabled")]
+    fn test_tie_resolution_requires_split_mode() {
+        let (env, market_id, market_client, _usdc_client, _user) = setup_reveal_test();
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        let oracle = stored_oracle(&env, &market_client);
+        market_client.close_market(&oracle.address, &market_id);
+        env.ledger().with_mut(|li| li.timestamp = 3000);
+        oracle.set_deadline_missed(&true);
+        market_client.resolve_market_as_tie(&oracle.address, &market_id);
+    }
+
+    #[test]
+    fn test_tie_resolution_requires_missed_deadline_and_keeper_or_oracle() {
+        let (env, market_id, market_client, _usdc_client, _user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        market_client.set_split_on_tie(&creator, &true);
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        let oracle = stored_oracle(&env, &market_client);
+        market_client.close_market(&oracle.address, &market_id);
+        env.ledger().with_mut(|li| li.timestamp = 3000);
+
+        // Consensus may still form, so not even the oracle can call a tie yet
+        assert!(market_client
+            .try_resolve_market_as_tie(&oracle.address, &market_id)
+            .is_err());
+
+        // Past the deadline the creator still cannot declare a tie on their own
+        oracle.set_deadline_missed(&true);
+        assert!(market_client
+            .try_resolve_market_as_tie(&creator, &market_id)
+            .is_err());
+
+        market_client.resolve_market_as_tie(&oracle.address, &market_id);
+        assert_eq!(market_client.test_get_winning_outcome(), Some(OUTCOME_TIE));
+    }
+
     #[test]
     #[should_panic(expected = "User did not predict winning outcome")]
     fn test_claim_winnings_loser_cannot_claim() {
@@ -1820,6 +5237,9 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &1000);
@@ -1852,6 +5272,9 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         let user = Address::generate(&env);
@@ -1882,6 +5305,9 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
         usdc_client.mint(&market_contract_id, &2000);
 
@@ -1914,6 +5340,9 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         // Total pool: 1000 (winners) + 500 (losers) = 1500
@@ -1952,6 +5381,9 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         // Total pool: 1000 (winners) + 1000 (losers) = 2000
@@ -1995,6 +5427,9 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &0);
@@ -2042,6 +5477,9 @@ mod tests {
             &oracle_contract_id,
             &closing_time,
             &resolution_time,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         // Advance time to closing
@@ -2050,7 +5488,7 @@ mod tests {
         });
 
         // Close market
-        market_client.close_market(&market_id_bytes);
+        market_client.close_market(&oracle_contract_id, &market_id_bytes);
 
         // Advance time to resolution
         env.ledger().with_mut(|li| {
@@ -2058,7 +5496,7 @@ mod tests {
         });
 
         // Resolve market
-        market_client.resolve_market(&market_id_bytes);
+        market_client.resolve_market(&oracle_contract_id, &market_id_bytes);
     }
 
     #[test]
@@ -2081,20 +5519,306 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         env.ledger().with_mut(|li| {
             li.timestamp = 2010;
         });
-        market_client.close_market(&market_id_bytes);
+        market_client.close_market(&oracle_contract_id, &market_id_bytes);
 
         env.ledger().with_mut(|li| {
             li.timestamp = 3010;
         });
-        market_client.resolve_market(&market_id_bytes);
+        market_client.resolve_market(&oracle_contract_id, &market_id_bytes);
 
         // Second call should panic
-        market_client.resolve_market(&market_id_bytes);
+        market_client.resolve_market(&oracle_contract_id, &market_id_bytes);
+    }
+
+    /// Helper: Reveal 300 on YES and 200 on NO, then close past resolution time
+    fn setup_split_pools_for_resolution() -> (
+        Env,
+        BytesN<32>,
+        PredictionMarketClient<'static>,
+        MockOracleClient<'static>,
+    ) {
+        let (env, market_id, market_client, usdc_client, yes_user) = setup_reveal_test();
+        let no_user = Address::generate(&env);
+        usdc_client.mint(&no_user, &1_000);
+
+        let yes_salt = BytesN::from_array(&env, &[1; 32]);
+        let no_salt = BytesN::from_array(&env, &[2; 32]);
+        market_client.commit_prediction(
+            &yes_user,
+            &compute_commit_hash(&env, &market_id, &yes_user, 1, &yes_salt),
+            &300,
+        );
+        market_client.commit_prediction(
+            &no_user,
+            &compute_commit_hash(&env, &market_id, &no_user, 0, &no_salt),
+            &200,
+        );
+        next_ledger(&env);
+        market_client.reveal_prediction(&yes_user, &market_id, &1, &300, &yes_salt);
+        market_client.reveal_prediction(&no_user, &market_id, &0, &200, &no_salt);
+
+        let oracle = stored_oracle(&env, &market_client);
+        env.ledger().with_mut(|li| li.timestamp = 3000);
+        market_client.close_market(&oracle.address, &market_id);
+
+        (env, market_id, market_client, oracle)
+    }
+
+    #[test]
+    fn test_resolve_market_uses_oracle_no_consensus() {
+        let (_env, market_id, market_client, oracle) = setup_split_pools_for_resolution();
+
+        oracle.set_outcome_value(&0);
+        market_client.resolve_market(&oracle.address, &market_id);
+
+        // NO won: the 200 NO pool is the winning side, the 300 YES pool the losing side
+        let (winner_shares, loser_shares, _, _) = market_client.get_resolution_stats(&market_id);
+        assert_eq!((winner_shares, loser_shares), (200, 300));
+        assert_eq!(
+            market_client.get_market_state(&market_id).winning_outcome,
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_add_to_commitment_accumulates_revealed_amount() {
+        let (env, market_id, market_client, usdc_client, user) = setup_reveal_test();
+        let token = token::TokenClient::new(&env, &usdc_client.address);
+        let salt = BytesN::from_array(&env, &[5; 32]);
+        market_client.commit_prediction(
+            &user,
+            &compute_commit_hash(&env, &market_id, &user, 1, &salt),
+            &300,
+        );
+
+        market_client.add_to_commitment(&user, &200);
+        assert_eq!(market_client.get_committed_amount(&user), Some(500));
+        assert_eq!(token.balance(&market_client.address), 500);
+        assert_eq!(market_client.get_pending_count(), 1);
+        assert_eq!(
+            market_client.try_add_to_commitment(&user, &0),
+            Err(Ok(MarketError::InvalidAmount))
+        );
+
+        // Only the accumulated amount reveals
+        next_ledger(&env);
+        assert_eq!(
+            market_client.try_reveal_prediction(&user, &market_id, &1, &300, &salt),
+            Err(Ok(MarketError::AmountMismatch))
+        );
+        market_client.reveal_prediction(&user, &market_id, &1, &500, &salt);
+
+        let summary = market_client.get_market_summary(&market_id);
+        assert_eq!(summary.yes_pool, 500);
+        assert_eq!(summary.total_volume, 500);
+        assert_eq!(
+            market_client.try_add_to_commitment(&user, &100),
+            Err(Ok(MarketError::DuplicateReveal))
+        );
+        assert_eq!(
+            market_client.try_add_to_commitment(&Address::generate(&env), &100),
+            Err(Ok(MarketError::NoPrediction))
+        );
+    }
+
+    #[test]
+    fn test_withdraw_commitment_refunds_and_clears_position() {
+        let (env, market_id, market_client, usdc_client, user) = setup_reveal_test();
+        let token = token::TokenClient::new(&env, &usdc_client.address);
+        let before = token.balance(&user);
+
+        market_client.commit_prediction(&user, &BytesN::from_array(&env, &[1; 32]), &300);
+        assert_eq!(market_client.get_pending_count(), 1);
+
+        market_client.withdraw_commitment(&user);
+        let withdrawn = last_event_fields(&env, "commitment_withdrawn_event");
+        let amount =
+            i128::try_from_val(&env, &withdrawn.get(Symbol::new(&env, "amount")).unwrap()).unwrap();
+        assert_eq!(amount, 300);
+
+        assert_eq!(token.balance(&user), before);
+        assert_eq!(market_client.get_escrow_balance(), 0);
+        assert_eq!(market_client.get_pending_count(), 0);
+        assert_eq!(market_client.get_refund_queue().len(), 0);
+        assert!(!market_client.verify_reveal(
+            &user,
+            &market_id,
+            &1,
+            &300,
+            &BytesN::from_array(&env, &[1; 32])
+        ));
+
+        // Double withdraw finds no commitment
+        assert_eq!(
+            market_client.try_withdraw_commitment(&user),
+            Err(Ok(MarketError::NoPrediction))
+        );
+    }
+
+    #[test]
+    fn test_withdraw_commitment_rejected_after_reveal() {
+        let (env, market_id, market_client, _usdc_client, user) = setup_reveal_test();
+        let salt = BytesN::from_array(&env, &[4; 32]);
+        market_client.commit_prediction(
+            &user,
+            &compute_commit_hash(&env, &market_id, &user, 1, &salt),
+            &300,
+        );
+        next_ledger(&env);
+        market_client.reveal_prediction(&user, &market_id, &1, &300, &salt);
+
+        assert_eq!(
+            market_client.try_withdraw_commitment(&user),
+            Err(Ok(MarketError::DuplicateReveal))
+        );
+        assert_eq!(market_client.get_escrow_balance(), 300);
+    }
+
+    #[test]
+    fn test_withdrawn_commitment_does_not_count_against_reveal_ratio() {
+        let (env, market_id, market_client, usdc_client, user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        market_client.set_min_reveal_ratio(&creator, &10000);
+
+        let salt = BytesN::from_array(&env, &[4; 32]);
+        market_client.commit_prediction(
+            &user,
+            &compute_commit_hash(&env, &market_id, &user, 1, &salt),
+            &100,
+        );
+        let quitter = Address::generate(&env);
+        usdc_client.mint(&quitter, &1_000);
+        market_client.commit_prediction(&quitter, &BytesN::from_array(&env, &[7; 32]), &100);
+        assert_eq!(market_client.get_reveal_counts(), (0, 2));
+
+        market_client.withdraw_commitment(&quitter);
+        assert_eq!(market_client.get_reveal_counts(), (0, 1));
+
+        next_ledger(&env);
+        market_client.reveal_prediction(&user, &market_id, &1, &100, &salt);
+        assert_eq!(market_client.get_reveal_counts(), (1, 1));
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        let oracle = stored_oracle(&env, &market_client).address;
+        market_client.close_market(&oracle, &market_id);
+        env.ledger().with_mut(|li| li.timestamp = 3000);
+
+        market_client.resolve_market(&oracle, &market_id);
+        assert_eq!(market_client.get_market_state_value(), Some(STATE_RESOLVED));
+    }
+
+    #[test]
+    fn test_commit_hash_binds_committer_address() {
+        let (env, market_id, market_client, usdc_client, first) = setup_reveal_test();
+        let second = Address::generate(&env);
+        usdc_client.mint(&second, &1_000);
+        let salt = BytesN::from_array(&env, &[9; 32]);
+
+        // Same outcome and salt, different committers: different hashes
+        let first_hash = compute_commit_hash(&env, &market_id, &first, 1, &salt);
+        let second_hash = compute_commit_hash(&env, &market_id, &second, 1, &salt);
+        assert_ne!(first_hash, second_hash);
+
+        market_client.commit_prediction(&first, &first_hash, &300);
+        market_client.commit_prediction(&second, &second_hash, &300);
+
+        // Both commitments coexist and each reveals with the shared outcome/salt
+        next_ledger(&env);
+        market_client.reveal_prediction(&first, &market_id, &1, &300, &salt);
+        market_client.reveal_prediction(&second, &market_id, &1, &300, &salt);
+        assert_eq!(market_client.get_market_summary(&market_id).yes_pool, 600);
+    }
+
+    #[test]
+    fn test_verify_reveal_matches_stored_commitment() {
+        let (env, market_id, market_client, _usdc_client, user) = setup_reveal_test();
+        let salt = BytesN::from_array(&env, &[7; 32]);
+        let wrong_salt = BytesN::from_array(&env, &[8; 32]);
+
+        // Nothing committed yet
+        assert!(!market_client.verify_reveal(&user, &market_id, &1, &300, &salt));
+
+        market_client.commit_prediction(
+            &user,
+            &compute_commit_hash(&env, &market_id, &user, 1, &salt),
+            &300,
+        );
+
+        assert!(market_client.verify_reveal(&user, &market_id, &1, &300, &salt));
+        assert!(!market_client.verify_reveal(&user, &market_id, &1, &300, &wrong_salt));
+        assert!(!market_client.verify_reveal(&user, &market_id, &0, &300, &salt));
+        assert!(!market_client.verify_reveal(&user, &market_id, &2, &300, &salt));
+        assert!(!market_client.verify_reveal(&user, &market_id, &1, &299, &salt));
+
+        // Read-only: the commitment is still pending and the real reveal goes through
+        assert_eq!(market_client.get_pending_count(), 1);
+        next_ledger(&env);
+        market_client.reveal_prediction(&user, &market_id, &1, &300, &salt);
+        assert!(!market_client.verify_reveal(&user, &market_id, &1, &300, &salt));
+    }
+
+    #[test]
+    fn test_get_market_leaderboard_after_full_flow() {
+        let (env, market_id, market_client, usdc_client, big_yes) = setup_reveal_test();
+        let small_yes = Address::generate(&env);
+        let no_user = Address::generate(&env);
+        usdc_client.mint(&small_yes, &1_000);
+        usdc_client.mint(&no_user, &1_000);
+
+        let bets = [
+            (&big_yes, 1u32, 300i128),
+            (&small_yes, 1, 100),
+            (&no_user, 0, 200),
+        ];
+        for (i, (user, outcome, amount)) in bets.iter().enumerate() {
+            let salt = BytesN::from_array(&env, &[i as u8 + 1; 32]);
+            market_client.commit_prediction(
+                user,
+                &compute_commit_hash(&env, &market_id, user, *outcome, &salt),
+                amount,
+            );
+        }
+        next_ledger(&env);
+        for (i, (user, outcome, amount)) in bets.iter().enumerate() {
+            let salt = BytesN::from_array(&env, &[i as u8 + 1; 32]);
+            market_client.reveal_prediction(user, &market_id, outcome, amount, &salt);
+        }
+
+        let oracle = stored_oracle(&env, &market_client);
+        env.ledger().with_mut(|li| li.timestamp = 3000);
+        market_client.close_market(&oracle.address, &market_id);
+        market_client.resolve_market(&oracle.address, &market_id);
+
+        // YES wins the 600 pool: 300/400 -> 450 - 45 = 405, 100/400 -> 150 - 15 = 135
+        assert_eq!(
+            market_client.get_market_leaderboard(&market_id, &10),
+            Vec::from_array(&env, [(big_yes.clone(), 405), (small_yes, 135)])
+        );
+        assert_eq!(
+            market_client.get_market_leaderboard(&market_id, &1),
+            Vec::from_array(&env, [(big_yes, 405)])
+        );
+        assert_eq!(
+            market_client.get_market_leaderboard(&market_id, &0).len(),
+            0
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Oracle consensus not reached")]
+    fn test_resolve_market_without_oracle_consensus_panics() {
+        let (_env, market_id, market_client, oracle) = setup_split_pools_for_resolution();
+
+        oracle.set_consensus_status(&false);
+        market_client.resolve_market(&oracle.address, &market_id);
     }
 
     #[test]
@@ -2120,6 +5844,9 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &resolution_time,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         // Advance time but NOT enough
@@ -2127,7 +5854,7 @@ mod tests {
             li.timestamp = resolution_time - 10;
         });
 
-        market_client.resolve_market(&market_id_bytes);
+        market_client.resolve_market(&oracle_contract_id, &market_id_bytes);
     }
 
     // ============================================================================
@@ -2135,21 +5862,52 @@ mod tests {
     // ============================================================================
 
     /// Helper: Compute the same commit hash that reveal_prediction reconstructs
-    /// Hash = sha256(market_id || outcome_be_bytes || salt)
+    /// Hash = sha256(market_id || user_xdr || outcome_be_bytes || salt)
     fn compute_commit_hash(
         env: &Env,
         market_id: &BytesN<32>,
+        user: &Address,
         outcome: u32,
         salt: &BytesN<32>,
     ) -> BytesN<32> {
         let mut preimage = soroban_sdk::Bytes::new(env);
         preimage.extend_from_array(&market_id.to_array());
+        preimage.append(&user.clone().to_xdr(env));
         preimage.extend_from_array(&outcome.to_be_bytes());
         preimage.extend_from_array(&salt.to_array());
         let hash = env.crypto().sha256(&preimage);
         BytesN::from_array(env, &hash.to_array())
     }
 
+    /// Helper: Read the market creator straight from contract storage
+    fn stored_creator(env: &Env, market_client: &PredictionMarketClient) -> Address {
+        env.as_contract(&market_client.address, || {
+            env.storage()
+                .persistent()
+                .get(&Symbol::new(env, CREATOR_KEY))
+                .unwrap()
+        })
+    }
+
+    /// Helper: Client for the MockOracle a market was initialized with
+    fn stored_oracle(
+        env: &Env,
+        market_client: &PredictionMarketClient,
+    ) -> MockOracleClient<'static> {
+        let oracle: Address = env.as_contract(&market_client.address, || {
+            env.storage()
+                .persistent()
+                .get(&Symbol::new(env, ORACLE_KEY))
+                .unwrap()
+        });
+        MockOracleClient::new(env, &oracle)
+    }
+
+    /// Helper: Move to the next ledger so a reveal doesn't share its commit's sequence
+    fn next_ledger(env: &Env) {
+        env.ledger().with_mut(|li| li.sequence_number += 1);
+    }
+
     /// Setup helper for reveal tests: creates env, market, token, and returns all needed objects
     fn setup_reveal_test() -> (
         Env,
@@ -2187,6 +5945,9 @@ mod tests {
             &oracle_contract_id,
             &closing_time,
             &resolution_time,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         let user = Address::generate(&env);
@@ -2205,7 +5966,7 @@ mod tests {
         let amount = 500i128;
 
         // Compute the commit hash the same way the contract will
-        let commit_hash = compute_commit_hash(&env, &market_id, outcome, &salt);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, outcome, &salt);
 
         // Phase 1: Commit
         market_client.commit_prediction(&user, &commit_hash, &amount);
@@ -2218,6 +5979,7 @@ mod tests {
         // Phase 2: Reveal
         env.ledger().with_mut(|li| {
             li.timestamp = 1000; // Still before closing_time (2000)
+            li.sequence_number += 1;
         });
 
         market_client.reveal_prediction(&user, &market_id, &outcome, &amount, &salt);
@@ -2230,57 +5992,422 @@ mod tests {
         assert_eq!(pred.amount, 500);
         assert!(!pred.claimed);
 
-        // Verify commitment removed
-        let commitment_after = market_client.get_commitment(&user);
-        assert!(commitment_after.is_none());
+        // Verify commitment removed
+        let commitment_after = market_client.get_commitment(&user);
+        assert!(commitment_after.is_none());
+
+        // Verify pending count decremented
+        assert_eq!(market_client.get_pending_count(), 0);
+    }
+
+    #[test]
+    fn test_escrow_balance_tracks_committed_funds() {
+        let (env, market_id, market_client, usdc_client, user) = setup_reveal_test();
+
+        assert_eq!(market_client.get_settlement_token(), usdc_client.address);
+        assert_eq!(market_client.get_escrow_balance(), 0);
+
+        let salt = BytesN::from_array(&env, &[5; 32]);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, 1u32, &salt);
+        market_client.commit_prediction(&user, &commit_hash, &700i128);
+
+        assert_eq!(market_client.get_escrow_balance(), 700);
+    }
+
+    #[test]
+    fn test_reveal_prediction_updates_yes_pool() {
+        let (env, market_id, market_client, _usdc_client, user) = setup_reveal_test();
+
+        let salt = BytesN::from_array(&env, &[1; 32]);
+        let outcome = 1u32; // YES
+        let amount = 300i128;
+
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, outcome, &salt);
+        market_client.commit_prediction(&user, &commit_hash, &amount);
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = 1000;
+            li.sequence_number += 1;
+        });
+
+        market_client.reveal_prediction(&user, &market_id, &outcome, &amount, &salt);
+
+        // Verify YES pool updated (read from test helper prediction)
+        let prediction = market_client.test_get_prediction(&user).unwrap();
+        assert_eq!(prediction.outcome, 1);
+        assert_eq!(prediction.amount, 300);
+    }
+
+    #[test]
+    fn test_reveal_prediction_updates_no_pool() {
+        let (env, market_id, market_client, _usdc_client, user) = setup_reveal_test();
+
+        let salt = BytesN::from_array(&env, &[2; 32]);
+        let outcome = 0u32; // NO
+        let amount = 200i128;
+
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, outcome, &salt);
+        market_client.commit_prediction(&user, &commit_hash, &amount);
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = 1000;
+            li.sequence_number += 1;
+        });
+
+        market_client.reveal_prediction(&user, &market_id, &outcome, &amount, &salt);
+
+        let prediction = market_client.test_get_prediction(&user).unwrap();
+        assert_eq!(prediction.outcome, 0);
+        assert_eq!(prediction.amount, 200);
+    }
+
+    #[test]
+    fn test_resolve_blocked_when_most_commits_unrevealed() {
+        let (env, market_id, market_client, usdc_client, user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        market_client.set_min_reveal_ratio(&creator, &5000);
+
+        let salt = BytesN::from_array(&env, &[4; 32]);
+        market_client.commit_prediction(
+            &user,
+            &compute_commit_hash(&env, &market_id, &user, 1, &salt),
+            &100,
+        );
+        for i in 0..3u8 {
+            let silent = Address::generate(&env);
+            usdc_client.mint(&silent, &1_000);
+            let silent_salt = BytesN::from_array(&env, &[10 + i; 32]);
+            market_client.commit_prediction(
+                &silent,
+                &compute_commit_hash(&env, &market_id, &silent, 0, &silent_salt),
+                &100,
+            );
+        }
+
+        next_ledger(&env);
+        market_client.reveal_prediction(&user, &market_id, &1, &100, &salt);
+        assert_eq!(market_client.get_reveal_counts(), (1, 4));
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        let oracle = stored_oracle(&env, &market_client).address;
+        market_client.close_market(&oracle, &market_id);
+        env.ledger().with_mut(|li| li.timestamp = 3000);
+
+        let (ready, reason) = market_client.can_resolve_detailed(&market_id);
+        assert!(!ready);
+        assert_eq!(reason, Symbol::new(&env, "insufficient_reveals"));
+        assert!(market_client
+            .try_resolve_market(&oracle, &market_id)
+            .is_err());
+    }
+
+    #[test]
+    fn test_time_remaining_before_close() {
+        let (_env, market_id, market_client, _usdc_client, _user) = setup_reveal_test();
+        // Ledger at 500, closes at 2000, resolves at 3000
+        assert_eq!(market_client.get_time_remaining(&market_id), (1500, 2500));
+    }
+
+    #[test]
+    fn test_time_remaining_between_close_and_resolution() {
+        let (env, market_id, market_client, _usdc_client, _user) = setup_reveal_test();
+        env.ledger().with_mut(|li| li.timestamp = 2400);
+        assert_eq!(market_client.get_time_remaining(&market_id), (-400, 600));
+    }
+
+    #[test]
+    fn test_time_remaining_after_resolution() {
+        let (env, market_id, market_client, _usdc_client, _user) = setup_reveal_test();
+        env.ledger().with_mut(|li| li.timestamp = 3100);
+        assert_eq!(market_client.get_time_remaining(&market_id), (-1100, -100));
+    }
+
+    #[test]
+    fn test_project_final_odds_blends_revealed_and_pending() {
+        let (env, market_id, market_client, usdc_client, user) = setup_reveal_test();
+
+        let salt = BytesN::from_array(&env, &[5; 32]);
+        market_client.commit_prediction(
+            &user,
+            &compute_commit_hash(&env, &market_id, &user, 1, &salt),
+            &300,
+        );
+        for (i, amount) in [100i128, 300].iter().enumerate() {
+            let pending = Address::generate(&env);
+            usdc_client.mint(&pending, &1_000);
+            let pending_salt = BytesN::from_array(&env, &[20 + i as u8; 32]);
+            market_client.commit_prediction(
+                &pending,
+                &compute_commit_hash(&env, &market_id, &pending, 0, &pending_salt),
+                amount,
+            );
+        }
 
-        // Verify pending count decremented
-        assert_eq!(market_client.get_pending_count(), 0);
+        next_ledger(&env);
+        market_client.reveal_prediction(&user, &market_id, &1, &300, &salt);
+
+        // Revealed YES 300; pending 400 split 25/75 => YES 400, NO 300 of 700
+        assert_eq!(
+            market_client.project_final_odds(&market_id, &2500),
+            (5714, 4286)
+        );
+        // All pending to YES => YES holds the entire projected pool
+        assert_eq!(
+            market_client.project_final_odds(&market_id, &10000),
+            (10000, 0)
+        );
+        // All pending to NO => 300 of 700
+        assert_eq!(
+            market_client.project_final_odds(&market_id, &0),
+            (4285, 5715)
+        );
     }
 
     #[test]
-    fn test_reveal_prediction_updates_yes_pool() {
+    fn test_reveal_publishes_pool_updated_event() {
         let (env, market_id, market_client, _usdc_client, user) = setup_reveal_test();
 
-        let salt = BytesN::from_array(&env, &[1; 32]);
-        let outcome = 1u32; // YES
-        let amount = 300i128;
-
-        let commit_hash = compute_commit_hash(&env, &market_id, outcome, &salt);
-        market_client.commit_prediction(&user, &commit_hash, &amount);
+        let salt = BytesN::from_array(&env, &[3; 32]);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, 1, &salt);
+        market_client.commit_prediction(&user, &commit_hash, &250);
 
         env.ledger().with_mut(|li| {
             li.timestamp = 1000;
+            li.sequence_number += 1;
         });
 
-        market_client.reveal_prediction(&user, &market_id, &outcome, &amount, &salt);
+        market_client.reveal_prediction(&user, &market_id, &1, &250, &salt);
+
+        let topic = Symbol::new(&env, "pool_updated_event");
+        let (_, _, data) = env
+            .events()
+            .all()
+            .iter()
+            .find(|(_, topics, _)| {
+                Symbol::try_from_val(&env, &topics.get(0).unwrap()).ok() == Some(topic.clone())
+            })
+            .expect("PoolUpdatedEvent not published");
+        let fields = Map::<Symbol, Val>::try_from_val(&env, &data).unwrap();
+        let field = |name: &str| -> Val { fields.get(Symbol::new(&env, name)).unwrap() };
+
+        assert_eq!(i128::try_from_val(&env, &field("yes_pool")).unwrap(), 250);
+        assert_eq!(i128::try_from_val(&env, &field("no_pool")).unwrap(), 0);
+        assert_eq!(u64::try_from_val(&env, &field("timestamp")).unwrap(), 1000);
+    }
 
-        // Verify YES pool updated (read from test helper prediction)
-        let prediction = market_client.test_get_prediction(&user).unwrap();
-        assert_eq!(prediction.outcome, 1);
-        assert_eq!(prediction.amount, 300);
+    /// Helper: Fields of the named event from the last call
+    fn last_event_fields(env: &Env, name: &str) -> Map<Symbol, Val> {
+        let topic = Symbol::new(env, name);
+        let (_, _, data) = env
+            .events()
+            .all()
+            .iter()
+            .find(|(_, topics, _)| {
+                Symbol::try_from_val(env, &topics.get(0).unwrap()).ok() == Some(topic.clone())
+            })
+            .expect("event not published");
+        Map::<Symbol, Val>::try_from_val(env, &data).unwrap()
+    }
+
+    /// Helper: Two committed users, then a 2-of-3 cancel multi-sig (creator + two admins)
+    fn setup_multisig_cancel() -> (
+        Env,
+        BytesN<32>,
+        PredictionMarketClient<'static>,
+        token::StellarAssetClient<'static>,
+        Vec<Address>,
+        Vec<Address>,
+    ) {
+        let (env, market_id, market_client, usdc_client, first) = setup_reveal_test();
+        let second = Address::generate(&env);
+        usdc_client.mint(&second, &1_000);
+        market_client.commit_prediction(&first, &BytesN::from_array(&env, &[1; 32]), &300);
+        market_client.commit_prediction(&second, &BytesN::from_array(&env, &[2; 32]), &200);
+
+        let admins = Vec::from_array(&env, [Address::generate(&env), Address::generate(&env)]);
+        market_client.set_cancel_multisig(&admins, &2);
+        let users = Vec::from_array(&env, [first, second]);
+        (env, market_id, market_client, usdc_client, admins, users)
     }
 
     #[test]
-    fn test_reveal_prediction_updates_no_pool() {
-        let (env, market_id, market_client, _usdc_client, user) = setup_reveal_test();
+    fn test_multisig_cancel_waits_for_quorum() {
+        let (env, market_id, market_client, _usdc_client, _admins, users) = setup_multisig_cancel();
+        let creator = stored_creator(&env, &market_client);
+
+        // The creator's cancel is only one of the two approvals needed
+        market_client.cancel_market(&creator, &market_id);
+        let approved = last_event_fields(&env, "cancel_approved_event");
+        let approvals =
+            u32::try_from_val(&env, &approved.get(Symbol::new(&env, "approvals")).unwrap())
+                .unwrap();
+        assert_eq!(approvals, 1);
+        assert_eq!(market_client.get_market_state_value(), Some(STATE_OPEN));
+        assert_eq!(
+            market_client.get_cancel_approvals(),
+            Vec::from_array(&env, [creator.clone()])
+        );
 
-        let salt = BytesN::from_array(&env, &[2; 32]);
-        let outcome = 0u32; // NO
-        let amount = 200i128;
+        // Repeats and outsiders don't count towards quorum
+        assert!(market_client
+            .try_approve_cancel(&creator, &market_id)
+            .is_err());
+        assert!(market_client
+            .try_approve_cancel(&Address::generate(&env), &market_id)
+            .is_err());
+        assert_eq!(market_client.get_cancel_approvals().len(), 1);
+
+        // Still open: refunds stay locked
+        assert_eq!(market_client.get_market_state_value(), Some(STATE_OPEN));
+        assert!(market_client
+            .try_claim_refund(&users.get(0).unwrap(), &market_id)
+            .is_err());
+    }
 
-        let commit_hash = compute_commit_hash(&env, &market_id, outcome, &salt);
-        market_client.commit_prediction(&user, &commit_hash, &amount);
+    #[test]
+    fn test_multisig_cancel_executes_at_quorum_with_refunds() {
+        let (env, market_id, market_client, usdc_client, admins, users) = setup_multisig_cancel();
+        let token = token::TokenClient::new(&env, &usdc_client.address);
+
+        market_client.approve_cancel(&admins.get(0).unwrap(), &market_id);
+        assert_eq!(market_client.get_market_state_value(), Some(STATE_OPEN));
+
+        // Second admin reaches 2-of-3 without the creator
+        market_client.approve_cancel(&admins.get(1).unwrap(), &market_id);
+        let cancelled = last_event_fields(&env, "market_cancelled_event");
+        let total_refundable = i128::try_from_val(
+            &env,
+            &cancelled
+                .get(Symbol::new(&env, "total_refundable"))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(total_refundable, 500);
+        assert_eq!(
+            market_client.get_market_state_value(),
+            Some(STATE_CANCELLED)
+        );
 
-        env.ledger().with_mut(|li| {
-            li.timestamp = 1000;
-        });
+        for user in users.iter() {
+            let before = token.balance(&user);
+            market_client.claim_refund(&user, &market_id);
+            assert!(token.balance(&user) > before);
+        }
+        assert_eq!(market_client.get_escrow_balance(), 0);
+    }
 
-        market_client.reveal_prediction(&user, &market_id, &outcome, &amount, &salt);
+    #[test]
+    fn test_cancel_reports_refund_total_and_each_refund_is_evented() {
+        let (env, market_id, market_client, usdc_client, first) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        let second = Address::generate(&env);
+        let third = Address::generate(&env);
+        usdc_client.mint(&second, &1_000);
+        usdc_client.mint(&third, &1_000);
 
-        let prediction = market_client.test_get_prediction(&user).unwrap();
-        assert_eq!(prediction.outcome, 0);
-        assert_eq!(prediction.amount, 200);
+        let salt = BytesN::from_array(&env, &[1; 32]);
+        market_client.commit_prediction(
+            &first,
+            &compute_commit_hash(&env, &market_id, &first, 1, &salt),
+            &300,
+        );
+        market_client.commit_prediction(&second, &BytesN::from_array(&env, &[2; 32]), &200);
+        market_client.commit_prediction(&third, &BytesN::from_array(&env, &[3; 32]), &100);
+        next_ledger(&env);
+        market_client.reveal_prediction(&first, &market_id, &1, &300, &salt);
+
+        let pool = market_client.get_escrow_balance();
+        assert_eq!(pool, 600);
+
+        market_client.cancel_market(&creator, &market_id);
+        let cancelled = last_event_fields(&env, "market_cancelled_event");
+        let total_refundable = i128::try_from_val(
+            &env,
+            &cancelled
+                .get(Symbol::new(&env, "total_refundable"))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(total_refundable, pool);
+
+        // Refunds are queued in commit order
+        let queue = market_client.get_refund_queue();
+        assert_eq!(
+            queue,
+            Vec::from_array(
+                &env,
+                [
+                    (first.clone(), 300),
+                    (second.clone(), 200),
+                    (third.clone(), 100)
+                ]
+            )
+        );
+
+        let mut refunded: i128 = 0;
+        for (user, amount) in queue.iter() {
+            if user == second {
+                assert!(market_client.retry_refund(&market_id, &user));
+            } else {
+                market_client.claim_refund(&user, &market_id);
+            }
+
+            let fields = last_event_fields(&env, "refund_processed_event");
+            let field = |name: &str| -> Val { fields.get(Symbol::new(&env, name)).unwrap() };
+            assert_eq!(Address::try_from_val(&env, &field("user")).unwrap(), user);
+            assert_eq!(
+                BytesN::<32>::try_from_val(&env, &field("market_id")).unwrap(),
+                market_id
+            );
+            let event_amount = i128::try_from_val(&env, &field("amount")).unwrap();
+            assert_eq!(event_amount, amount);
+            refunded += event_amount;
+        }
+
+        assert_eq!(refunded, pool);
+        assert_eq!(market_client.get_escrow_balance(), 0);
+        assert_eq!(market_client.get_refund_queue().len(), 0);
+    }
+
+    /// Helper: (old_state, new_state) of the MarketStateChangedEvent from the last call
+    fn last_state_change(env: &Env) -> (u32, u32) {
+        let topic = Symbol::new(env, "market_state_changed_event");
+        let (_, _, data) = env
+            .events()
+            .all()
+            .iter()
+            .find(|(_, topics, _)| {
+                Symbol::try_from_val(env, &topics.get(0).unwrap()).ok() == Some(topic.clone())
+            })
+            .expect("MarketStateChangedEvent not published");
+        let fields = Map::<Symbol, Val>::try_from_val(env, &data).unwrap();
+        let field = |name: &str| -> u32 {
+            u32::try_from_val(env, &fields.get(Symbol::new(env, name)).unwrap()).unwrap()
+        };
+        (field("old_state"), field("new_state"))
+    }
+
+    #[test]
+    fn test_market_state_changed_event_on_each_transition() {
+        let (env, market_id, market_client, usdc_client, _user) = setup_reveal_test();
+        let oracle = stored_oracle(&env, &market_client).address;
+        let creator = stored_creator(&env, &market_client);
+
+        env.ledger().with_mut(|li| li.timestamp = 2010);
+        market_client.close_market(&oracle, &market_id);
+        assert_eq!(last_state_change(&env), (STATE_OPEN, STATE_CLOSED));
+
+        env.ledger().with_mut(|li| li.timestamp = 3010);
+        market_client.resolve_market(&oracle, &market_id);
+        assert_eq!(last_state_change(&env), (STATE_CLOSED, STATE_RESOLVED));
+
+        let disputer = Address::generate(&env);
+        usdc_client.mint(&disputer, &1000);
+        market_client.dispute_market(&disputer, &market_id, &Symbol::new(&env, "wrong"), &None);
+        assert_eq!(last_state_change(&env), (STATE_RESOLVED, STATE_DISPUTED));
+
+        market_client.cancel_market(&creator, &market_id);
+        assert_eq!(last_state_change(&env), (STATE_DISPUTED, STATE_CANCELLED));
     }
 
     #[test]
@@ -2291,7 +6418,7 @@ mod tests {
         let outcome = 1u32;
         let amount = 100i128;
 
-        let commit_hash = compute_commit_hash(&env, &market_id, outcome, &salt);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, outcome, &salt);
         market_client.commit_prediction(&user, &commit_hash, &amount);
 
         // Advance past closing time
@@ -2312,11 +6439,12 @@ mod tests {
         let outcome = 1u32;
         let amount = 100i128;
 
-        let commit_hash = compute_commit_hash(&env, &market_id, outcome, &salt);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, outcome, &salt);
         market_client.commit_prediction(&user, &commit_hash, &amount);
 
         env.ledger().with_mut(|li| {
             li.timestamp = 1000;
+            li.sequence_number += 1;
         });
 
         // First reveal succeeds
@@ -2326,7 +6454,7 @@ mod tests {
         // Need to re-commit first since commitment was removed, but prediction exists
         // So even if we try to commit again it'll fail due to duplicate reveal check
         let salt2 = BytesN::from_array(&env, &[5; 32]);
-        let _commit_hash2 = compute_commit_hash(&env, &market_id, outcome, &salt2);
+        let _commit_hash2 = compute_commit_hash(&env, &market_id, &user, outcome, &salt2);
 
         // Trying to commit again will fail with DuplicateCommit since commitment was removed
         // but prediction exists. Let's use test helper to set up the scenario:
@@ -2339,10 +6467,11 @@ mod tests {
         let user2 = Address::generate(&env);
         _usdc_client.mint(&user2, &10_000);
 
-        let commit_hash_u2 = compute_commit_hash(&env, &market_id, outcome, &salt2);
+        let commit_hash_u2 = compute_commit_hash(&env, &market_id, &user2, outcome, &salt2);
         market_client.commit_prediction(&user2, &commit_hash_u2, &amount);
 
         // First reveal for user2 works
+        next_ledger(&env);
         market_client.reveal_prediction(&user2, &market_id, &outcome, &amount, &salt2);
 
         // Now use test_set_prediction to set prediction for another user, then try reveal
@@ -2350,7 +6479,7 @@ mod tests {
         _usdc_client.mint(&user3, &10_000);
 
         let salt3 = BytesN::from_array(&env, &[6; 32]);
-        let commit_hash_u3 = compute_commit_hash(&env, &market_id, outcome, &salt3);
+        let commit_hash_u3 = compute_commit_hash(&env, &market_id, &user3, outcome, &salt3);
         market_client.commit_prediction(&user3, &commit_hash_u3, &amount);
 
         // Manually set prediction already (simulating an already-revealed state)
@@ -2373,6 +6502,7 @@ mod tests {
         // Don't commit, just try to reveal directly
         env.ledger().with_mut(|li| {
             li.timestamp = 1000;
+            li.sequence_number += 1;
         });
 
         let result =
@@ -2388,11 +6518,12 @@ mod tests {
         let outcome = 1u32;
         let amount = 100i128;
 
-        let commit_hash = compute_commit_hash(&env, &market_id, outcome, &salt);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, outcome, &salt);
         market_client.commit_prediction(&user, &commit_hash, &amount);
 
         env.ledger().with_mut(|li| {
             li.timestamp = 1000;
+            li.sequence_number += 1;
         });
 
         // Reveal with WRONG outcome (0 instead of 1) - hash won't match
@@ -2410,11 +6541,12 @@ mod tests {
         let outcome = 1u32;
         let amount = 100i128;
 
-        let commit_hash = compute_commit_hash(&env, &market_id, outcome, &salt);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, outcome, &salt);
         market_client.commit_prediction(&user, &commit_hash, &amount);
 
         env.ledger().with_mut(|li| {
             li.timestamp = 1000;
+            li.sequence_number += 1;
         });
 
         // Reveal with WRONG salt
@@ -2432,14 +6564,15 @@ mod tests {
         let outcome = 1u32;
         let amount = 100i128;
 
-        let commit_hash = compute_commit_hash(&env, &market_id, outcome, &salt);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, outcome, &salt);
         market_client.commit_prediction(&user, &commit_hash, &amount);
 
         // Advance past closing time and close the market
         env.ledger().with_mut(|li| {
             li.timestamp = 2001;
         });
-        market_client.close_market(&market_id);
+        let oracle = stored_oracle(&env, &market_client).address;
+        market_client.close_market(&oracle, &market_id);
 
         // Try to reveal on closed market - should fail
         let result =
@@ -2455,136 +6588,695 @@ mod tests {
         let outcome = 1u32;
         let amount = 100i128;
 
-        let commit_hash = compute_commit_hash(&env, &market_id, outcome, &salt);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, outcome, &salt);
+        market_client.commit_prediction(&user, &commit_hash, &amount);
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = 1000;
+            li.sequence_number += 1;
+        });
+
+        // Reveal with WRONG amount
+        let wrong_amount = 200i128;
+        let result =
+            market_client.try_reveal_prediction(&user, &market_id, &outcome, &wrong_amount, &salt);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_get_committed_amount_prefills_reveal() {
+        let (env, market_id, market_client, _usdc_client, user) = setup_reveal_test();
+        assert_eq!(market_client.get_committed_amount(&user), None);
+
+        let salt = BytesN::from_array(&env, &[16; 32]);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, 1, &salt);
+        market_client.commit_prediction(&user, &commit_hash, &250);
+        assert_eq!(market_client.get_committed_amount(&user), Some(250));
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = 1000;
+            li.sequence_number += 1;
+        });
+
+        let result = market_client.try_reveal_prediction(&user, &market_id, &1, &249, &salt);
+        assert_eq!(result, Err(Ok(MarketError::AmountMismatch)));
+
+        let amount = market_client.get_committed_amount(&user).unwrap();
+        market_client.reveal_prediction(&user, &market_id, &1, &amount, &salt);
+        assert_eq!(market_client.get_committed_amount(&user), None);
+    }
+
+    #[test]
+    fn test_reveal_rejects_wrong_outcome_explicit() {
+        let (env, market_id, market_client, _usdc_client, user) = setup_reveal_test();
+
+        let salt = BytesN::from_array(&env, &[15; 32]);
+        let outcome = 1u32;
+        let amount = 100i128;
+
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, outcome, &salt);
+        market_client.commit_prediction(&user, &commit_hash, &amount);
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = 1000;
+            li.sequence_number += 1;
+        });
+
+        // Reveal with WRONG outcome
+        let wrong_outcome = 0u32;
+        let result =
+            market_client.try_reveal_prediction(&user, &market_id, &wrong_outcome, &amount, &salt);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_reveal_full_lifecycle_commit_reveal_resolve_claim() {
+        let (env, market_id, market_client, usdc_client, user) = setup_reveal_test();
+
+        let salt = BytesN::from_array(&env, &[11; 32]);
+        let outcome = 1u32; // YES
+        let amount = 1000i128;
+
+        // Step 1: Commit
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, outcome, &salt);
         market_client.commit_prediction(&user, &commit_hash, &amount);
 
-        env.ledger().with_mut(|li| {
-            li.timestamp = 1000;
-        });
+        // Step 2: Reveal
+        env.ledger().with_mut(|li| {
+            li.timestamp = 1000;
+            li.sequence_number += 1;
+        });
+        market_client.reveal_prediction(&user, &market_id, &outcome, &amount, &salt);
+
+        // Verify prediction exists after reveal
+        let prediction = market_client.test_get_prediction(&user);
+        assert!(prediction.is_some());
+        assert_eq!(prediction.unwrap().outcome, 1);
+
+        // Step 3: Close market
+        env.ledger().with_mut(|li| {
+            li.timestamp = 2001;
+        });
+        let oracle = stored_oracle(&env, &market_client).address;
+        market_client.close_market(&oracle, &market_id);
+
+        // Step 4: Setup resolution (simulate oracle)
+        market_client.test_setup_resolution(
+            &market_id, &1u32,     // YES wins
+            &1000i128, // winner shares
+            &0i128,    // loser shares
+        );
+
+        // Mint tokens to contract to cover payout
+        let market_addr = market_client.address.clone();
+        usdc_client.mint(&market_addr, &1000);
+
+        // Step 5: Claim winnings
+        let payout = market_client.claim_winnings(&user, &market_id);
+        // 1000 total pool, user has all 1000 winner shares, gross 1000, net 900 (10% fee)
+        assert_eq!(payout, 900);
+    }
+
+    #[test]
+    fn test_reveal_multiple_users_different_outcomes() {
+        let (env, market_id, market_client, usdc_client, user1) = setup_reveal_test();
+
+        let user2 = Address::generate(&env);
+        usdc_client.mint(&user2, &10_000);
+
+        // User1 commits YES
+        let salt1 = BytesN::from_array(&env, &[12; 32]);
+        let outcome1 = 1u32;
+        let amount1 = 500i128;
+        let commit_hash1 = compute_commit_hash(&env, &market_id, &user1, outcome1, &salt1);
+        market_client.commit_prediction(&user1, &commit_hash1, &amount1);
+
+        // User2 commits NO
+        let salt2 = BytesN::from_array(&env, &[13; 32]);
+        let outcome2 = 0u32;
+        let amount2 = 300i128;
+        let commit_hash2 = compute_commit_hash(&env, &market_id, &user2, outcome2, &salt2);
+        market_client.commit_prediction(&user2, &commit_hash2, &amount2);
+
+        assert_eq!(market_client.get_pending_count(), 2);
+
+        // Both reveal
+        env.ledger().with_mut(|li| {
+            li.timestamp = 1000;
+            li.sequence_number += 1;
+        });
+
+        market_client.reveal_prediction(&user1, &market_id, &outcome1, &amount1, &salt1);
+        market_client.reveal_prediction(&user2, &market_id, &outcome2, &amount2, &salt2);
+
+        // Both predictions stored
+        let pred1 = market_client.test_get_prediction(&user1).unwrap();
+        let pred2 = market_client.test_get_prediction(&user2).unwrap();
+
+        assert_eq!(pred1.outcome, 1);
+        assert_eq!(pred1.amount, 500);
+        assert_eq!(pred2.outcome, 0);
+        assert_eq!(pred2.amount, 300);
+
+        // Pending count back to 0
+        assert_eq!(market_client.get_pending_count(), 0);
+    }
+
+    #[test]
+    fn test_get_outcome_participation_counts_reveals() {
+        let (env, market_id, market_client, usdc_client, user1) = setup_reveal_test();
+        let user2 = Address::generate(&env);
+        let user3 = Address::generate(&env);
+        usdc_client.mint(&user2, &10_000);
+        usdc_client.mint(&user3, &10_000);
+
+        let bets = [
+            (&user1, 1u32, 17u8),
+            (&user2, 1u32, 18u8),
+            (&user3, 0u32, 19u8),
+        ];
+        for (user, outcome, seed) in bets.iter() {
+            let salt = BytesN::from_array(&env, &[*seed; 32]);
+            let commit_hash = compute_commit_hash(&env, &market_id, user, *outcome, &salt);
+            market_client.commit_prediction(user, &commit_hash, &100);
+        }
+        assert_eq!(market_client.get_outcome_participation(&market_id), (0, 0));
+
+        next_ledger(&env);
+        for (user, outcome, seed) in bets.iter() {
+            let salt = BytesN::from_array(&env, &[*seed; 32]);
+            market_client.reveal_prediction(user, &market_id, outcome, &100, &salt);
+        }
+
+        assert_eq!(market_client.get_outcome_participation(&market_id), (2, 1));
+    }
+
+    #[test]
+    fn test_reveal_rejects_non_binary_outcome() {
+        let (env, market_id, market_client, _usdc_client, user) = setup_reveal_test();
+
+        // A well-formed commitment to outcome 5
+        let salt = BytesN::from_array(&env, &[16; 32]);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, 5, &salt);
+        market_client.commit_prediction(&user, &commit_hash, &500);
+        next_ledger(&env);
+
+        let result = market_client.try_reveal_prediction(&user, &market_id, &5, &500, &salt);
+        assert_eq!(result, Err(Ok(MarketError::InvalidReveal)));
+
+        // Not counted into either pool
+        assert!(market_client.test_get_prediction(&user).is_none());
+        let summary = market_client.get_market_summary(&market_id);
+        assert_eq!(summary.yes_pool, 0);
+        assert_eq!(summary.no_pool, 0);
+        assert_eq!(summary.pending_count, 1);
+    }
+
+    #[test]
+    fn test_reveal_rejected_in_same_ledger_as_commit() {
+        let (env, market_id, market_client, _usdc_client, user) = setup_reveal_test();
+
+        let salt = BytesN::from_array(&env, &[14; 32]);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, 1, &salt);
+        market_client.commit_prediction(&user, &commit_hash, &500);
+        assert_eq!(
+            market_client.get_commitment(&user).unwrap().ledger_sequence,
+            env.ledger().sequence()
+        );
+
+        // Same ledger: rejected, commitment untouched
+        let result = market_client.try_reveal_prediction(&user, &market_id, &1, &500, &salt);
+        assert_eq!(result, Err(Ok(MarketError::RevealTooEarly)));
+        assert!(market_client.get_commitment(&user).is_some());
+
+        // Next ledger: allowed
+        next_ledger(&env);
+        market_client.reveal_prediction(&user, &market_id, &1, &500, &salt);
+        assert!(market_client.test_get_prediction(&user).is_some());
+    }
+
+    #[test]
+    fn test_reveal_respects_configured_min_delay() {
+        let (env, market_id, market_client, _usdc_client, user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        market_client.set_min_reveal_delay(&creator, &3);
+
+        let salt = BytesN::from_array(&env, &[15; 32]);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, 0, &salt);
+        market_client.commit_prediction(&user, &commit_hash, &500);
+
+        next_ledger(&env);
+        next_ledger(&env);
+        let result = market_client.try_reveal_prediction(&user, &market_id, &0, &500, &salt);
+        assert_eq!(result, Err(Ok(MarketError::RevealTooEarly)));
+
+        next_ledger(&env);
+        market_client.reveal_prediction(&user, &market_id, &0, &500, &salt);
+    }
+
+    // ============================================================================
+    // RESOLUTION DRY RUN TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_can_resolve_detailed_reports_each_blocker() {
+        let (env, market_id, market_client, _usdc_client, _user) = setup_reveal_test();
+        let oracle = stored_oracle(&env, &market_client);
+        let reason = |name: &str| Symbol::new(&env, name);
+
+        // Before resolution time
+        assert_eq!(
+            market_client.can_resolve_detailed(&market_id),
+            (false, reason("before_resolution_time"))
+        );
+
+        // Past resolution time but never closed
+        env.ledger().with_mut(|li| li.timestamp = 3000);
+        assert_eq!(
+            market_client.can_resolve_detailed(&market_id),
+            (false, reason("not_closed"))
+        );
+
+        market_client.close_market(&oracle.address, &market_id);
+
+        // Oracle attestation under challenge
+        oracle.set_active_challenge(&true);
+        assert_eq!(
+            market_client.can_resolve_detailed(&market_id),
+            (false, reason("active_challenge"))
+        );
+        oracle.set_active_challenge(&false);
+
+        // Oracle consensus not reached
+        oracle.set_consensus_status(&false);
+        assert_eq!(
+            market_client.can_resolve_detailed(&market_id),
+            (false, reason("no_consensus"))
+        );
+        oracle.set_consensus_status(&true);
+
+        // Ready, and the real call succeeds
+        assert_eq!(
+            market_client.can_resolve_detailed(&market_id),
+            (true, reason("ready"))
+        );
+        market_client.resolve_market(&oracle.address, &market_id);
+        assert_eq!(
+            market_client.can_resolve_detailed(&market_id),
+            (false, reason("already_resolved"))
+        );
+    }
+
+    // ============================================================================
+    // FEE HOLIDAY TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_claim_during_fee_holiday_pays_no_fee() {
+        let (env, market_id, market_client, usdc_client, user) = setup_reveal_test();
+        market_client.set_fee_free_until(&stored_creator(&env, &market_client), &5000);
+
+        market_client.test_setup_resolution(&market_id, &1u32, &1000i128, &0i128);
+        market_client.test_set_prediction(&user, &1u32, &1000i128);
+        usdc_client.mint(&market_client.address, &1000);
+
+        env.ledger().with_mut(|li| li.timestamp = 4999);
+        let (gross, protocol_fee, creator_fee, net) =
+            market_client.claim_winnings_verbose(&user, &market_id);
+        assert_eq!((gross, protocol_fee, creator_fee, net), (1000, 0, 0, 1000));
+    }
+
+    #[test]
+    fn test_claim_after_fee_holiday_pays_normal_fee() {
+        let (env, market_id, market_client, usdc_client, user) = setup_reveal_test();
+        market_client.set_fee_free_until(&stored_creator(&env, &market_client), &5000);
+
+        market_client.test_setup_resolution(&market_id, &1u32, &1000i128, &0i128);
+        market_client.test_set_prediction(&user, &1u32, &1000i128);
+        usdc_client.mint(&market_client.address, &1000);
 
-        // Reveal with WRONG amount
-        let wrong_amount = 200i128;
-        let result =
-            market_client.try_reveal_prediction(&user, &market_id, &outcome, &wrong_amount, &salt);
-        assert!(result.is_err());
+        env.ledger().with_mut(|li| li.timestamp = 5000);
+        assert_eq!(market_client.claim_winnings(&user, &market_id), 900);
     }
 
     #[test]
-    fn test_reveal_rejects_wrong_outcome_explicit() {
-        let (env, market_id, market_client, _usdc_client, user) = setup_reveal_test();
+    fn test_small_claim_still_pays_at_least_one_unit_fee() {
+        let (_env, market_id, market_client, usdc_client, user) = setup_reveal_test();
+        market_client.test_setup_resolution(&market_id, &1u32, &5i128, &0i128);
+        market_client.test_set_prediction(&user, &1u32, &5i128);
+        usdc_client.mint(&market_client.address, &5);
+
+        let (gross, protocol_fee, creator_fee, net) =
+            market_client.claim_winnings_verbose(&user, &market_id);
+        assert_eq!((gross, protocol_fee + creator_fee, net), (5, 1, 4));
+        assert_eq!(market_client.get_resolution_stats(&market_id).2, 1);
+    }
 
-        let salt = BytesN::from_array(&env, &[15; 32]);
-        let outcome = 1u32;
-        let amount = 100i128;
+    #[test]
+    fn test_governance_holder_claims_with_discounted_fee() {
+        let (env, market_id, market_client, usdc_client, holder) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        let non_holder = Address::generate(&env);
+
+        let gov_token = create_token_contract(&env, &Address::generate(&env));
+        gov_token.mint(&holder, &1_500);
+        let tiers = Vec::from_array(
+            &env,
+            [
+                FeeDiscountTier {
+                    min_balance: 100,
+                    discount_bps: 2500,
+                },
+                FeeDiscountTier {
+                    min_balance: 1_000,
+                    discount_bps: 5000,
+                },
+            ],
+        );
+        market_client.set_fee_discount(&creator, &Some(gov_token.address.clone()), &tiers);
+        assert_eq!(market_client.get_fee_discount_bps(&holder), 5000);
+        assert_eq!(market_client.get_fee_discount_bps(&non_holder), 0);
+
+        market_client.test_setup_resolution(&market_id, &1u32, &2000i128, &0i128);
+        market_client.test_set_prediction(&holder, &1u32, &1000i128);
+        market_client.test_set_prediction(&non_holder, &1u32, &1000i128);
+        usdc_client.mint(&market_client.address, &2000);
+
+        // Holder pays half the 10% fee, the non-holder pays it in full
+        assert_eq!(market_client.claim_winnings(&holder, &market_id), 950);
+        assert_eq!(market_client.claim_winnings(&non_holder, &market_id), 900);
+        assert_eq!(market_client.get_resolution_stats(&market_id).2, 150);
+    }
 
-        let commit_hash = compute_commit_hash(&env, &market_id, outcome, &salt);
-        market_client.commit_prediction(&user, &commit_hash, &amount);
+    #[test]
+    #[should_panic(expected = "Fee discount tiers must be sorted by min_balance")]
+    fn test_fee_discount_rejects_unsorted_tiers() {
+        let (env, _market_id, market_client, _usdc_client, _user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        let tiers = Vec::from_array(
+            &env,
+            [
+                FeeDiscountTier {
+                    min_balance: 1_000,
+                    discount_bps: 5000,
+                },
+                FeeDiscountTier {
+                    min_balance: 100,
+                    discount_bps: 2500,
+                },
+            ],
+        );
+        market_client.set_fee_discount(&creator, &Some(Address::generate(&env)), &tiers);
+    }
 
-        env.ledger().with_mut(|li| {
-            li.timestamp = 1000;
-        });
+    #[test]
+    #[should_panic(expected = "Winning positions close by claiming")]
+    fn test_winner_cannot_close_out_as_loser() {
+        let (_env, market_id, market_client, _usdc_client, user) = setup_reveal_test();
+        market_client.test_set_prediction(&user, &1u32, &1000i128);
+        market_client.test_setup_resolution(&market_id, &1u32, &1000i128, &0i128);
 
-        // Reveal with WRONG outcome
-        let wrong_outcome = 0u32;
-        let result =
-            market_client.try_reveal_prediction(&user, &market_id, &wrong_outcome, &amount, &salt);
-        assert!(result.is_err());
+        market_client.close_losing_position(&user);
     }
 
     #[test]
-    fn test_reveal_full_lifecycle_commit_reveal_resolve_claim() {
+    fn test_failing_gov_token_claims_without_discount() {
         let (env, market_id, market_client, usdc_client, user) = setup_reveal_test();
+        let tiers = Vec::from_array(
+            &env,
+            [FeeDiscountTier {
+                min_balance: 0,
+                discount_bps: 5000,
+            }],
+        );
+        // Not a token contract, so every balance lookup fails
+        market_client.set_fee_discount(
+            &stored_creator(&env, &market_client),
+            &Some(market_client.address.clone()),
+            &tiers,
+        );
+        assert_eq!(market_client.get_fee_discount_bps(&user), 0);
 
-        let salt = BytesN::from_array(&env, &[11; 32]);
-        let outcome = 1u32; // YES
-        let amount = 1000i128;
+        market_client.test_setup_resolution(&market_id, &1u32, &1000i128, &0i128);
+        market_client.test_set_prediction(&user, &1u32, &1000i128);
+        usdc_client.mint(&market_client.address, &1000);
+        assert_eq!(market_client.claim_winnings(&user, &market_id), 900);
+    }
 
-        // Step 1: Commit
-        let commit_hash = compute_commit_hash(&env, &market_id, outcome, &salt);
-        market_client.commit_prediction(&user, &commit_hash, &amount);
+    #[test]
+    #[should_panic(expected = "Fee parameters are frozen once the market closes")]
+    fn test_fee_discount_cannot_change_after_close() {
+        let (env, market_id, market_client, _usdc_client, _user) = setup_reveal_test();
+        let oracle = stored_oracle(&env, &market_client).address;
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        market_client.close_market(&oracle, &market_id);
+        market_client.set_fee_discount(
+            &stored_creator(&env, &market_client),
+            &None,
+            &Vec::new(&env),
+        );
+    }
 
-        // Step 2: Reveal
-        env.ledger().with_mut(|li| {
-            li.timestamp = 1000;
-        });
-        market_client.reveal_prediction(&user, &market_id, &outcome, &amount, &salt);
+    #[test]
+    #[should_panic(expected = "Payout amount is zero")]
+    fn test_claim_rejected_when_fee_consumes_whole_payout() {
+        let (_env, market_id, market_client, usdc_client, user) = setup_reveal_test();
+        market_client.test_setup_resolution(&market_id, &1u32, &1i128, &0i128);
+        market_client.test_set_prediction(&user, &1u32, &1i128);
+        usdc_client.mint(&market_client.address, &1);
+
+        market_client.claim_winnings(&user, &market_id);
+    }
 
-        // Verify prediction exists after reveal
-        let prediction = market_client.test_get_prediction(&user);
-        assert!(prediction.is_some());
-        assert_eq!(prediction.unwrap().outcome, 1);
+    // ============================================================================
+    // MARKET SUMMARY TESTS
+    // ============================================================================
 
-        // Step 3: Close market
-        env.ledger().with_mut(|li| {
-            li.timestamp = 2001;
-        });
-        market_client.close_market(&market_id);
+    #[test]
+    fn test_get_market_summary_through_full_lifecycle() {
+        let (env, market_id, market_client, usdc_client, user1) = setup_reveal_test();
+        let user2 = Address::generate(&env);
+        usdc_client.mint(&user2, &10_000);
 
-        // Step 4: Setup resolution (simulate oracle)
-        market_client.test_setup_resolution(
-            &market_id, &1u32,     // YES wins
-            &1000i128, // winner shares
-            &0i128,    // loser shares
+        // Fresh market
+        let summary = market_client.get_market_summary(&market_id);
+        assert_eq!(summary.status, STATE_OPEN);
+        assert_eq!(summary.closing_time, 2000);
+        assert_eq!(summary.resolution_time, 3000);
+        assert_eq!(summary.participant_count, 0);
+        assert_eq!(summary.protocol_fee_bps, 1000);
+        assert_eq!(summary.reveal_bond, 0);
+        assert_eq!(summary.winning_outcome, None);
+        assert!(!summary.is_disputed);
+        assert_eq!(summary.disputer, None);
+
+        // Two commitments pending
+        let salt1 = BytesN::from_array(&env, &[31; 32]);
+        let salt2 = BytesN::from_array(&env, &[32; 32]);
+        market_client.commit_prediction(
+            &user1,
+            &compute_commit_hash(&env, &market_id, &user1, 1, &salt1),
+            &600,
+        );
+        market_client.commit_prediction(
+            &user2,
+            &compute_commit_hash(&env, &market_id, &user2, 0, &salt2),
+            &400,
+        );
+        let summary = market_client.get_market_summary(&market_id);
+        assert_eq!(summary.participant_count, 2);
+        assert_eq!(summary.pending_count, 2);
+        assert_eq!(summary.revealed_count, 0);
+        assert_eq!(summary.yes_pool, 0);
+
+        // Both revealed
+        next_ledger(&env);
+        market_client.reveal_prediction(&user1, &market_id, &1, &600, &salt1);
+        market_client.reveal_prediction(&user2, &market_id, &0, &400, &salt2);
+        let summary = market_client.get_market_summary(&market_id);
+        assert_eq!(summary.pending_count, 0);
+        assert_eq!(summary.revealed_count, 2);
+        assert_eq!(summary.yes_pool, 600);
+        assert_eq!(summary.no_pool, 400);
+        assert_eq!(summary.total_volume, 1000);
+
+        // Closed
+        env.ledger().with_mut(|li| li.timestamp = 2001);
+        let oracle = stored_oracle(&env, &market_client).address;
+        market_client.close_market(&oracle, &market_id);
+        assert_eq!(
+            market_client.get_market_summary(&market_id).status,
+            STATE_CLOSED
         );
 
-        // Mint tokens to contract to cover payout
-        let market_addr = market_client.address.clone();
-        usdc_client.mint(&market_addr, &1000);
-
-        // Step 5: Claim winnings
-        let payout = market_client.claim_winnings(&user, &market_id);
-        // 1000 total pool, user has all 1000 winner shares, gross 1000, net 900 (10% fee)
-        assert_eq!(payout, 900);
+        // Resolved
+        env.ledger().with_mut(|li| li.timestamp = 3000);
+        market_client.resolve_market(&oracle, &market_id);
+        let summary = market_client.get_market_summary(&market_id);
+        assert_eq!(summary.status, STATE_RESOLVED);
+        assert_eq!(summary.winning_outcome, Some(1));
+        assert!(!summary.is_disputed);
+        assert_eq!(summary.dispute_stake_held, 0);
+
+        // Disputed
+        market_client.dispute_market(&user2, &market_id, &Symbol::new(&env, "wrong"), &None);
+        let summary = market_client.get_market_summary(&market_id);
+        assert_eq!(summary.status, STATE_DISPUTED);
+        assert_eq!(summary.winning_outcome, Some(1));
+        assert!(summary.is_disputed);
+        assert_eq!(summary.disputer, Some(user2));
+        assert_eq!(summary.dispute_stake_held, 1000);
+        assert_eq!(summary.yes_pool, 600);
+        assert_eq!(summary.no_pool, 400);
     }
 
+    // ============================================================================
+    // RECOMPUTE POOLS TESTS
+    // ============================================================================
+
     #[test]
-    fn test_reveal_multiple_users_different_outcomes() {
+    fn test_recompute_pools_repairs_corrupted_totals() {
         let (env, market_id, market_client, usdc_client, user1) = setup_reveal_test();
-
         let user2 = Address::generate(&env);
         usdc_client.mint(&user2, &10_000);
 
-        // User1 commits YES
-        let salt1 = BytesN::from_array(&env, &[12; 32]);
-        let outcome1 = 1u32;
-        let amount1 = 500i128;
-        let commit_hash1 = compute_commit_hash(&env, &market_id, outcome1, &salt1);
-        market_client.commit_prediction(&user1, &commit_hash1, &amount1);
-
-        // User2 commits NO
-        let salt2 = BytesN::from_array(&env, &[13; 32]);
-        let outcome2 = 0u32;
-        let amount2 = 300i128;
-        let commit_hash2 = compute_commit_hash(&env, &market_id, outcome2, &salt2);
-        market_client.commit_prediction(&user2, &commit_hash2, &amount2);
-
-        assert_eq!(market_client.get_pending_count(), 2);
+        let salt1 = BytesN::from_array(&env, &[21; 32]);
+        let salt2 = BytesN::from_array(&env, &[22; 32]);
+        market_client.commit_prediction(
+            &user1,
+            &compute_commit_hash(&env, &market_id, &user1, 1, &salt1),
+            &500,
+        );
+        market_client.commit_prediction(
+            &user2,
+            &compute_commit_hash(&env, &market_id, &user2, 0, &salt2),
+            &300,
+        );
+        next_ledger(&env);
+        market_client.reveal_prediction(&user1, &market_id, &1, &500, &salt1);
+        market_client.reveal_prediction(&user2, &market_id, &0, &300, &salt2);
 
-        // Both reveal
-        env.ledger().with_mut(|li| {
-            li.timestamp = 1000;
+        // Corrupt the stored accounting
+        let creator: Address = env.as_contract(&market_client.address, || {
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, YES_POOL_KEY), &9_999i128);
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, TOTAL_VOLUME_KEY), &1i128);
+            env.storage()
+                .persistent()
+                .get(&Symbol::new(&env, CREATOR_KEY))
+                .unwrap()
         });
+        assert_eq!(
+            market_client.get_market_state(&market_id).total_pool,
+            10_299
+        );
 
-        market_client.reveal_prediction(&user1, &market_id, &outcome1, &amount1, &salt1);
-        market_client.reveal_prediction(&user2, &market_id, &outcome2, &amount2, &salt2);
-
-        // Both predictions stored
-        let pred1 = market_client.test_get_prediction(&user1).unwrap();
-        let pred2 = market_client.test_get_prediction(&user2).unwrap();
+        let repaired = market_client.recompute_pools(&creator, &market_id);
+        assert_eq!(repaired, (500, 300, 800));
 
-        assert_eq!(pred1.outcome, 1);
-        assert_eq!(pred1.amount, 500);
-        assert_eq!(pred2.outcome, 0);
-        assert_eq!(pred2.amount, 300);
+        assert_eq!(market_client.get_market_state(&market_id).total_pool, 800);
+        env.as_contract(&market_client.address, || {
+            let yes: i128 = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, YES_POOL_KEY))
+                .unwrap();
+            let volume: i128 = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, TOTAL_VOLUME_KEY))
+                .unwrap();
+            assert_eq!(yes, 500);
+            assert_eq!(volume, 800);
+        });
+    }
 
-        // Pending count back to 0
-        assert_eq!(market_client.get_pending_count(), 0);
+    #[test]
+    #[should_panic(expected = "Unauthorized: only creator can manage this market")]
+    fn test_recompute_pools_non_creator_rejected() {
+        let (_env, market_id, market_client, _usdc_client, user) = setup_reveal_test();
+        market_client.recompute_pools(&user, &market_id);
     }
 
     // ============================================================================
     // GET USER PREDICTION TESTS
     // ============================================================================
 
+    #[test]
+    fn test_outcome_labels_stored_at_initialize() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let usdc_client = create_token_contract(&env, &Address::generate(&env));
+        let labels = Vec::from_array(
+            &env,
+            [Symbol::new(&env, "Rejected"), Symbol::new(&env, "Approved")],
+        );
+
+        market_client.initialize(
+            &BytesN::from_array(&env, &[0; 32]),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &usdc_client.address,
+            &env.register(MockOracle, ()),
+            &2000,
+            &3000,
+            &Vec::new(&env),
+            &0,
+            &labels,
+        );
+
+        assert_eq!(market_client.get_outcome_labels(), labels);
+    }
+
+    #[test]
+    fn test_outcome_labels_default_to_no_yes() {
+        let (env, _market_id, market_client, _usdc_client, _user) = setup_reveal_test();
+
+        let labels = market_client.get_outcome_labels();
+        assert_eq!(labels.len(), OUTCOME_COUNT);
+        assert_eq!(labels.get(0).unwrap(), Symbol::new(&env, "NO"));
+        assert_eq!(labels.get(1).unwrap(), Symbol::new(&env, "YES"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Outcome label count must match outcome count")]
+    fn test_outcome_labels_count_mismatch_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let usdc_client = create_token_contract(&env, &Address::generate(&env));
+        let labels = Vec::from_array(
+            &env,
+            [
+                Symbol::new(&env, "TeamA"),
+                Symbol::new(&env, "Draw"),
+                Symbol::new(&env, "TeamB"),
+            ],
+        );
+
+        market_client.initialize(
+            &BytesN::from_array(&env, &[0; 32]),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &usdc_client.address,
+            &env.register(MockOracle, ()),
+            &2000,
+            &3000,
+            &Vec::new(&env),
+            &0,
+            &labels,
+        );
+    }
+
     #[test]
     fn test_get_user_prediction_no_prediction_returns_none() {
         let env = Env::default();
@@ -2605,6 +7297,9 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         let user = Address::generate(&env);
@@ -2632,6 +7327,9 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         let user = Address::generate(&env);
@@ -2671,6 +7369,9 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         let user = Address::generate(&env);
@@ -2688,6 +7389,54 @@ mod tests {
         assert_eq!(r.predicted_outcome, outcome);
     }
 
+    #[test]
+    fn test_get_all_positions_mixed_committed_and_revealed() {
+        let (env, market_id, market_client, usdc_client, revealer) = setup_reveal_test();
+        let committer = Address::generate(&env);
+        usdc_client.mint(&committer, &10_000);
+
+        let salt = BytesN::from_array(&env, &[7; 32]);
+        let commit_hash = compute_commit_hash(&env, &market_id, &revealer, 1, &salt);
+        market_client.commit_prediction(&revealer, &commit_hash, &500);
+
+        let pending_hash = compute_commit_hash(
+            &env,
+            &market_id,
+            &committer,
+            0,
+            &BytesN::from_array(&env, &[8; 32]),
+        );
+        market_client.commit_prediction(&committer, &pending_hash, &300);
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = 1000;
+            li.sequence_number += 1;
+        });
+        market_client.reveal_prediction(&revealer, &market_id, &1, &500, &salt);
+
+        let creator = stored_creator(&env, &market_client);
+        let positions = market_client.get_all_positions(&creator, &market_id);
+        assert_eq!(positions.len(), 2);
+
+        let revealed = positions.get(0).unwrap();
+        assert_eq!(revealed.status, PREDICTION_STATUS_REVEALED);
+        assert_eq!(revealed.predicted_outcome, 1);
+        assert_eq!(revealed.amount, 500);
+
+        let committed = positions.get(1).unwrap();
+        assert_eq!(committed.status, PREDICTION_STATUS_COMMITTED);
+        assert_eq!(committed.commitment_hash, pending_hash);
+        assert_eq!(committed.predicted_outcome, PREDICTION_OUTCOME_NONE);
+        assert_eq!(committed.amount, 300);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only creator or factory")]
+    fn test_get_all_positions_rejects_other_callers() {
+        let (_env, market_id, market_client, _usdc_client, user) = setup_reveal_test();
+        market_client.get_all_positions(&user, &market_id);
+    }
+
     #[test]
     fn test_get_user_prediction_revealed_no_outcome() {
         let env = Env::default();
@@ -2708,6 +7457,9 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         let user = Address::generate(&env);
@@ -2744,57 +7496,461 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
+        );
+
+        let user = Address::generate(&env);
+        let dispute_reason = Symbol::new(&env, "wrong");
+        let evidence_hash = Some(BytesN::from_array(&env, &[5u8; 32]));
+
+        // Mint USDC to user for dispute stake (1000)
+        usdc_client.mint(&user, &2000);
+
+        // Resolve market
+        market_client.test_setup_resolution(&market_id, &1u32, &1000, &0);
+
+        // Intial state is 2 (RESOLVED)
+        assert_eq!(market_client.get_market_state_value().unwrap(), 2);
+
+        // Dispute
+        market_client.dispute_market(&user, &market_id, &dispute_reason, &evidence_hash);
+
+        // Verify state transitioned to DISPUTED (3)
+        let state = market_client.get_market_state_value().unwrap();
+        assert_eq!(state, 3);
+    }
+
+    #[test]
+    fn test_dispute_rejected_after_custom_window() {
+        let (env, market_id, market_client, usdc_client, _user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        market_client.set_dispute_params(&creator, &1000, &100);
+        assert_eq!(market_client.get_dispute_params(), (1000, 100));
+
+        let disputer = Address::generate(&env);
+        usdc_client.mint(&disputer, &1000);
+        market_client.test_setup_resolution(&market_id, &1u32, &1000, &0);
+
+        // resolution_time is 3000: the window covers [3000, 3100)
+        env.ledger().with_mut(|li| li.timestamp = 3100);
+        let reason = Symbol::new(&env, "wrong");
+        assert!(market_client
+            .try_dispute_market(&disputer, &market_id, &reason, &None)
+            .is_err());
+
+        env.ledger().with_mut(|li| li.timestamp = 3099);
+        market_client.dispute_market(&disputer, &market_id, &reason, &None);
+        assert_eq!(market_client.get_market_state_value(), Some(STATE_DISPUTED));
+    }
+
+    #[test]
+    fn test_dispute_transfers_custom_stake() {
+        let (env, market_id, market_client, usdc_client, _user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        market_client.set_dispute_params(&creator, &250, &604800);
+
+        let disputer = Address::generate(&env);
+        usdc_client.mint(&disputer, &1000);
+        market_client.test_setup_resolution(&market_id, &1u32, &1000, &0);
+        market_client.dispute_market(&disputer, &market_id, &Symbol::new(&env, "wrong"), &None);
+
+        assert_eq!(usdc_client.balance(&disputer), 750);
+        assert_eq!(usdc_client.balance(&market_client.address), 250);
+        assert_eq!(market_client.get_dispute_stake_held(), 250);
+        assert_eq!(market_client.get_dispute(&market_id).unwrap().stake, 250);
+    }
+
+    #[test]
+    fn test_set_dispute_params_validates_inputs() {
+        let (env, market_id, market_client, _usdc_client, _user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+
+        assert_eq!(market_client.get_dispute_params(), (1000, 604800));
+        assert!(market_client
+            .try_set_dispute_params(&creator, &0, &100)
+            .is_err());
+        assert!(market_client
+            .try_set_dispute_params(&creator, &100, &0)
+            .is_err());
+        assert!(market_client
+            .try_set_dispute_params(&Address::generate(&env), &100, &100)
+            .is_err());
+
+        // Economics are fixed once disputes can be filed
+        market_client.test_setup_resolution(&market_id, &1u32, &1000, &0);
+        assert!(market_client
+            .try_set_dispute_params(&creator, &100, &100)
+            .is_err());
+        assert_eq!(market_client.get_dispute_params(), (1000, 604800));
+    }
+
+    #[test]
+    fn test_cancel_disputed_market_returns_dispute_stake() {
+        let (env, market_id, market_client, usdc_client, _user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+
+        let disputer = Address::generate(&env);
+        usdc_client.mint(&disputer, &1000);
+        market_client.test_setup_resolution(&market_id, &1u32, &1000, &0);
+        market_client.dispute_market(&disputer, &market_id, &Symbol::new(&env, "wrong"), &None);
+        assert_eq!(market_client.get_dispute_stake_held(), 1000);
+        assert_eq!(usdc_client.balance(&disputer), 0);
+
+        market_client.cancel_market(&creator, &market_id);
+
+        assert_eq!(market_client.get_dispute_stake_held(), 0);
+        assert_eq!(usdc_client.balance(&disputer), 1000);
+        assert_eq!(usdc_client.balance(&market_client.address), 0);
+    }
+
+    #[test]
+    fn test_flipped_outcome_after_claim_is_covered_by_insurance() {
+        let (env, market_id, market_client, usdc_client, _user) = setup_reveal_test();
+        let oracle = stored_oracle(&env, &market_client).address;
+
+        let yes_user = Address::generate(&env);
+        let no_user = Address::generate(&env);
+        market_client.test_set_prediction(&yes_user, &1u32, &1000i128);
+        market_client.test_set_prediction(&no_user, &0u32, &1000i128);
+        usdc_client.mint(&market_client.address, &2000);
+        market_client.test_setup_resolution(&market_id, &1u32, &1000, &1000);
+
+        let funder = Address::generate(&env);
+        usdc_client.mint(&funder, &2500);
+        market_client.fund_insurance(&funder, &2500);
+
+        // YES claims under the original outcome: 2000 gross, 1800 net
+        assert_eq!(market_client.claim_winnings(&yes_user, &market_id), 1800);
+
+        let disputer = Address::generate(&env);
+        usdc_client.mint(&disputer, &1000);
+        market_client.dispute_market(&disputer, &market_id, &Symbol::new(&env, "wrong"), &None);
+        market_client.resolve_dispute(&oracle, &market_id, &true);
+
+        // The stale 2000 gross payout is drawn from insurance, leaving nothing uncovered
+        assert!(market_client.is_stale_claim(&yes_user));
+        assert_eq!(market_client.get_insurance_fund(), 500);
+        assert_eq!(market_client.get_uncovered_shortfall(), 0);
+        assert_eq!(market_client.get_claim_window_end(), 500 + 604800);
+
+        // NO is now the winner and is paid in full from the restored escrow
+        assert_eq!(market_client.claim_winnings(&no_user, &market_id), 1800);
+        assert!(!market_client.is_stale_claim(&no_user));
+        assert_eq!(usdc_client.balance(&no_user), 1800);
+        assert_eq!(usdc_client.balance(&disputer), 1000);
+        // Only both claims' fees and the undrawn insurance remain
+        assert_eq!(usdc_client.balance(&market_client.address), 200 + 200 + 500);
+    }
+
+    #[test]
+    #[should_panic(expected = "Claim window closed")]
+    fn test_claim_rejected_after_post_dispute_grace() {
+        let (env, market_id, market_client, usdc_client, _user) = setup_reveal_test();
+        let oracle = stored_oracle(&env, &market_client).address;
+        let creator = stored_creator(&env, &market_client);
+        market_client.set_dispute_claim_grace(&creator, &100);
+
+        let no_user = Address::generate(&env);
+        market_client.test_set_prediction(&no_user, &0u32, &1000i128);
+        usdc_client.mint(&market_client.address, &1000);
+        market_client.test_setup_resolution(&market_id, &1u32, &0, &1000);
+
+        let disputer = Address::generate(&env);
+        usdc_client.mint(&disputer, &1000);
+        market_client.dispute_market(&disputer, &market_id, &Symbol::new(&env, "wrong"), &None);
+        market_client.resolve_dispute(&oracle, &market_id, &true);
+
+        env.ledger().with_mut(|li| li.timestamp = 500 + 101);
+        market_client.claim_winnings(&no_user, &market_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Market not resolved")]
+    fn test_dispute_market_not_resolved() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let oracle_contract_id = env.register(MockOracle, ());
+        let admin = Address::generate(&env);
+        let usdc_client = create_token_contract(&env, &admin);
+
+        market_client.initialize(
+            &market_id,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &usdc_client.address,
+            &oracle_contract_id,
+            &2000,
+            &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         let user = Address::generate(&env);
         let dispute_reason = Symbol::new(&env, "wrong");
-        let evidence_hash = Some(BytesN::from_array(&env, &[5u8; 32]));
-
-        // Mint USDC to user for dispute stake (1000)
-        usdc_client.mint(&user, &2000);
-
-        // Resolve market
-        market_client.test_setup_resolution(&market_id, &1u32, &1000, &0);
 
-        // Intial state is 2 (RESOLVED)
-        assert_eq!(market_client.get_market_state_value().unwrap(), 2);
+        // Market is OPEN, not RESOLVED
+        market_client.dispute_market(&user, &market_id, &dispute_reason, &None);
+    }
 
-        // Dispute
-        market_client.dispute_market(&user, &market_id, &dispute_reason, &evidence_hash);
+    // ============================================================================
+    // REVEAL BOND TESTS
+    // ============================================================================
 
-        // Verify state transitioned to DISPUTED (3)
-        let state = market_client.get_market_state_value().unwrap();
-        assert_eq!(state, 3);
+    /// Setup helper: market wired to a real Factory/Treasury with a reveal bond of `bond`.
+    /// Returns (env, market_id, market_client, usdc_client, user, treasury_id).
+    fn setup_bonded_market(
+        bond: i128,
+    ) -> (
+        Env,
+        BytesN<32>,
+        PredictionMarketClient<'static>,
+        token::StellarAssetClient<'static>,
+        Address,
+        Address,
+    ) {
+        setup_treasury_backed_market(bond, 0)
     }
 
-    #[test]
-    #[should_panic(expected = "Market not resolved")]
-    fn test_dispute_market_not_resolved() {
+    /// Market wired to a real Factory and Treasury, with optional reveal and creator bonds
+    fn setup_treasury_backed_market(
+        bond: i128,
+        creator_bond: i128,
+    ) -> (
+        Env,
+        BytesN<32>,
+        PredictionMarketClient<'static>,
+        token::StellarAssetClient<'static>,
+        Address,
+        Address,
+    ) {
         let env = Env::default();
         env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 500);
+
+        let admin = Address::generate(&env);
+        let usdc_client = create_token_contract(&env, &Address::generate(&env));
+
+        let factory_id = env.register(crate::factory::MarketFactory, ());
+        let treasury_id = env.register(crate::treasury::Treasury, ());
+        crate::factory::MarketFactoryClient::new(&env, &factory_id).initialize(
+            &admin,
+            &usdc_client.address,
+            &treasury_id,
+        );
+        crate::treasury::TreasuryClient::new(&env, &treasury_id).initialize(
+            &admin,
+            &usdc_client.address,
+            &factory_id,
+        );
 
         let market_id = BytesN::from_array(&env, &[0; 32]);
         let market_contract_id = env.register(PredictionMarket, ());
         let market_client = PredictionMarketClient::new(&env, &market_contract_id);
-        let oracle_contract_id = env.register(MockOracle, ());
-        let admin = Address::generate(&env);
-        let usdc_client = create_token_contract(&env, &admin);
-
+        let creator = Address::generate(&env);
+        usdc_client.mint(&creator, &creator_bond);
         market_client.initialize(
             &market_id,
-            &Address::generate(&env),
-            &Address::generate(&env),
+            &creator,
+            &factory_id,
             &usdc_client.address,
-            &oracle_contract_id,
+            &env.register(MockOracle, ()),
             &2000,
             &3000,
+            &Vec::new(&env),
+            &creator_bond,
+            &Vec::new(&env),
         );
+        market_client.set_reveal_bond(&creator, &bond);
 
         let user = Address::generate(&env);
-        let dispute_reason = Symbol::new(&env, "wrong");
+        usdc_client.mint(&user, &10_000);
 
-        // Market is OPEN, not RESOLVED
-        market_client.dispute_market(&user, &market_id, &dispute_reason, &None);
+        (
+            env,
+            market_id,
+            market_client,
+            usdc_client,
+            user,
+            treasury_id,
+        )
+    }
+
+    #[test]
+    fn test_creator_bond_returned_on_resolution() {
+        let (env, market_id, market_client, usdc_client, _user, treasury_id) =
+            setup_treasury_backed_market(0, 5_000);
+        let creator = stored_creator(&env, &market_client);
+        assert_eq!(usdc_client.balance(&creator), 0);
+        assert_eq!(market_client.get_creator_bond(), 5_000);
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        let oracle = stored_oracle(&env, &market_client).address;
+        market_client.close_market(&oracle, &market_id);
+        env.ledger().with_mut(|li| li.timestamp = 3000);
+        market_client.resolve_market(&oracle, &market_id);
+
+        assert_eq!(usdc_client.balance(&creator), 5_000);
+        assert_eq!(usdc_client.balance(&treasury_id), 0);
+        assert_eq!(market_client.get_creator_bond(), 0);
+    }
+
+    #[test]
+    fn test_creator_bond_slashed_on_creator_cancel() {
+        let (env, market_id, market_client, usdc_client, _user, treasury_id) =
+            setup_treasury_backed_market(0, 5_000);
+        let creator = stored_creator(&env, &market_client);
+
+        market_client.cancel_market(&creator, &market_id);
+
+        assert_eq!(usdc_client.balance(&creator), 0);
+        assert_eq!(usdc_client.balance(&treasury_id), 5_000);
+        assert_eq!(market_client.get_creator_bond(), 0);
+    }
+
+    #[test]
+    fn test_commit_event_carries_bucketed_amount() {
+        let (env, _market_id, market_client, _usdc_client, user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        market_client.set_commit_amount_bucket(&creator, &100);
+        assert_eq!(market_client.get_commit_amount_bucket(), 100);
+
+        market_client.commit_prediction(&user, &BytesN::from_array(&env, &[1; 32]), &345);
+
+        let fields = last_event_fields(&env, "commitment_made_event");
+        let event_amount =
+            i128::try_from_val(&env, &fields.get(Symbol::new(&env, "amount")).unwrap()).unwrap();
+        assert_eq!(event_amount, 300);
+        assert_eq!(market_client.get_committed_amount(&user), Some(345));
+    }
+
+    #[test]
+    #[should_panic(expected = "Commit amount bucket cannot be negative")]
+    fn test_commit_amount_bucket_rejects_negative() {
+        let (env, _market_id, market_client, _usdc_client, _user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        market_client.set_commit_amount_bucket(&creator, &-1);
+    }
+
+    #[test]
+    fn test_max_pending_applies_back_pressure_until_reveals() {
+        let (env, market_id, market_client, usdc_client, _user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+        market_client.set_max_pending(&creator, &2);
+
+        let mut users = Vec::new(&env);
+        let mut hashes = Vec::new(&env);
+        for seed in 1..=4u8 {
+            let user = Address::generate(&env);
+            usdc_client.mint(&user, &1_000);
+            let salt = BytesN::from_array(&env, &[seed; 32]);
+            hashes.push_back(compute_commit_hash(&env, &market_id, &user, 1, &salt));
+            users.push_back(user);
+        }
+        let commit = |i: u32| {
+            market_client.try_commit_prediction(
+                &users.get(i).unwrap(),
+                &hashes.get(i).unwrap(),
+                &100,
+            )
+        };
+
+        assert_eq!(commit(0), Ok(Ok(())));
+        assert_eq!(commit(1), Ok(Ok(())));
+        assert_eq!(commit(2), Err(Ok(MarketError::TooManyPending)));
+
+        next_ledger(&env);
+        let salt = BytesN::from_array(&env, &[1; 32]);
+        market_client.reveal_prediction(&users.get(0).unwrap(), &market_id, &1, &100, &salt);
+        assert_eq!(market_client.get_pending_count(), 1);
+
+        assert_eq!(commit(2), Ok(Ok(())));
+        assert_eq!(commit(3), Err(Ok(MarketError::TooManyPending)));
+        assert_eq!(market_client.get_pending_count(), 2);
+    }
+
+    #[test]
+    fn test_identical_commit_hash_rejected_for_second_user() {
+        let (env, market_id, market_client, usdc_client, user) = setup_reveal_test();
+        let other = Address::generate(&env);
+        usdc_client.mint(&other, &1_000);
+
+        let salt = BytesN::from_array(&env, &[7; 32]);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, 1, &salt);
+        market_client.commit_prediction(&user, &commit_hash, &100);
+
+        assert_eq!(
+            market_client.try_commit_prediction(&other, &commit_hash, &100),
+            Err(Ok(MarketError::DuplicateCommitHash))
+        );
+        assert_eq!(market_client.get_pending_count(), 1);
+        assert_eq!(
+            token::TokenClient::new(&env, &usdc_client.address).balance(&other),
+            1_000
+        );
+    }
+
+    #[test]
+    fn test_reveal_bond_returned_on_reveal() {
+        let (env, market_id, market_client, usdc_client, user, _treasury) = setup_bonded_market(50);
+
+        let salt = BytesN::from_array(&env, &[3; 32]);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, 1, &salt);
+        market_client.commit_prediction(&user, &commit_hash, &500);
+
+        assert_eq!(market_client.get_commitment(&user).unwrap().bond, 50);
+        assert_eq!(usdc_client.balance(&user), 10_000 - 550);
+
+        next_ledger(&env);
+        market_client.reveal_prediction(&user, &market_id, &1, &500, &salt);
+
+        assert_eq!(usdc_client.balance(&user), 10_000 - 500);
+        assert_eq!(usdc_client.balance(&market_client.address), 500);
+    }
+
+    #[test]
+    fn test_reveal_bond_forfeited_to_treasury_on_non_reveal() {
+        let (env, market_id, market_client, usdc_client, user, treasury) = setup_bonded_market(50);
+
+        let salt = BytesN::from_array(&env, &[4; 32]);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, 0, &salt);
+        market_client.commit_prediction(&user, &commit_hash, &500);
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        assert_eq!(market_client.forfeit_reveal_bond(&user), 50);
+
+        assert_eq!(usdc_client.balance(&treasury), 50);
+        assert_eq!(usdc_client.balance(&market_client.address), 500);
+        assert_eq!(market_client.get_commitment(&user).unwrap().bond, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Reveal window still open")]
+    fn test_reveal_bond_cannot_be_forfeited_before_close() {
+        let (env, market_id, market_client, _usdc_client, user, _treasury) =
+            setup_bonded_market(50);
+
+        let salt = BytesN::from_array(&env, &[5; 32]);
+        let commit_hash = compute_commit_hash(&env, &market_id, &user, 0, &salt);
+        market_client.commit_prediction(&user, &commit_hash, &500);
+
+        market_client.forfeit_reveal_bond(&user);
+    }
+
+    #[test]
+    #[should_panic(expected = "Weighting mode is locked once predictions exist")]
+    fn test_quadratic_weighting_locked_after_first_commit() {
+        let (env, _market_id, market_client, _usdc_client, user) = setup_reveal_test();
+        let creator = stored_creator(&env, &market_client);
+
+        market_client.commit_prediction(&user, &BytesN::from_array(&env, &[1; 32]), &100);
+        market_client.set_quadratic_weighting(&creator, &true);
     }
 }
 
@@ -2835,6 +7991,9 @@ mod market_leaderboard_tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         // Setup: 3 winners with different payouts
@@ -2895,6 +8054,9 @@ mod market_leaderboard_tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &500);
@@ -2947,6 +8109,9 @@ mod market_leaderboard_tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &500);
@@ -2978,6 +8143,9 @@ mod market_leaderboard_tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         // No winner shares (edge case)
@@ -3011,6 +8179,9 @@ mod market_leaderboard_tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         // Market is still OPEN (not resolved)
@@ -3039,6 +8210,9 @@ mod market_leaderboard_tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         // Winning outcome is YES (1)
@@ -3090,6 +8264,9 @@ mod market_leaderboard_tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &500);
@@ -3124,6 +8301,64 @@ mod market_leaderboard_tests {
         assert_eq!(w3.1, 270);
     }
 
+    #[test]
+    fn test_get_market_leaderboard_tie_order_is_deterministic() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let oracle_contract_id = env.register(super::tests::MockOracle, ());
+
+        let token_admin = Address::generate(&env);
+        let usdc_client = create_token_contract(&env, &token_admin);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &usdc_client.address,
+            &oracle_contract_id,
+            &2000,
+            &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
+        );
+
+        market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &500);
+
+        let tied_a = Address::generate(&env);
+        let tied_b = Address::generate(&env);
+        let tied_c = Address::generate(&env);
+        let top = Address::generate(&env);
+        market_client.test_set_prediction(&tied_a, &1u32, &200);
+        market_client.test_set_prediction(&tied_b, &1u32, &200);
+        market_client.test_set_prediction(&tied_c, &1u32, &200);
+        market_client.test_set_prediction(&top, &1u32, &400);
+
+        let forward = Vec::from_array(
+            &env,
+            [tied_a.clone(), tied_b.clone(), top.clone(), tied_c.clone()],
+        );
+        let reversed = Vec::from_array(&env, [tied_c, top.clone(), tied_b, tied_a]);
+
+        let first = market_client.test_get_leaderboard_with_users(&market_id_bytes, &3, &forward);
+        let second = market_client.test_get_leaderboard_with_users(&market_id_bytes, &3, &forward);
+        let shuffled =
+            market_client.test_get_leaderboard_with_users(&market_id_bytes, &3, &reversed);
+
+        assert_eq!(first, second);
+        assert_eq!(first, shuffled);
+        assert_eq!(first.get(0).unwrap(), (top, 540));
+        // Tied payouts are ordered by address
+        let (tie1, payout1) = first.get(1).unwrap();
+        let (tie2, payout2) = first.get(2).unwrap();
+        assert_eq!((payout1, payout2), (270, 270));
+        assert!(tie1 < tie2);
+    }
+
     #[test]
     fn test_get_market_leaderboard_limit_exceeds_total() {
         let env = Env::default();
@@ -3145,6 +8380,9 @@ mod market_leaderboard_tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
         );
 
         market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &500);
@@ -3164,4 +8402,282 @@ mod market_leaderboard_tests {
 
         assert_eq!(winners.len(), 2);
     }
+    /// Helper: Resolved market with a 900 whale and a 100 minnow on YES against 500 on NO
+    fn setup_weighted_market(
+        env: &Env,
+        quadratic: bool,
+    ) -> (PredictionMarketClient<'_>, Address, Address) {
+        let market_id_bytes = BytesN::from_array(env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(env, &market_contract_id);
+        let oracle_contract_id = env.register(super::tests::MockOracle, ());
+
+        let token_admin = Address::generate(env);
+        let usdc_client = create_token_contract(env, &token_admin);
+        let creator = Address::generate(env);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &creator,
+            &Address::generate(env),
+            &usdc_client.address,
+            &oracle_contract_id,
+            &2000,
+            &3000,
+            &Vec::new(env),
+            &0,
+            &Vec::new(env),
+        );
+        market_client.set_quadratic_weighting(&creator, &quadratic);
+        usdc_client.mint(&market_contract_id, &1500);
+
+        let whale = Address::generate(env);
+        let minnow = Address::generate(env);
+        market_client.test_set_prediction(&whale, &1u32, &900);
+        market_client.test_set_prediction(&minnow, &1u32, &100);
+        market_client.test_set_prediction(&Address::generate(env), &0u32, &500);
+        market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &500);
+
+        (market_client, whale, minnow)
+    }
+
+    #[test]
+    fn test_claim_winnings_linear_vs_quadratic_weighting() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+
+        // Linear: 900/1000 and 100/1000 of 1500, minus 10%
+        let (linear, whale, minnow) = setup_weighted_market(&env, false);
+        assert!(!linear.is_quadratic_weighting());
+        assert_eq!(linear.claim_winnings(&whale, &market_id_bytes), 1215);
+        assert_eq!(linear.claim_winnings(&minnow, &market_id_bytes), 135);
+
+        // Quadratic: sqrt weights 30 and 10 of 40 -> 1125 and 375 gross, minus 10% (rounded up)
+        let (quadratic, whale, minnow) = setup_weighted_market(&env, true);
+        assert!(quadratic.is_quadratic_weighting());
+        assert_eq!(quadratic.claim_winnings(&whale, &market_id_bytes), 1012);
+        assert_eq!(quadratic.claim_winnings(&minnow, &market_id_bytes), 337);
+        assert_eq!(quadratic.get_liabilities(), 0);
+    }
+
+    #[test]
+    fn test_quadratic_total_weight_fixed_at_resolution() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+
+        // Claims read the weight stored at resolution rather than rescanning reveals
+        let (quadratic, whale, _minnow) = setup_weighted_market(&env, true);
+        quadratic.test_set_prediction(&Address::generate(&env), &1u32, &400);
+        assert_eq!(quadratic.claim_winnings(&whale, &market_id_bytes), 1012);
+    }
+
+    /// Helper: Resolved YES market with the given YES stakes against `no_stake` on NO
+    fn setup_protected_market<'a>(
+        env: &'a Env,
+        quadratic: bool,
+        protected: bool,
+        yes_stakes: &[i128],
+        no_stake: i128,
+    ) -> (
+        PredictionMarketClient<'a>,
+        token::StellarAssetClient<'a>,
+        Vec<Address>,
+    ) {
+        let (market_client, usdc_client) = setup_open_market(env, quadratic, protected);
+        let winners = resolve_with_stakes(env, &market_client, &usdc_client, yes_stakes, no_stake);
+        (market_client, usdc_client, winners)
+    }
+
+    /// Helper: Open market with the given weighting and principal protection settings
+    fn setup_open_market<'a>(
+        env: &'a Env,
+        quadratic: bool,
+        protected: bool,
+    ) -> (PredictionMarketClient<'a>, token::StellarAssetClient<'a>) {
+        let market_id_bytes = BytesN::from_array(env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(env, &market_contract_id);
+        let oracle_contract_id = env.register(super::tests::MockOracle, ());
+
+        let token_admin = Address::generate(env);
+        let usdc_client = create_token_contract(env, &token_admin);
+        let creator = Address::generate(env);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &creator,
+            &Address::generate(env),
+            &usdc_client.address,
+            &oracle_contract_id,
+            &2000,
+            &3000,
+            &Vec::new(env),
+            &0,
+            &Vec::new(env),
+        );
+        market_client.set_quadratic_weighting(&creator, &quadratic);
+        market_client.set_principal_protection(&creator, &protected);
+
+        (market_client, usdc_client)
+    }
+
+    /// Helper: Stake `yes_stakes` on YES and `no_stake` on NO, then resolve YES
+    fn resolve_with_stakes(
+        env: &Env,
+        market_client: &PredictionMarketClient,
+        usdc_client: &token::StellarAssetClient,
+        yes_stakes: &[i128],
+        no_stake: i128,
+    ) -> Vec<Address> {
+        let market_id_bytes = BytesN::from_array(env, &[0; 32]);
+        let mut winners = Vec::new(env);
+        let mut winner_shares = 0;
+        for stake in yes_stakes.iter() {
+            let user = Address::generate(env);
+            market_client.test_add_participant(&user);
+            market_client.test_set_prediction(&user, &1u32, stake);
+            winners.push_back(user);
+            winner_shares += stake;
+        }
+        if no_stake > 0 {
+            market_client.test_set_prediction(&Address::generate(env), &0u32, &no_stake);
+        }
+        usdc_client.mint(&market_client.address, &(winner_shares + no_stake));
+        market_client.test_setup_resolution(&market_id_bytes, &1u32, &winner_shares, &no_stake);
+
+        winners
+    }
+
+    #[test]
+    fn test_principal_protection_floors_thin_market_payout() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+
+        // A sole 1000 winner against 50 of losers grosses 1050 and nets 945 after the fee
+        let (unprotected, _, winners) = setup_protected_market(&env, false, false, &[1000], 50);
+        assert!(!unprotected.is_principal_protected());
+        assert_eq!(
+            unprotected.claim_winnings(&winners.get(0).unwrap(), &market_id_bytes),
+            945
+        );
+
+        // Protected: the fee is capped at the 50 of profit so the stake comes back whole
+        let (protected, _, winners) = setup_protected_market(&env, false, true, &[1000], 50);
+        assert!(protected.is_principal_protected());
+        let (gross, protocol_fee, creator_fee, net) =
+            protected.claim_winnings_verbose(&winners.get(0).unwrap(), &market_id_bytes);
+        assert_eq!(net, 1000);
+        assert_eq!(gross, 1050);
+        assert_eq!(protocol_fee + creator_fee, 50);
+    }
+
+    #[test]
+    fn test_principal_protection_draws_shortfall_from_insurance() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+
+        // Quadratic with no losers: sqrt weights 30 and 10 split 1000 as 750 / 250, so the
+        // 900 whale's pool payout is below its stake even with the fee waived
+        let (market_client, usdc_client, winners) =
+            setup_protected_market(&env, true, true, &[900, 100], 0);
+        let funder = Address::generate(&env);
+        usdc_client.mint(&funder, &100);
+        market_client.fund_insurance(&funder, &100);
+
+        let whale = winners.get(0).unwrap();
+        let (gross, protocol_fee, creator_fee, net) =
+            market_client.claim_winnings_verbose(&whale, &market_id_bytes);
+        assert_eq!((protocol_fee, creator_fee), (0, 0));
+        assert_eq!(net, 850);
+        assert_eq!(gross, 850);
+        assert_eq!(market_client.get_insurance_fund(), 0);
+        assert_eq!(usdc_client.balance(&whale), 850);
+
+        // The minnow is already above principal and pays the normal fee
+        let minnow = winners.get(1).unwrap();
+        assert_eq!(market_client.claim_winnings(&minnow, &market_id_bytes), 225);
+    }
+
+    /// Helper: Creator address stored by the market
+    fn market_creator(env: &Env, market_client: &PredictionMarketClient) -> Address {
+        env.as_contract(&market_client.address, || {
+            env.storage()
+                .persistent()
+                .get(&Symbol::new(env, CREATOR_KEY))
+                .unwrap()
+        })
+    }
+
+    #[test]
+    fn test_configured_fee_bps_applies_to_claims_and_leaderboard() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+
+        let (market_client, usdc_client) = setup_open_market(&env, false, false);
+        assert_eq!(market_client.get_fee_bps(), 1000);
+
+        market_client.set_fee_bps(&market_creator(&env, &market_client), &500);
+        assert_eq!(market_client.get_fee_bps(), 500);
+        let winners = resolve_with_stakes(&env, &market_client, &usdc_client, &[600, 400], 1000);
+
+        // (600 / 1000) * 2000 = 1200 minus 5% = 1140; (400 / 1000) * 2000 = 800 minus 5% = 760
+        let (user1, user2) = (winners.get(0).unwrap(), winners.get(1).unwrap());
+        assert_eq!(
+            market_client.get_market_leaderboard(&market_id_bytes, &10),
+            Vec::from_array(&env, [(user1.clone(), 1140), (user2.clone(), 760)])
+        );
+        assert_eq!(
+            market_client.test_get_leaderboard_with_users(&market_id_bytes, &10, &winners),
+            Vec::from_array(&env, [(user1.clone(), 1140), (user2, 760)])
+        );
+        assert_eq!(market_client.claim_winnings(&user1, &market_id_bytes), 1140);
+    }
+
+    #[test]
+    #[should_panic(expected = "Fee exceeds maximum")]
+    fn test_set_fee_bps_over_cap_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (market_client, _) = setup_open_market(&env, false, false);
+        let creator = market_creator(&env, &market_client);
+        market_client.set_fee_bps(&creator, &3000);
+        market_client.set_fee_bps(&creator, &3001);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only creator can manage this market")]
+    fn test_set_fee_bps_creator_only() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (market_client, _) = setup_open_market(&env, false, false);
+        market_client.set_fee_bps(&Address::generate(&env), &500);
+    }
+
+    #[test]
+    fn test_leaderboard_linear_vs_quadratic_weighting() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+
+        let (linear, whale, minnow) = setup_weighted_market(&env, false);
+        let users = Vec::from_array(&env, [whale.clone(), minnow.clone()]);
+        assert_eq!(
+            linear.test_get_leaderboard_with_users(&market_id_bytes, &10, &users),
+            Vec::from_array(&env, [(whale.clone(), 1215), (minnow.clone(), 135)])
+        );
+
+        let (quadratic, whale, minnow) = setup_weighted_market(&env, true);
+        let users = Vec::from_array(&env, [whale.clone(), minnow.clone()]);
+        assert_eq!(
+            quadratic.test_get_leaderboard_with_users(&market_id_bytes, &10, &users),
+            Vec::from_array(&env, [(whale, 1012), (minnow, 337)])
+        );
+    }
 }
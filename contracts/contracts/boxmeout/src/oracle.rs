@@ -1,8 +1,12 @@
 // contract/src/oracle.rs - Oracle & Market Resolution Contract Implementation
 // Handles multi-source oracle consensus for market resolution
 
+// `initialize` carries the full staking and challenge-penalty configuration
+#![allow(clippy::too_many_arguments)]
+
 use soroban_sdk::{
-    contract, contractevent, contractimpl, contracttype, Address, BytesN, Env, Symbol, Vec,
+    contract, contracterror, contractevent, contractimpl, contracttype, token, Address, Bytes,
+    BytesN, Env, Symbol, Vec,
 };
 
 #[contractevent]
@@ -37,6 +41,19 @@ pub struct AttestationSubmittedEvent {
     pub attestation_result: u32,
 }
 
+#[contractevent]
+pub struct AttestationCommittedEvent {
+    pub market_id: BytesN<32>,
+    pub oracle: Address,
+    pub commit_hash: BytesN<32>,
+}
+
+#[contractevent]
+pub struct SignedFeedRegisteredEvent {
+    pub feed_pubkey: BytesN<32>,
+    pub identity: Address,
+}
+
 #[contractevent]
 pub struct ResolutionFinalizedEvent {
     pub market_id: BytesN<32>,
@@ -44,6 +61,20 @@ pub struct ResolutionFinalizedEvent {
     pub timestamp: u64,
 }
 
+#[contractevent]
+pub struct ResolutionRewardFundedEvent {
+    pub market_id: BytesN<32>,
+    pub funder: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct ResolutionRewardClaimedEvent {
+    pub market_id: BytesN<32>,
+    pub oracle: Address,
+    pub amount: i128,
+}
+
 #[contractevent]
 pub struct AttestationChallengedEvent {
     pub oracle: Address,
@@ -61,6 +92,25 @@ pub struct ChallengeResolvedEvent {
     pub slashed_amount: i128,
 }
 
+#[contractevent]
+pub struct AccuracyRecomputedEvent {
+    pub oracle: Address,
+    pub accuracy: u32,
+    pub markets_compared: u32,
+}
+
+#[contractevent]
+pub struct MarketAbandonedEvent {
+    pub market_id: BytesN<32>,
+    pub released_challenges: u32,
+}
+
+#[contractevent]
+pub struct ConsensusDeadlineMissedEvent {
+    pub market_id: BytesN<32>,
+    pub deadline: u64,
+}
+
 // Storage keys
 const ADMIN_KEY: &str = "admin";
 const REQUIRED_CONSENSUS_KEY: &str = "required_consensus";
@@ -74,6 +124,138 @@ const LAST_OVERRIDE_TIME_KEY: &str = "last_override"; // Timestamp of last emerg
 const OVERRIDE_COOLDOWN_KEY: &str = "override_cooldown"; // Cooldown period in seconds (default 86400 = 24h)
 const CHALLENGE_STAKE_AMOUNT: i128 = 1000; // Minimum stake required to challenge
 const ORACLE_STAKE_KEY: &str = "oracle_stake"; // Oracle's staked amount
+const SIGNED_FEED_KEY: &str = "signed_feed"; // Ed25519 feed public key -> attesting identity
+const SIGNED_NONCE_KEY: &str = "signed_nonce"; // Nonces already consumed per signed feed
+const SUPERMAJORITY_BPS_KEY: &str = "supermajority_bps"; // Winning share of cast votes required (0 = off)
+const ATTEST_COMMIT_KEY: &str = "attest_commit"; // Sealed attestation hash per (market, oracle)
+const SEALED_VOTING_KEY: &str = "sealed_voting"; // Markets that only take commit-reveal attestations
+const ATTEST_COMMIT_WINDOW: u64 = 86400; // Sealed votes accepted for 24h after resolution_time
+const ATTEST_REVEAL_WINDOW: u64 = 86400; // Then revealed during the following 24h
+const ORACLE_WEIGHT_KEY: &str = "oracle_weight"; // Fixed expert weight per oracle (default 1)
+const ORACLE_MARKETS_KEY: &str = "oracle_markets"; // Markets an oracle has attested on
+const MARKET_CONTRACT_KEY: &str = "mkt_contract"; // Market contract allowed to abandon a market_id
+const MARKET_ABANDONED_KEY: &str = "mkt_abandoned"; // Market cancelled; attestations no longer accepted
+const RELEASED_STAKE_KEY: &str = "released_stake"; // Challenge stake released back to a challenger
+const MIN_ORACLE_AGE_KEY: &str = "min_oracle_age"; // Seconds an oracle must be registered before attesting
+const REGISTRAR_KEY: &str = "registrar"; // Manages the oracle set (defaults to admin)
+const RESOLVER_KEY: &str = "resolver"; // Arbitrates challenges (defaults to admin)
+const STAKE_TOKEN_KEY: &str = "stake_token"; // Token oracles lock as registration stake
+const REGISTRATION_STAKE_KEY: &str = "registration_stake"; // Stake each oracle must lock to register
+const SLASH_BPS_KEY: &str = "slash_bps"; // Share of stake slashed on a valid challenge
+const ACCURACY_PENALTY_KEY: &str = "accuracy_penalty"; // Accuracy points lost on a valid challenge
+const ACCURACY_REWARD_KEY: &str = "accuracy_reward"; // Accuracy points gained on an invalid challenge
+const REWARD_BUDGET_KEY: &str = "reward_budget"; // Resolution reward budget per market (stake token)
+const REWARD_CLAIMED_KEY: &str = "reward_claimed"; // Oracle already claimed its share of a market's budget
+const REWARD_SPLIT_KEY: &str = "reward_split"; // (budget, agreeing oracles) frozen when a market is finalized
+const CONSENSUS_DEADLINE_KEY: &str = "consensus_deadline"; // After this, anyone may abandon a market without consensus
+const REGISTERED_MARKETS_KEY: &str = "registered_markets"; // Every registered market_id, in registration order
+const CHALLENGE_COOLDOWN_KEY: &str = "challenge_cooldown"; // Seconds a challenger waits between challenges (0 = off)
+const MAX_OPEN_CHALLENGES_KEY: &str = "max_open_challenges"; // Unresolved challenges allowed per challenger (0 = no cap)
+const LAST_CHALLENGE_KEY: &str = "last_challenge"; // Timestamp of a challenger's latest challenge
+const OPEN_CHALLENGES_KEY: &str = "open_challenges"; // Unresolved challenges per challenger
+
+/// Oracle error codes
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum OracleError {
+    /// Registration stake is negative
+    InvalidRegistrationStake = 1,
+    /// Slash share exceeds 10000 bps
+    InvalidSlashBps = 2,
+    /// Accuracy penalty or reward exceeds 100 points
+    InvalidAccuracyAdjustment = 3,
+    /// The oracle set is full
+    OracleLimitReached = 4,
+    /// Oracle is already registered
+    OracleAlreadyRegistered = 5,
+    /// Registration stake could not be transferred from the oracle
+    StakeTransferFailed = 6,
+    /// Oracle is not registered or has been deregistered
+    OracleNotRegistered = 7,
+    /// Market has not been registered with the oracle
+    MarketNotRegistered = 8,
+    /// No market contract is bound to this market_id
+    MarketContractNotBound = 9,
+    /// Market was already abandoned
+    MarketAlreadyAbandoned = 10,
+    /// Market was cancelled and no longer accepts attestations or challenges
+    MarketAbandoned = 11,
+    /// Attestation batch is empty
+    EmptyBatch = 12,
+    /// Market resolution time has not been reached
+    TooEarlyToAttest = 13,
+    /// Oracle has not been registered for the minimum age
+    OracleTooNew = 14,
+    /// Sealed attestation commit window has closed
+    CommitWindowClosed = 15,
+    /// Oracle already attested or committed on this market
+    AlreadyAttested = 16,
+    /// Sealed attestation reveal window has not opened
+    RevealWindowNotOpen = 17,
+    /// Sealed attestation reveal window has closed
+    RevealWindowClosed = 18,
+    /// Oracle has no sealed attestation on this market
+    CommitmentNotFound = 19,
+    /// Revealed outcome and salt do not match the commitment
+    CommitmentMismatch = 20,
+    /// Signed feed is not trusted
+    SignedFeedNotRegistered = 21,
+    /// Signed feed nonce was already consumed
+    NonceAlreadyUsed = 22,
+    /// Outcome must be 0 (NO) or 1 (YES)
+    InvalidOutcome = 23,
+    /// Oracle weight must be positive
+    InvalidWeight = 24,
+    /// Supermajority must be above 50% and at most 100%
+    InvalidSupermajority = 25,
+    /// Attestations have not reached consensus
+    ConsensusNotReached = 26,
+    /// Dispute period after resolution time has not elapsed
+    DisputePeriodNotElapsed = 27,
+    /// Oracle has not attested on this market
+    AttestationNotFound = 28,
+    /// Attestation is already being challenged
+    ChallengeAlreadyExists = 29,
+    /// No challenge exists for this oracle and market
+    ChallengeNotFound = 30,
+    /// Challenge was already resolved
+    ChallengeAlreadyResolved = 31,
+    /// Fewer approvers than the required signatures
+    InsufficientApprovers = 32,
+    /// Approver is not an admin signer
+    InvalidApprover = 33,
+    /// The same admin approved more than once
+    DuplicateApprovers = 34,
+    /// Emergency override cooldown has not elapsed
+    OverrideCooldownActive = 35,
+    /// Fewer oracles are registered than `required_consensus`
+    InsufficientOracles = 36,
+    /// Reward amount must be positive
+    InvalidRewardAmount = 37,
+    /// Market resolution has not been finalized
+    ResolutionNotFinalized = 38,
+    /// Oracle did not attest the finalized outcome
+    RewardNotEarned = 39,
+    /// Oracle already claimed its resolution reward for this market
+    RewardAlreadyClaimed = 40,
+    /// Consensus deadline must be after the market's resolution time
+    InvalidConsensusDeadline = 41,
+    /// Consensus deadline has not passed, or consensus formed in time
+    ConsensusDeadlineNotMissed = 42,
+    /// Challenger's cooldown since their last challenge has not elapsed
+    ChallengeCooldownActive = 43,
+    /// Challenger already has the maximum number of unresolved challenges
+    TooManyOpenChallenges = 44,
+    /// The market's outcome is final; no more attestations or reward funding
+    ResolutionAlreadyFinalized = 45,
+    /// Market takes sealed attestations only; commit and reveal instead
+    SealedVotingRequired = 46,
+    /// Market takes plaintext attestations; sealed voting is not enabled
+    SealedVotingNotEnabled = 47,
+    /// Voting on the market has opened, so its voting mode is fixed
+    VotingAlreadyOpen = 48,
+}
 
 /// Attestation record for market resolution
 #[contracttype]
@@ -123,10 +305,32 @@ pub struct OracleManager;
 #[contractimpl]
 impl OracleManager {
     /// Initialize oracle system with validator set and multi-sig admins
-    pub fn initialize(env: Env, admin: Address, required_consensus: u32) {
+    ///
+    /// `registration_stake` is the amount of `stake_token` each oracle must lock
+    /// when registering (0 disables the requirement).
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        required_consensus: u32,
+        stake_token: Address,
+        registration_stake: i128,
+        slash_bps: u32,
+        accuracy_penalty: u32,
+        accuracy_reward: u32,
+    ) -> Result<(), OracleError> {
         // Verify admin signature
         admin.require_auth();
 
+        if registration_stake < 0 {
+            return Err(OracleError::InvalidRegistrationStake);
+        }
+        if slash_bps > 10000 {
+            return Err(OracleError::InvalidSlashBps);
+        }
+        if accuracy_penalty > 100 || accuracy_reward > 100 {
+            return Err(OracleError::InvalidAccuracyAdjustment);
+        }
+
         // Store admin
         env.storage()
             .persistent()
@@ -138,6 +342,26 @@ impl OracleManager {
             &required_consensus,
         );
 
+        // Store registration staking config
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, STAKE_TOKEN_KEY), &stake_token);
+        env.storage().persistent().set(
+            &Symbol::new(&env, REGISTRATION_STAKE_KEY),
+            &registration_stake,
+        );
+
+        // Store challenge outcome parameters
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, SLASH_BPS_KEY), &slash_bps);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, ACCURACY_PENALTY_KEY), &accuracy_penalty);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, ACCURACY_REWARD_KEY), &accuracy_reward);
+
         // Initialize oracle counter
         env.storage()
             .persistent()
@@ -171,17 +395,21 @@ impl OracleManager {
             required_consensus,
         }
         .publish(&env);
+
+        Ok(())
     }
 
     /// Register a new oracle node
-    pub fn register_oracle(env: Env, oracle: Address, oracle_name: Symbol) {
-        // Require admin authentication
-        let admin: Address = env
-            .storage()
-            .persistent()
-            .get(&Symbol::new(&env, ADMIN_KEY))
-            .unwrap();
-        admin.require_auth();
+    ///
+    /// The oracle must lock the configured registration stake, which becomes its
+    /// slashable stake. Registration is rejected if the transfer fails.
+    pub fn register_oracle(
+        env: Env,
+        oracle: Address,
+        oracle_name: Symbol,
+    ) -> Result<(), OracleError> {
+        // Require registrar authentication
+        Self::require_registrar(&env);
 
         // Get current oracle count
         let oracle_count: u32 = env
@@ -192,7 +420,7 @@ impl OracleManager {
 
         // Validate total_oracles < max_oracles (max 10 oracles)
         if oracle_count >= 10 {
-            panic!("Maximum oracle limit reached");
+            return Err(OracleError::OracleLimitReached);
         }
 
         // Create storage key for this oracle using the oracle address
@@ -202,7 +430,21 @@ impl OracleManager {
         let is_registered: bool = env.storage().persistent().has(&oracle_key);
 
         if is_registered {
-            panic!("Oracle already registered");
+            return Err(OracleError::OracleAlreadyRegistered);
+        }
+
+        // Lock the registration stake (required for slashing)
+        let registration_stake = Self::get_registration_stake(env.clone());
+        if registration_stake > 0 {
+            oracle.require_auth();
+            let stake_token = Self::get_stake_token(env.clone());
+            let token_client = token::Client::new(&env, &stake_token);
+            if token_client
+                .try_transfer(&oracle, env.current_contract_address(), &registration_stake)
+                .is_err()
+            {
+                return Err(OracleError::StakeTransferFailed);
+            }
         }
 
         // Store oracle metadata
@@ -218,11 +460,11 @@ impl OracleManager {
         let accuracy_key = (Symbol::new(&env, "oracle_accuracy"), oracle.clone());
         env.storage().persistent().set(&accuracy_key, &100u32);
 
-        // Initialize oracle's stake (required for slashing)
+        // Record the locked stake
         let stake_key = (Symbol::new(&env, ORACLE_STAKE_KEY), oracle.clone());
         env.storage()
             .persistent()
-            .set(&stake_key, &(CHALLENGE_STAKE_AMOUNT * 10)); // 10x challenge stake
+            .set(&stake_key, &registration_stake);
 
         // Store registration timestamp
         let timestamp_key = (Symbol::new(&env, "oracle_timestamp"), oracle.clone());
@@ -242,33 +484,44 @@ impl OracleManager {
             timestamp: env.ledger().timestamp(),
         }
         .publish(&env);
+
+        Ok(())
     }
 
     /// Deregister an oracle node
     ///
-    /// Admin-only function that removes an oracle from the active set.
-    /// Marks the oracle as inactive (keeps history) and recalculates the
-    /// consensus threshold. Existing attestations are not affected.
-    pub fn deregister_oracle(env: Env, oracle: Address) {
-        // 1. Require admin authentication
-        let admin: Address = env
-            .storage()
-            .persistent()
-            .get(&Symbol::new(&env, ADMIN_KEY))
-            .expect("Contract not initialized");
-        admin.require_auth();
+    /// Registrar-only function that removes an oracle from the active set.
+    /// Marks the oracle as inactive (keeps history), refunds whatever is left of
+    /// its registration stake after slashing and recalculates the consensus
+    /// threshold. Existing attestations are not affected.
+    pub fn deregister_oracle(env: Env, oracle: Address) -> Result<(), OracleError> {
+        // 1. Require registrar authentication
+        Self::require_registrar(&env);
 
         // 2. Validate oracle is currently registered and active
         let oracle_key = (Symbol::new(&env, "oracle"), oracle.clone());
         let is_active: bool = env.storage().persistent().get(&oracle_key).unwrap_or(false);
 
         if !is_active {
-            panic!("Oracle not registered or already inactive");
+            return Err(OracleError::OracleNotRegistered);
         }
 
         // 3. Mark oracle as inactive (don't delete, keep for history)
         env.storage().persistent().set(&oracle_key, &false);
 
+        // Refund the stake left after any slashing
+        let stake_key = (Symbol::new(&env, ORACLE_STAKE_KEY), oracle.clone());
+        let remaining_stake: i128 = env.storage().persistent().get(&stake_key).unwrap_or(0);
+        if remaining_stake > 0 {
+            env.storage().persistent().set(&stake_key, &0i128);
+            let stake_token = Self::get_stake_token(env.clone());
+            token::Client::new(&env, &stake_token).transfer(
+                &env.current_contract_address(),
+                &oracle,
+                &remaining_stake,
+            );
+        }
+
         // 4. Decrement oracle count
         let oracle_count: u32 = env
             .storage()
@@ -301,11 +554,19 @@ impl OracleManager {
             timestamp: env.ledger().timestamp(),
         }
         .publish(&env);
+
+        Ok(())
     }
 
     /// Register a market with its resolution time for attestation validation
     /// Must be called before oracles can submit attestations for this market.
-    pub fn register_market(env: Env, market_id: BytesN<32>, resolution_time: u64) {
+    /// Rejected while fewer than `required_consensus` oracles are registered, since
+    /// such a market could never reach consensus.
+    pub fn register_market(
+        env: Env,
+        market_id: BytesN<32>,
+        resolution_time: u64,
+    ) -> Result<(), OracleError> {
         // Require admin authentication
         let admin: Address = env
             .storage()
@@ -314,8 +575,19 @@ impl OracleManager {
             .expect("Oracle not initialized");
         admin.require_auth();
 
+        if !Self::has_enough_oracles(env.clone()) {
+            return Err(OracleError::InsufficientOracles);
+        }
+
         // Store market resolution time
         let market_key = (Symbol::new(&env, MARKET_RES_TIME_KEY), market_id.clone());
+        if !env.storage().persistent().has(&market_key) {
+            let mut markets = Self::get_registered_markets(env.clone());
+            markets.push_back(market_id.clone());
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, REGISTERED_MARKETS_KEY), &markets);
+        }
         env.storage()
             .persistent()
             .set(&market_key, &resolution_time);
@@ -332,6 +604,177 @@ impl OracleManager {
             resolution_time,
         }
         .publish(&env);
+
+        Ok(())
+    }
+
+    /// Whether enough oracles are registered to reach `required_consensus`
+    pub fn has_enough_oracles(env: Env) -> bool {
+        let oracle_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_COUNT_KEY))
+            .unwrap_or(0);
+        let required_consensus: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, REQUIRED_CONSENSUS_KEY))
+            .unwrap_or(0);
+        oracle_count >= required_consensus
+    }
+
+    /// Admin: bind a market_id to the market contract allowed to abandon it
+    pub fn bind_market_contract(env: Env, market_id: BytesN<32>, market: Address) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        let contract_key = (Symbol::new(&env, MARKET_CONTRACT_KEY), market_id);
+        env.storage().persistent().set(&contract_key, &market);
+    }
+
+    /// Admin: set the time by which consensus must form for a market
+    ///
+    /// Once it passes without consensus, anyone may call `abandon_market`.
+    pub fn set_consensus_deadline(
+        env: Env,
+        market_id: BytesN<32>,
+        deadline: u64,
+    ) -> Result<(), OracleError> {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        let resolution_time = Self::get_market_resolution_time(env.clone(), market_id.clone())
+            .ok_or(OracleError::MarketNotRegistered)?;
+        if deadline <= resolution_time {
+            return Err(OracleError::InvalidConsensusDeadline);
+        }
+
+        let deadline_key = (Symbol::new(&env, CONSENSUS_DEADLINE_KEY), market_id);
+        env.storage().persistent().set(&deadline_key, &deadline);
+
+        Ok(())
+    }
+
+    /// Get the consensus deadline for a market, if one is set
+    pub fn get_consensus_deadline(env: Env, market_id: BytesN<32>) -> Option<u64> {
+        let deadline_key = (Symbol::new(&env, CONSENSUS_DEADLINE_KEY), market_id);
+        env.storage().persistent().get(&deadline_key)
+    }
+
+    /// Whether the consensus deadline passed with no consensus and no finalized result
+    pub fn is_consensus_deadline_missed(env: Env, market_id: BytesN<32>) -> bool {
+        let Some(deadline) = Self::get_consensus_deadline(env.clone(), market_id.clone()) else {
+            return false;
+        };
+        if env.ledger().timestamp() <= deadline {
+            return false;
+        }
+
+        let result_key = (Symbol::new(&env, "consensus_result"), market_id.clone());
+        if env.storage().persistent().has(&result_key) {
+            return false;
+        }
+        !Self::check_consensus(env, market_id).0
+    }
+
+    /// Mark a cancelled market abandoned and release its pending challenge stakes
+    ///
+    /// Called by the bound market contract from `cancel_market`, or by anyone once the
+    /// market's consensus deadline has been missed; the latter emits
+    /// `ConsensusDeadlineMissedEvent` for the market to cancel on. Open challenges on the
+    /// market's attestations are closed without slashing anyone, and each challenger's
+    /// stake is credited back. No further attestations are accepted for the market.
+    pub fn abandon_market(env: Env, market_id: BytesN<32>) -> Result<(), OracleError> {
+        let deadline_missed = Self::is_consensus_deadline_missed(env.clone(), market_id.clone());
+        if !deadline_missed {
+            let contract_key = (Symbol::new(&env, MARKET_CONTRACT_KEY), market_id.clone());
+            let market: Option<Address> = env.storage().persistent().get(&contract_key);
+            let Some(market) = market else {
+                if Self::get_consensus_deadline(env.clone(), market_id.clone()).is_some() {
+                    return Err(OracleError::ConsensusDeadlineNotMissed);
+                }
+                return Err(OracleError::MarketContractNotBound);
+            };
+            market.require_auth();
+        }
+
+        let abandoned_key = (Symbol::new(&env, MARKET_ABANDONED_KEY), market_id.clone());
+        if env.storage().persistent().has(&abandoned_key) {
+            return Err(OracleError::MarketAlreadyAbandoned);
+        }
+        env.storage().persistent().set(&abandoned_key, &true);
+
+        let voters_key = (Symbol::new(&env, "voters"), market_id.clone());
+        let voters: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&voters_key)
+            .unwrap_or(Vec::new(&env));
+
+        let mut released_challenges = 0u32;
+        for oracle in voters.iter() {
+            let challenge_key = (Symbol::new(&env, "challenge"), market_id.clone(), oracle);
+            let challenge: Option<Challenge> = env.storage().persistent().get(&challenge_key);
+            let Some(mut challenge) = challenge else {
+                continue;
+            };
+            if challenge.resolved {
+                continue;
+            }
+
+            let released_key = (
+                Symbol::new(&env, RELEASED_STAKE_KEY),
+                challenge.challenger.clone(),
+            );
+            let released: i128 = env.storage().persistent().get(&released_key).unwrap_or(0);
+            env.storage()
+                .persistent()
+                .set(&released_key, &(released + challenge.stake));
+
+            challenge.resolved = true;
+            env.storage().persistent().set(&challenge_key, &challenge);
+            Self::close_open_challenge(&env, &challenge.challenger);
+            released_challenges += 1;
+        }
+
+        let market_challenge_key = (Symbol::new(&env, "market_challenged"), market_id.clone());
+        env.storage().persistent().remove(&market_challenge_key);
+
+        if deadline_missed {
+            ConsensusDeadlineMissedEvent {
+                market_id: market_id.clone(),
+                deadline: Self::get_consensus_deadline(env.clone(), market_id.clone()).unwrap_or(0),
+            }
+            .publish(&env);
+        }
+
+        MarketAbandonedEvent {
+            market_id,
+            released_challenges,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Whether a market was abandoned after cancellation
+    pub fn is_market_abandoned(env: Env, market_id: BytesN<32>) -> bool {
+        let abandoned_key = (Symbol::new(&env, MARKET_ABANDONED_KEY), market_id);
+        env.storage().persistent().has(&abandoned_key)
+    }
+
+    /// Get the challenge stake released back to `challenger` from abandoned markets
+    pub fn get_released_challenge_stake(env: Env, challenger: Address) -> i128 {
+        let released_key = (Symbol::new(&env, RELEASED_STAKE_KEY), challenger);
+        env.storage().persistent().get(&released_key).unwrap_or(0)
     }
 
     /// Get market resolution time (helper function)
@@ -340,6 +783,34 @@ impl OracleManager {
         env.storage().persistent().get(&market_key)
     }
 
+    /// Get every registered market, in registration order
+    pub fn get_registered_markets(env: Env) -> Vec<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, REGISTERED_MARKETS_KEY))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the registered markets `oracle` still needs to attest on
+    ///
+    /// A market qualifies once its resolution_time has passed, while it is not abandoned
+    /// and the oracle has no attestation on it yet.
+    pub fn get_markets_needing_attestation(env: Env, oracle: Address) -> Vec<BytesN<32>> {
+        let now = env.ledger().timestamp();
+        let mut pending = Vec::new(&env);
+        for market_id in Self::get_registered_markets(env.clone()).iter() {
+            let past_resolution = Self::get_market_resolution_time(env.clone(), market_id.clone())
+                .is_some_and(|resolution_time| now >= resolution_time);
+            if past_resolution
+                && !Self::is_market_abandoned(env.clone(), market_id.clone())
+                && Self::get_attestation(env.clone(), market_id.clone(), oracle.clone()).is_none()
+            {
+                pending.push_back(market_id);
+            }
+        }
+        pending
+    }
+
     /// Get attestation counts for a market
     pub fn get_attestation_counts(env: Env, market_id: BytesN<32>) -> (u32, u32) {
         let yes_count_key = (Symbol::new(&env, ATTEST_COUNT_YES_KEY), market_id.clone());
@@ -374,103 +845,433 @@ impl OracleManager {
         market_id: BytesN<32>,
         attestation_result: u32,
         _data_hash: BytesN<32>,
-    ) {
+    ) -> Result<(), OracleError> {
         // 1. Require oracle authentication
         oracle.require_auth();
 
+        Self::submit_attestation_unchecked(&env, &oracle, &market_id, attestation_result)
+    }
+
+    /// Submit attestations for several markets in one call
+    ///
+    /// Each `(market_id, attestation_result, data_hash)` entry goes through the same
+    /// validation as `submit_attestation`. Any failure aborts the whole batch, so
+    /// counts never reflect a partial submission.
+    pub fn submit_attestations_batch(
+        env: Env,
+        oracle: Address,
+        submissions: Vec<(BytesN<32>, u32, BytesN<32>)>,
+    ) -> Result<(), OracleError> {
+        oracle.require_auth();
+
+        if submissions.is_empty() {
+            return Err(OracleError::EmptyBatch);
+        }
+
+        for (market_id, attestation_result, _data_hash) in submissions.iter() {
+            Self::submit_attestation_unchecked(&env, &oracle, &market_id, attestation_result)?;
+        }
+
+        Ok(())
+    }
+
+    /// Validate and record a direct attestation once the oracle has authenticated
+    fn submit_attestation_unchecked(
+        env: &Env,
+        oracle: &Address,
+        market_id: &BytesN<32>,
+        attestation_result: u32,
+    ) -> Result<(), OracleError> {
         // 2. Validate oracle is registered (trusted attestor)
-        let oracle_key = (Symbol::new(&env, "oracle"), oracle.clone());
+        let oracle_key = (Symbol::new(env, "oracle"), oracle.clone());
         let is_registered: bool = env.storage().persistent().get(&oracle_key).unwrap_or(false);
         if !is_registered {
-            panic!("Oracle not registered");
+            return Err(OracleError::OracleNotRegistered);
         }
 
         // 3. Validate market is registered and past resolution_time
-        let market_key = (Symbol::new(&env, MARKET_RES_TIME_KEY), market_id.clone());
+        let market_key = (Symbol::new(env, MARKET_RES_TIME_KEY), market_id.clone());
         let resolution_time: u64 = env
             .storage()
             .persistent()
             .get(&market_key)
-            .expect("Market not registered");
+            .ok_or(OracleError::MarketNotRegistered)?;
 
         let current_time = env.ledger().timestamp();
         if current_time < resolution_time {
-            panic!("Cannot attest before resolution time");
+            return Err(OracleError::TooEarlyToAttest);
         }
+        Self::require_min_oracle_age(env, oracle, current_time)?;
 
-        // 4. Validate result is binary (0 or 1)
-        if attestation_result > 1 {
-            panic!("Invalid attestation result");
+        // 4. Validate result and record the vote
+        Self::require_open_ballot(env, market_id)?;
+        Self::record_attestation(env, oracle, market_id, attestation_result, current_time)
+    }
+
+    /// Reject plaintext attestations on markets that vote by commit-reveal
+    fn require_open_ballot(env: &Env, market_id: &BytesN<32>) -> Result<(), OracleError> {
+        if Self::is_sealed_voting(env.clone(), market_id.clone()) {
+            return Err(OracleError::SealedVotingRequired);
         }
+        Ok(())
+    }
 
-        // 5. Check if oracle already attested
-        let vote_key = (Symbol::new(&env, "vote"), market_id.clone(), oracle.clone());
-        if env.storage().persistent().has(&vote_key) {
-            panic!("Oracle already attested");
+    /// Reject oracles registered less than `min_oracle_age` seconds ago
+    fn require_min_oracle_age(
+        env: &Env,
+        oracle: &Address,
+        current_time: u64,
+    ) -> Result<(), OracleError> {
+        let min_age = Self::get_min_oracle_age(env.clone());
+        if min_age == 0 {
+            return Ok(());
         }
 
-        // 6. Store vote for consensus
-        env.storage()
+        let timestamp_key = (Symbol::new(env, "oracle_timestamp"), oracle.clone());
+        let registered_at: u64 = env
+            .storage()
             .persistent()
-            .set(&vote_key, &attestation_result);
+            .get(&timestamp_key)
+            .unwrap_or(current_time);
+        if current_time < registered_at + min_age {
+            return Err(OracleError::OracleTooNew);
+        }
 
-        // 7. Store attestation with timestamp
-        let attestation = Attestation {
-            attestor: oracle.clone(),
-            outcome: attestation_result,
-            timestamp: current_time,
-        };
-        let attestation_key = (
-            Symbol::new(&env, "attestation"),
-            market_id.clone(),
-            oracle.clone(),
-        );
-        env.storage()
-            .persistent()
-            .set(&attestation_key, &attestation);
+        Ok(())
+    }
 
-        // 8. Track oracle in market's voter list
-        let voters_key = (Symbol::new(&env, "voters"), market_id.clone());
-        let mut voters: Vec<Address> = env
+    /// Admin: require commit-reveal attestations on a market, so no vote is public
+    /// before the reveal window
+    ///
+    /// Plaintext and signed attestations are rejected on sealed markets and commits on
+    /// the rest. The mode can only change before the market's resolution time.
+    pub fn set_sealed_voting(
+        env: Env,
+        market_id: BytesN<32>,
+        enabled: bool,
+    ) -> Result<(), OracleError> {
+        let admin: Address = env
             .storage()
             .persistent()
-            .get(&voters_key)
-            .unwrap_or(Vec::new(&env));
-
-        voters.push_back(oracle.clone());
-        env.storage().persistent().set(&voters_key, &voters);
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
 
-        // 9. Update attestation count per outcome
-        if attestation_result == 1 {
-            let yes_count_key = (Symbol::new(&env, ATTEST_COUNT_YES_KEY), market_id.clone());
-            let current_count: u32 = env.storage().persistent().get(&yes_count_key).unwrap_or(0);
-            env.storage()
-                .persistent()
-                .set(&yes_count_key, &(current_count + 1));
-        } else {
-            let no_count_key = (Symbol::new(&env, ATTEST_COUNT_NO_KEY), market_id.clone());
-            let current_count: u32 = env.storage().persistent().get(&no_count_key).unwrap_or(0);
-            env.storage()
-                .persistent()
-                .set(&no_count_key, &(current_count + 1));
+        let resolution_time = Self::get_market_resolution_time(env.clone(), market_id.clone())
+            .ok_or(OracleError::MarketNotRegistered)?;
+        if env.ledger().timestamp() >= resolution_time {
+            return Err(OracleError::VotingAlreadyOpen);
         }
 
-        // 10. Emit AttestationSubmitted(market_id, attestor, outcome)
-        AttestationSubmittedEvent {
-            market_id,
-            oracle,
-            attestation_result,
-        }
-        .publish(&env);
+        let sealed_key = (Symbol::new(&env, SEALED_VOTING_KEY), market_id);
+        env.storage().persistent().set(&sealed_key, &enabled);
+        Ok(())
     }
 
-    /// Check if consensus has been reached for market
-    pub fn check_consensus(env: Env, market_id: BytesN<32>) -> (bool, u32) {
-        // 1. Query attestations for market_id
-        let voters_key = (Symbol::new(&env, "voters"), market_id.clone());
-        let voters: Vec<Address> = env
-            .storage()
-            .persistent()
+    /// Whether a market only takes commit-reveal attestations
+    pub fn is_sealed_voting(env: Env, market_id: BytesN<32>) -> bool {
+        let sealed_key = (Symbol::new(&env, SEALED_VOTING_KEY), market_id);
+        env.storage().persistent().get(&sealed_key).unwrap_or(false)
+    }
+
+    /// Commit a sealed attestation (phase 1 of private voting)
+    ///
+    /// `commit_hash` = sha256(market_id || outcome_be_bytes || salt). Accepted during
+    /// the commit window, which opens at the market's resolution_time and lasts
+    /// ATTEST_COMMIT_WINDOW seconds; votes only count once revealed. Only markets with
+    /// sealed voting enabled take commits.
+    pub fn commit_attestation(
+        env: Env,
+        oracle: Address,
+        market_id: BytesN<32>,
+        commit_hash: BytesN<32>,
+    ) -> Result<(), OracleError> {
+        oracle.require_auth();
+
+        let oracle_key = (Symbol::new(&env, "oracle"), oracle.clone());
+        let is_registered: bool = env.storage().persistent().get(&oracle_key).unwrap_or(false);
+        if !is_registered {
+            return Err(OracleError::OracleNotRegistered);
+        }
+
+        let resolution_time: u64 = Self::get_market_resolution_time(env.clone(), market_id.clone())
+            .ok_or(OracleError::MarketNotRegistered)?;
+        let current_time = env.ledger().timestamp();
+        if current_time < resolution_time {
+            return Err(OracleError::TooEarlyToAttest);
+        }
+        if current_time >= resolution_time + ATTEST_COMMIT_WINDOW {
+            return Err(OracleError::CommitWindowClosed);
+        }
+        if !Self::is_sealed_voting(env.clone(), market_id.clone()) {
+            return Err(OracleError::SealedVotingNotEnabled);
+        }
+        if Self::is_market_abandoned(env.clone(), market_id.clone()) {
+            return Err(OracleError::MarketAbandoned);
+        }
+        Self::require_min_oracle_age(&env, &oracle, current_time)?;
+
+        let commit_key = (
+            Symbol::new(&env, ATTEST_COMMIT_KEY),
+            market_id.clone(),
+            oracle.clone(),
+        );
+        let vote_key = (Symbol::new(&env, "vote"), market_id.clone(), oracle.clone());
+        if env.storage().persistent().has(&commit_key) || env.storage().persistent().has(&vote_key)
+        {
+            return Err(OracleError::AlreadyAttested);
+        }
+
+        env.storage().persistent().set(&commit_key, &commit_hash);
+
+        AttestationCommittedEvent {
+            market_id,
+            oracle,
+            commit_hash,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Reveal a sealed attestation (phase 2 of private voting)
+    ///
+    /// Accepted during the reveal window that follows the commit window. The
+    /// revealed outcome is checked against the stored hash and then counted
+    /// exactly like a plaintext attestation.
+    pub fn reveal_attestation(
+        env: Env,
+        oracle: Address,
+        market_id: BytesN<32>,
+        outcome: u32,
+        salt: BytesN<32>,
+    ) -> Result<(), OracleError> {
+        oracle.require_auth();
+
+        let resolution_time: u64 = Self::get_market_resolution_time(env.clone(), market_id.clone())
+            .ok_or(OracleError::MarketNotRegistered)?;
+        let current_time = env.ledger().timestamp();
+        let reveal_start = resolution_time + ATTEST_COMMIT_WINDOW;
+        if current_time < reveal_start {
+            return Err(OracleError::RevealWindowNotOpen);
+        }
+        if current_time >= reveal_start + ATTEST_REVEAL_WINDOW {
+            return Err(OracleError::RevealWindowClosed);
+        }
+
+        let commit_key = (
+            Symbol::new(&env, ATTEST_COMMIT_KEY),
+            market_id.clone(),
+            oracle.clone(),
+        );
+        let commit_hash: BytesN<32> = env
+            .storage()
+            .persistent()
+            .get(&commit_key)
+            .ok_or(OracleError::CommitmentNotFound)?;
+
+        let mut preimage = Bytes::new(&env);
+        preimage.extend_from_array(&market_id.to_array());
+        preimage.extend_from_array(&outcome.to_be_bytes());
+        preimage.extend_from_array(&salt.to_array());
+        let reconstructed = env.crypto().sha256(&preimage);
+        if BytesN::from_array(&env, &reconstructed.to_array()) != commit_hash {
+            return Err(OracleError::CommitmentMismatch);
+        }
+
+        env.storage().persistent().remove(&commit_key);
+        Self::record_attestation(&env, &oracle, &market_id, outcome, current_time)
+    }
+
+    /// Get an oracle's sealed attestation hash, if committed and not yet revealed
+    pub fn get_attestation_commitment(
+        env: Env,
+        market_id: BytesN<32>,
+        oracle: Address,
+    ) -> Option<BytesN<32>> {
+        let commit_key = (Symbol::new(&env, ATTEST_COMMIT_KEY), market_id, oracle);
+        env.storage().persistent().get(&commit_key)
+    }
+
+    /// Registrar: trust an off-chain signed feed, attesting as `identity`
+    pub fn register_signed_feed(env: Env, feed_pubkey: BytesN<32>, identity: Address) {
+        Self::require_registrar(&env);
+
+        let feed_key = (Symbol::new(&env, SIGNED_FEED_KEY), feed_pubkey.clone());
+        env.storage().persistent().set(&feed_key, &identity);
+
+        SignedFeedRegisteredEvent {
+            feed_pubkey,
+            identity,
+        }
+        .publish(&env);
+    }
+
+    /// Whether a signed feed has already consumed `nonce`
+    pub fn is_nonce_used(env: Env, feed_pubkey: BytesN<32>, nonce: u64) -> bool {
+        let nonce_key = (Symbol::new(&env, SIGNED_NONCE_KEY), feed_pubkey, nonce);
+        env.storage().persistent().has(&nonce_key)
+    }
+
+    /// Get the identity a signed feed attests as, if the feed is trusted
+    pub fn get_signed_feed(env: Env, feed_pubkey: BytesN<32>) -> Option<Address> {
+        let feed_key = (Symbol::new(&env, SIGNED_FEED_KEY), feed_pubkey);
+        env.storage().persistent().get(&feed_key)
+    }
+
+    /// Submit an attestation signed off-chain by a trusted feed
+    ///
+    /// The feed signs `market_id || outcome (u32 BE) || timestamp (u64 BE) || nonce (u64 BE)`
+    /// with Ed25519, where `timestamp` is the market's registered resolution time. Anyone may
+    /// relay the signature; it counts as an attestation from the feed's registered identity.
+    /// Each nonce is accepted once per feed. Invalid signatures abort the invocation.
+    pub fn submit_signed_attestation(
+        env: Env,
+        market_id: BytesN<32>,
+        outcome: u32,
+        nonce: u64,
+        signature: BytesN<64>,
+        feed_pubkey: BytesN<32>,
+    ) -> Result<(), OracleError> {
+        let feed_key = (Symbol::new(&env, SIGNED_FEED_KEY), feed_pubkey.clone());
+        let identity: Address = env
+            .storage()
+            .persistent()
+            .get(&feed_key)
+            .ok_or(OracleError::SignedFeedNotRegistered)?;
+
+        let market_key = (Symbol::new(&env, MARKET_RES_TIME_KEY), market_id.clone());
+        let resolution_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&market_key)
+            .ok_or(OracleError::MarketNotRegistered)?;
+
+        let current_time = env.ledger().timestamp();
+        if current_time < resolution_time {
+            return Err(OracleError::TooEarlyToAttest);
+        }
+        Self::require_open_ballot(&env, &market_id)?;
+
+        let mut message = Bytes::new(&env);
+        message.extend_from_array(&market_id.to_array());
+        message.extend_from_array(&outcome.to_be_bytes());
+        message.extend_from_array(&resolution_time.to_be_bytes());
+        message.extend_from_array(&nonce.to_be_bytes());
+        env.crypto()
+            .ed25519_verify(&feed_pubkey, &message, &signature);
+
+        let nonce_key = (Symbol::new(&env, SIGNED_NONCE_KEY), feed_pubkey, nonce);
+        if env.storage().persistent().has(&nonce_key) {
+            return Err(OracleError::NonceAlreadyUsed);
+        }
+        env.storage().persistent().set(&nonce_key, &true);
+
+        Self::record_attestation(&env, &identity, &market_id, outcome, current_time)
+    }
+
+    /// Helper: store a validated attestation and update consensus tallies
+    fn record_attestation(
+        env: &Env,
+        oracle: &Address,
+        market_id: &BytesN<32>,
+        attestation_result: u32,
+        current_time: u64,
+    ) -> Result<(), OracleError> {
+        // Validate result is binary (0 or 1)
+        if attestation_result > 1 {
+            return Err(OracleError::InvalidOutcome);
+        }
+
+        if Self::is_market_abandoned(env.clone(), market_id.clone()) {
+            return Err(OracleError::MarketAbandoned);
+        }
+
+        let result_key = (Symbol::new(env, "consensus_result"), market_id.clone());
+        if env.storage().persistent().has(&result_key) {
+            return Err(OracleError::ResolutionAlreadyFinalized);
+        }
+
+        // Check if oracle already attested
+        let vote_key = (Symbol::new(env, "vote"), market_id.clone(), oracle.clone());
+        if env.storage().persistent().has(&vote_key) {
+            return Err(OracleError::AlreadyAttested);
+        }
+
+        // Store vote for consensus
+        env.storage()
+            .persistent()
+            .set(&vote_key, &attestation_result);
+
+        // Store attestation with timestamp
+        let attestation = Attestation {
+            attestor: oracle.clone(),
+            outcome: attestation_result,
+            timestamp: current_time,
+        };
+        let attestation_key = (
+            Symbol::new(env, "attestation"),
+            market_id.clone(),
+            oracle.clone(),
+        );
+        env.storage()
+            .persistent()
+            .set(&attestation_key, &attestation);
+
+        // Track oracle in market's voter list
+        let voters_key = (Symbol::new(env, "voters"), market_id.clone());
+        let mut voters: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&voters_key)
+            .unwrap_or(Vec::new(env));
+
+        voters.push_back(oracle.clone());
+        env.storage().persistent().set(&voters_key, &voters);
+
+        // Track market in the oracle's attestation history
+        let markets_key = (Symbol::new(env, ORACLE_MARKETS_KEY), oracle.clone());
+        let mut markets: Vec<BytesN<32>> = env
+            .storage()
+            .persistent()
+            .get(&markets_key)
+            .unwrap_or(Vec::new(env));
+        markets.push_back(market_id.clone());
+        env.storage().persistent().set(&markets_key, &markets);
+
+        // Update attestation count per outcome
+        if attestation_result == 1 {
+            let yes_count_key = (Symbol::new(env, ATTEST_COUNT_YES_KEY), market_id.clone());
+            let current_count: u32 = env.storage().persistent().get(&yes_count_key).unwrap_or(0);
+            env.storage()
+                .persistent()
+                .set(&yes_count_key, &(current_count + 1));
+        } else {
+            let no_count_key = (Symbol::new(env, ATTEST_COUNT_NO_KEY), market_id.clone());
+            let current_count: u32 = env.storage().persistent().get(&no_count_key).unwrap_or(0);
+            env.storage()
+                .persistent()
+                .set(&no_count_key, &(current_count + 1));
+        }
+
+        // Emit AttestationSubmitted(market_id, attestor, outcome)
+        AttestationSubmittedEvent {
+            market_id: market_id.clone(),
+            oracle: oracle.clone(),
+            attestation_result,
+        }
+        .publish(env);
+
+        Ok(())
+    }
+
+    /// Check if consensus has been reached for market
+    pub fn check_consensus(env: Env, market_id: BytesN<32>) -> (bool, u32) {
+        // 1. Query attestations for market_id
+        let voters_key = (Symbol::new(&env, "voters"), market_id.clone());
+        let voters: Vec<Address> = env
+            .storage()
+            .persistent()
             .get(&voters_key)
             .unwrap_or(Vec::new(&env));
 
@@ -499,7 +1300,21 @@ impl OracleManager {
             }
         }
 
-        // 4. Compare counts against threshold
+        // 4. With supermajority enabled, the leading outcome must also hold at least
+        // the configured share of all cast votes
+        let supermajority_bps: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, SUPERMAJORITY_BPS_KEY))
+            .unwrap_or(0);
+        if supermajority_bps > 0 {
+            let leading_votes = yes_votes.max(no_votes);
+            if leading_votes * 10000 < voters.len() * supermajority_bps {
+                return (false, 0);
+            }
+        }
+
+        // 5. Compare counts against threshold
         // Winner is the one that reached the threshold first
         // If both reach threshold (possible if threshold is low), we favor the one with more votes
         // If tied and both >= threshold, return false (no clear winner yet)
@@ -515,6 +1330,139 @@ impl OracleManager {
         }
     }
 
+    /// Consensus with each vote counted at its oracle's fixed weight
+    ///
+    /// Same participation threshold as `check_consensus` (at least
+    /// `required_consensus` oracles must have attested), but the outcome is decided
+    /// by summed weights rather than head count. The supermajority setting, if
+    /// enabled, applies to the weighted totals.
+    pub fn check_consensus_weighted(env: Env, market_id: BytesN<32>) -> (bool, u32) {
+        let voters_key = (Symbol::new(&env, "voters"), market_id.clone());
+        let voters: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&voters_key)
+            .unwrap_or(Vec::new(&env));
+
+        let threshold: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, REQUIRED_CONSENSUS_KEY))
+            .unwrap_or(0);
+
+        if voters.len() < threshold {
+            return (false, 0);
+        }
+
+        let mut yes_weight: u64 = 0;
+        let mut no_weight: u64 = 0;
+        for oracle in voters.iter() {
+            let vote_key = (Symbol::new(&env, "vote"), market_id.clone(), oracle.clone());
+            let vote: u32 = env.storage().persistent().get(&vote_key).unwrap_or(0);
+            let weight = Self::get_oracle_weight(env.clone(), oracle) as u64;
+            if vote == 1 {
+                yes_weight += weight;
+            } else {
+                no_weight += weight;
+            }
+        }
+
+        let supermajority_bps = Self::get_supermajority(env.clone()) as u64;
+        if supermajority_bps > 0
+            && yes_weight.max(no_weight) * 10000 < (yes_weight + no_weight) * supermajority_bps
+        {
+            return (false, 0);
+        }
+
+        if yes_weight > no_weight {
+            (true, 1)
+        } else if no_weight > yes_weight {
+            (true, 0)
+        } else {
+            (false, 0)
+        }
+    }
+
+    /// Registrar: set a registered oracle's fixed vote weight for weighted consensus
+    pub fn set_oracle_weight(env: Env, oracle: Address, weight: u32) -> Result<(), OracleError> {
+        Self::require_registrar(&env);
+
+        if weight == 0 {
+            return Err(OracleError::InvalidWeight);
+        }
+
+        let oracle_key = (Symbol::new(&env, "oracle"), oracle.clone());
+        let is_registered: bool = env.storage().persistent().get(&oracle_key).unwrap_or(false);
+        if !is_registered {
+            return Err(OracleError::OracleNotRegistered);
+        }
+
+        let weight_key = (Symbol::new(&env, ORACLE_WEIGHT_KEY), oracle);
+        env.storage().persistent().set(&weight_key, &weight);
+
+        Ok(())
+    }
+
+    /// Get an oracle's fixed vote weight (1 unless set by the admin)
+    pub fn get_oracle_weight(env: Env, oracle: Address) -> u32 {
+        let weight_key = (Symbol::new(&env, ORACLE_WEIGHT_KEY), oracle);
+        env.storage().persistent().get(&weight_key).unwrap_or(1)
+    }
+
+    /// Admin: require the winning outcome to hold a supermajority of cast votes
+    ///
+    /// `supermajority_bps` is the minimum share of all attestations the winner must
+    /// reach (e.g. 6666 for 2/3). Pass 0 to fall back to plain majority.
+    pub fn set_supermajority(env: Env, supermajority_bps: u32) -> Result<(), OracleError> {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        if supermajority_bps != 0 && (supermajority_bps <= 5000 || supermajority_bps > 10000) {
+            return Err(OracleError::InvalidSupermajority);
+        }
+
+        env.storage().persistent().set(
+            &Symbol::new(&env, SUPERMAJORITY_BPS_KEY),
+            &supermajority_bps,
+        );
+
+        Ok(())
+    }
+
+    /// Admin: require oracles to be registered for `min_age` seconds before attesting (0 = off)
+    pub fn set_min_oracle_age(env: Env, min_age: u64) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, MIN_ORACLE_AGE_KEY), &min_age);
+    }
+
+    /// Get the minimum registration age in seconds before an oracle may attest
+    pub fn get_min_oracle_age(env: Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, MIN_ORACLE_AGE_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Get the configured supermajority requirement in basis points (0 = disabled)
+    pub fn get_supermajority(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, SUPERMAJORITY_BPS_KEY))
+            .unwrap_or(0)
+    }
+
     /// Get the consensus result for a market
     pub fn get_consensus_result(env: Env, market_id: BytesN<32>) -> u32 {
         let result_key = (Symbol::new(&env, "consensus_result"), market_id.clone());
@@ -529,39 +1477,44 @@ impl OracleManager {
     /// Called after consensus reached and dispute period elapsed.
     /// Makes cross-contract call to Market.resolve_market().
     /// Locks in final outcome permanently.
-    pub fn finalize_resolution(env: Env, market_id: BytesN<32>, _market_address: Address) {
+    pub fn finalize_resolution(
+        env: Env,
+        market_id: BytesN<32>,
+        _market_address: Address,
+    ) -> Result<(), OracleError> {
         // 1. Validate market is registered
         let market_key = (Symbol::new(&env, MARKET_RES_TIME_KEY), market_id.clone());
         let resolution_time: u64 = env
             .storage()
             .persistent()
             .get(&market_key)
-            .expect("Market not registered");
+            .ok_or(OracleError::MarketNotRegistered)?;
 
         // 2. Validate consensus reached
         let (consensus_reached, final_outcome) =
             Self::check_consensus(env.clone(), market_id.clone());
         if !consensus_reached {
-            panic!("Consensus not reached");
+            return Err(OracleError::ConsensusNotReached);
         }
 
         // 3. Validate dispute period elapsed (7 days = 604800 seconds)
         let current_time = env.ledger().timestamp();
         let dispute_period = 604800u64;
         if current_time < resolution_time + dispute_period {
-            panic!("Dispute period not elapsed");
+            return Err(OracleError::DisputePeriodNotElapsed);
         }
 
-        // 4. Store consensus result permanently
+        // 4. Store consensus result permanently and freeze the reward split
         let result_key = (Symbol::new(&env, "consensus_result"), market_id.clone());
         env.storage().persistent().set(&result_key, &final_outcome);
+        Self::freeze_reward_split(&env, &market_id, final_outcome);
 
         // 5. Cross-contract call to Market.resolve_market()
         #[cfg(feature = "market")]
         {
             use crate::market::PredictionMarketClient;
             let market_client = PredictionMarketClient::new(&env, &_market_address);
-            market_client.resolve_market(&market_id);
+            market_client.resolve_with_oracle_outcome(&market_id, &final_outcome);
         }
 
         // 6. Emit ResolutionFinalized event
@@ -571,19 +1524,160 @@ impl OracleManager {
             timestamp: current_time,
         }
         .publish(&env);
+
+        Ok(())
     }
 
-    /// Challenge an attestation (dispute oracle honesty)
+    /// Add to a market's resolution reward budget, paid in the stake token
     ///
-    /// Allows users to challenge attestations with stake.
-    /// Requires challenger to put up stake that will be slashed if challenge is invalid.
-    pub fn challenge_attestation(
+    /// Anyone may fund (e.g. the creator at registration, or fees routed later) until the
+    /// market is finalized. The budget is then split evenly among oracles that attested
+    /// the final outcome; any rounding dust stays with the contract.
+    pub fn fund_resolution_reward(
         env: Env,
-        challenger: Address,
-        oracle: Address,
+        funder: Address,
         market_id: BytesN<32>,
-        challenge_reason: Symbol,
-    ) {
+        amount: i128,
+    ) -> Result<(), OracleError> {
+        funder.require_auth();
+
+        if amount <= 0 {
+            return Err(OracleError::InvalidRewardAmount);
+        }
+        let market_key = (Symbol::new(&env, MARKET_RES_TIME_KEY), market_id.clone());
+        if !env.storage().persistent().has(&market_key) {
+            return Err(OracleError::MarketNotRegistered);
+        }
+        let result_key = (Symbol::new(&env, "consensus_result"), market_id.clone());
+        if env.storage().persistent().has(&result_key) {
+            return Err(OracleError::ResolutionAlreadyFinalized);
+        }
+
+        let stake_token = Self::get_stake_token(env.clone());
+        token::Client::new(&env, &stake_token).transfer(
+            &funder,
+            env.current_contract_address(),
+            &amount,
+        );
+
+        let budget = Self::get_resolution_reward_budget(env.clone(), market_id.clone());
+        let budget_key = (Symbol::new(&env, REWARD_BUDGET_KEY), market_id.clone());
+        env.storage()
+            .persistent()
+            .set(&budget_key, &(budget + amount));
+
+        ResolutionRewardFundedEvent {
+            market_id,
+            funder,
+            amount,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Get a market's unclaimed resolution reward budget
+    pub fn get_resolution_reward_budget(env: Env, market_id: BytesN<32>) -> i128 {
+        let budget_key = (Symbol::new(&env, REWARD_BUDGET_KEY), market_id);
+        env.storage().persistent().get(&budget_key).unwrap_or(0)
+    }
+
+    /// Claim an oracle's share of a finalized market's resolution reward budget
+    ///
+    /// Only oracles whose attestation matched the finalized outcome are paid, each
+    /// receiving `budget / agreeing_oracles` as frozen at finalization, and never more
+    /// than the market's unclaimed budget. Returns the amount paid.
+    pub fn claim_resolution_reward(
+        env: Env,
+        oracle: Address,
+        market_id: BytesN<32>,
+    ) -> Result<i128, OracleError> {
+        oracle.require_auth();
+
+        let result_key = (Symbol::new(&env, "consensus_result"), market_id.clone());
+        let final_outcome: u32 = env
+            .storage()
+            .persistent()
+            .get(&result_key)
+            .ok_or(OracleError::ResolutionNotFinalized)?;
+
+        let vote_key = (Symbol::new(&env, "vote"), market_id.clone(), oracle.clone());
+        let vote: Option<u32> = env.storage().persistent().get(&vote_key);
+        if vote != Some(final_outcome) {
+            return Err(OracleError::RewardNotEarned);
+        }
+
+        let claimed_key = (
+            Symbol::new(&env, REWARD_CLAIMED_KEY),
+            market_id.clone(),
+            oracle.clone(),
+        );
+        if env.storage().persistent().has(&claimed_key) {
+            return Err(OracleError::RewardAlreadyClaimed);
+        }
+
+        let split_key = (Symbol::new(&env, REWARD_SPLIT_KEY), market_id.clone());
+        let (split_budget, agreeing): (i128, u32) = env
+            .storage()
+            .persistent()
+            .get(&split_key)
+            .ok_or(OracleError::ResolutionNotFinalized)?;
+        if agreeing == 0 {
+            return Err(OracleError::RewardNotEarned);
+        }
+        let budget = Self::get_resolution_reward_budget(env.clone(), market_id.clone());
+        let amount = (split_budget / agreeing as i128).min(budget);
+
+        env.storage().persistent().set(&claimed_key, &true);
+        let budget_key = (Symbol::new(&env, REWARD_BUDGET_KEY), market_id.clone());
+        env.storage()
+            .persistent()
+            .set(&budget_key, &(budget - amount));
+        if amount > 0 {
+            let stake_token = Self::get_stake_token(env.clone());
+            token::Client::new(&env, &stake_token).transfer(
+                &env.current_contract_address(),
+                &oracle,
+                &amount,
+            );
+        }
+
+        ResolutionRewardClaimedEvent {
+            market_id,
+            oracle,
+            amount,
+        }
+        .publish(&env);
+
+        Ok(amount)
+    }
+
+    /// Helper: Freeze the reward budget and the number of oracles agreeing with `final_outcome`
+    fn freeze_reward_split(env: &Env, market_id: &BytesN<32>, final_outcome: u32) {
+        let (yes_count, no_count) = Self::get_attestation_counts(env.clone(), market_id.clone());
+        let agreeing = match final_outcome {
+            1 => yes_count,
+            0 => no_count,
+            _ => 0,
+        };
+        let budget = Self::get_resolution_reward_budget(env.clone(), market_id.clone());
+        let split_key = (Symbol::new(env, REWARD_SPLIT_KEY), market_id.clone());
+        env.storage()
+            .persistent()
+            .set(&split_key, &(budget, agreeing));
+    }
+
+    /// Challenge an attestation (dispute oracle honesty)
+    ///
+    /// Allows users to challenge attestations with stake.
+    /// Requires challenger to put up stake that will be slashed if challenge is invalid.
+    pub fn challenge_attestation(
+        env: Env,
+        challenger: Address,
+        oracle: Address,
+        market_id: BytesN<32>,
+        challenge_reason: Symbol,
+    ) -> Result<(), OracleError> {
         // 1. Require challenger authentication
         challenger.require_auth();
 
@@ -591,7 +1685,7 @@ impl OracleManager {
         let oracle_key = (Symbol::new(&env, "oracle"), oracle.clone());
         let is_registered: bool = env.storage().persistent().get(&oracle_key).unwrap_or(false);
         if !is_registered {
-            panic!("Oracle not registered");
+            return Err(OracleError::OracleNotRegistered);
         }
 
         // 3. Validate attestation exists
@@ -602,7 +1696,7 @@ impl OracleManager {
         );
         let attestation: Option<Attestation> = env.storage().persistent().get(&attestation_key);
         if attestation.is_none() {
-            panic!("Attestation not found");
+            return Err(OracleError::AttestationNotFound);
         }
 
         // 4. Check if challenge already exists for this oracle/market
@@ -612,8 +1706,34 @@ impl OracleManager {
             oracle.clone(),
         );
         if env.storage().persistent().has(&challenge_key) {
-            panic!("Challenge already exists");
+            return Err(OracleError::ChallengeAlreadyExists);
+        }
+        if Self::is_market_abandoned(env.clone(), market_id.clone()) {
+            return Err(OracleError::MarketAbandoned);
+        }
+
+        // 4b. Anti-spam: per-challenger cooldown and cap on unresolved challenges
+        let (cooldown, max_open) = Self::get_challenge_limits(env.clone());
+        let now = env.ledger().timestamp();
+        let last_challenge_key = (Symbol::new(&env, LAST_CHALLENGE_KEY), challenger.clone());
+        if let Some(last) = env
+            .storage()
+            .persistent()
+            .get::<_, u64>(&last_challenge_key)
+        {
+            if now < last.saturating_add(cooldown) {
+                return Err(OracleError::ChallengeCooldownActive);
+            }
+        }
+        let open_challenges = Self::get_open_challenge_count(env.clone(), challenger.clone());
+        if max_open > 0 && open_challenges >= max_open {
+            return Err(OracleError::TooManyOpenChallenges);
         }
+        env.storage().persistent().set(&last_challenge_key, &now);
+        env.storage().persistent().set(
+            &(Symbol::new(&env, OPEN_CHALLENGES_KEY), challenger.clone()),
+            &(open_challenges + 1),
+        );
 
         // 5. Create challenge record
         let challenge = Challenge {
@@ -622,7 +1742,7 @@ impl OracleManager {
             market_id: market_id.clone(),
             reason: challenge_reason.clone(),
             stake: CHALLENGE_STAKE_AMOUNT,
-            timestamp: env.ledger().timestamp(),
+            timestamp: now,
             resolved: false,
         };
 
@@ -641,25 +1761,22 @@ impl OracleManager {
             challenge_reason,
         }
         .publish(&env);
+
+        Ok(())
     }
 
     /// Resolve a challenge and update oracle reputation
     ///
-    /// Admin arbitration or multi-oracle re-vote to resolve challenges.
+    /// Resolver arbitration or multi-oracle re-vote to resolve challenges.
     /// Slashes dishonest oracle's stake on successful challenge.
     pub fn resolve_challenge(
         env: Env,
         oracle: Address,
         market_id: BytesN<32>,
         challenge_valid: bool,
-    ) {
-        // 1. Require admin authentication
-        let admin: Address = env
-            .storage()
-            .persistent()
-            .get(&Symbol::new(&env, ADMIN_KEY))
-            .expect("Oracle not initialized");
-        admin.require_auth();
+    ) -> Result<(), OracleError> {
+        // 1. Require resolver authentication
+        Self::require_resolver(&env);
 
         // 2. Query challenge record
         let challenge_key = (
@@ -671,11 +1788,11 @@ impl OracleManager {
             .storage()
             .persistent()
             .get(&challenge_key)
-            .expect("Challenge not found");
+            .ok_or(OracleError::ChallengeNotFound)?;
 
         // 3. Validate challenge not already resolved
         if challenge.resolved {
-            panic!("Challenge already resolved");
+            return Err(OracleError::ChallengeAlreadyResolved);
         }
 
         // 4. Get oracle's current accuracy score
@@ -692,12 +1809,12 @@ impl OracleManager {
         if challenge_valid {
             // Challenge is valid - oracle was dishonest
 
-            // 6a. Reduce oracle's reputation/accuracy score (reduce by 20%)
-            accuracy = accuracy.saturating_sub(20);
+            // 6a. Reduce oracle's reputation/accuracy score by the configured penalty
+            accuracy = accuracy.saturating_sub(Self::get_accuracy_penalty(env.clone()));
             new_reputation = accuracy;
 
-            // 6b. Slash oracle's stake (50% of stake)
-            slashed_amount = oracle_stake / 2;
+            // 6b. Slash the configured share of the oracle's stake
+            slashed_amount = oracle_stake * Self::get_slash_bps(env.clone()) as i128 / 10000;
             let remaining_stake = oracle_stake - slashed_amount;
             env.storage().persistent().set(&stake_key, &remaining_stake);
 
@@ -742,8 +1859,8 @@ impl OracleManager {
         } else {
             // Challenge is invalid - oracle was honest
 
-            // 7a. Increase oracle's reputation (increase by 5%)
-            accuracy = if accuracy <= 95 { accuracy + 5 } else { 100 };
+            // 7a. Increase oracle's reputation by the configured reward (capped at 100)
+            accuracy = (accuracy + Self::get_accuracy_reward(env.clone())).min(100);
             new_reputation = accuracy;
             slashed_amount = 0;
 
@@ -769,6 +1886,7 @@ impl OracleManager {
         // 9. Mark challenge as resolved
         challenge.resolved = true;
         env.storage().persistent().set(&challenge_key, &challenge);
+        Self::close_open_challenge(&env, &challenge.challenger);
 
         // 10. Remove market challenge flag (allow finalization)
         let market_challenge_key = (Symbol::new(&env, "market_challenged"), market_id.clone());
@@ -783,6 +1901,58 @@ impl OracleManager {
             slashed_amount,
         }
         .publish(&env);
+
+        Ok(())
+    }
+
+    /// Admin: limit challenge spam per challenger
+    ///
+    /// `cooldown` is the minimum number of seconds between a challenger's challenges and
+    /// `max_open` caps their unresolved challenges at once; 0 disables either limit.
+    pub fn set_challenge_limits(env: Env, cooldown: u64, max_open: u32) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, CHALLENGE_COOLDOWN_KEY), &cooldown);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, MAX_OPEN_CHALLENGES_KEY), &max_open);
+    }
+
+    /// Get the (cooldown, max_open) challenge limits; (0, 0) when unset
+    pub fn get_challenge_limits(env: Env) -> (u64, u32) {
+        let cooldown: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CHALLENGE_COOLDOWN_KEY))
+            .unwrap_or(0);
+        let max_open: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MAX_OPEN_CHALLENGES_KEY))
+            .unwrap_or(0);
+        (cooldown, max_open)
+    }
+
+    /// Get how many of `challenger`'s challenges are still unresolved
+    pub fn get_open_challenge_count(env: Env, challenger: Address) -> u32 {
+        let open_key = (Symbol::new(&env, OPEN_CHALLENGES_KEY), challenger);
+        env.storage().persistent().get(&open_key).unwrap_or(0)
+    }
+
+    /// Helper: Drop a resolved or released challenge from its challenger's open count
+    fn close_open_challenge(env: &Env, challenger: &Address) {
+        let open = Self::get_open_challenge_count(env.clone(), challenger.clone());
+        let open_key = (Symbol::new(env, OPEN_CHALLENGES_KEY), challenger.clone());
+        env.storage()
+            .persistent()
+            .set(&open_key, &open.saturating_sub(1));
     }
 
     /// Get all attestations for a market
@@ -857,12 +2027,189 @@ impl OracleManager {
             .unwrap_or(false)
     }
 
+    /// Admin: delegate oracle registration and configuration to `registrar`
+    pub fn set_registrar(env: Env, registrar: Address) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, REGISTRAR_KEY), &registrar);
+    }
+
+    /// Admin: delegate challenge arbitration to `resolver`
+    pub fn set_resolver(env: Env, resolver: Address) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, RESOLVER_KEY), &resolver);
+    }
+
+    /// Get the registrar (the admin unless delegated)
+    pub fn get_registrar(env: Env) -> Address {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, REGISTRAR_KEY))
+            .unwrap_or_else(|| {
+                env.storage()
+                    .persistent()
+                    .get(&Symbol::new(&env, ADMIN_KEY))
+                    .expect("Oracle not initialized")
+            })
+    }
+
+    /// Get the resolver (the admin unless delegated)
+    pub fn get_resolver(env: Env) -> Address {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, RESOLVER_KEY))
+            .unwrap_or_else(|| {
+                env.storage()
+                    .persistent()
+                    .get(&Symbol::new(&env, ADMIN_KEY))
+                    .expect("Oracle not initialized")
+            })
+    }
+
+    /// Require authorization from the registrar
+    fn require_registrar(env: &Env) {
+        Self::get_registrar(env.clone()).require_auth();
+    }
+
+    /// Require authorization from the resolver
+    fn require_resolver(env: &Env) {
+        Self::get_resolver(env.clone()).require_auth();
+    }
+
+    /// Get the share of an oracle's stake slashed on a valid challenge (basis points)
+    pub fn get_slash_bps(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, SLASH_BPS_KEY))
+            .unwrap_or(5000)
+    }
+
+    /// Get the accuracy points an oracle loses on a valid challenge
+    pub fn get_accuracy_penalty(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, ACCURACY_PENALTY_KEY))
+            .unwrap_or(20)
+    }
+
+    /// Get the accuracy points an oracle gains when a challenge against it fails
+    pub fn get_accuracy_reward(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, ACCURACY_REWARD_KEY))
+            .unwrap_or(5)
+    }
+
+    /// Get the token oracles lock as registration stake
+    pub fn get_stake_token(env: Env) -> Address {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, STAKE_TOKEN_KEY))
+            .expect("Stake token not set")
+    }
+
+    /// Get the stake each oracle must lock to register
+    pub fn get_registration_stake(env: Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, REGISTRATION_STAKE_KEY))
+            .unwrap_or(0)
+    }
+
     /// Get oracle's current stake
     pub fn get_oracle_stake(env: Env, oracle: Address) -> i128 {
         let stake_key = (Symbol::new(&env, ORACLE_STAKE_KEY), oracle);
         env.storage().persistent().get(&stake_key).unwrap_or(0)
     }
 
+    /// Get the total current stake of every oracle that attested on a market
+    pub fn get_market_backing_stake(env: Env, market_id: BytesN<32>) -> i128 {
+        let voters_key = (Symbol::new(&env, "voters"), market_id);
+        let voters: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&voters_key)
+            .unwrap_or(Vec::new(&env));
+
+        let mut backing_stake: i128 = 0;
+        for oracle in voters.iter() {
+            backing_stake += Self::get_oracle_stake(env.clone(), oracle);
+        }
+        backing_stake
+    }
+
+    /// Get the markets an oracle has attested on, in attestation order
+    pub fn get_oracle_markets(env: Env, oracle: Address) -> Vec<BytesN<32>> {
+        let markets_key = (Symbol::new(&env, ORACLE_MARKETS_KEY), oracle);
+        env.storage()
+            .persistent()
+            .get(&markets_key)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Recalculate an oracle's accuracy from its attestation history
+    ///
+    /// Resolver only, since the recomputed score replaces any challenge penalties.
+    /// Compares each attestation against the finalized consensus result and sets
+    /// accuracy to the agreement percentage; markets without a final result are
+    /// skipped. With nothing finalized yet the score is left unchanged.
+    pub fn recompute_accuracy(env: Env, oracle: Address) -> u32 {
+        Self::require_resolver(&env);
+
+        let accuracy_key = (Symbol::new(&env, "oracle_accuracy"), oracle.clone());
+
+        let mut compared = 0u32;
+        let mut agreed = 0u32;
+        for market_id in Self::get_oracle_markets(env.clone(), oracle.clone()).iter() {
+            let result_key = (Symbol::new(&env, "consensus_result"), market_id.clone());
+            let Some(final_outcome) = env.storage().persistent().get::<_, u32>(&result_key) else {
+                continue;
+            };
+            let vote_key = (Symbol::new(&env, "vote"), market_id, oracle.clone());
+            let vote: u32 = env
+                .storage()
+                .persistent()
+                .get(&vote_key)
+                .expect("Vote not found");
+
+            compared += 1;
+            if vote == final_outcome {
+                agreed += 1;
+            }
+        }
+
+        if compared == 0 {
+            return Self::get_oracle_accuracy(env, oracle);
+        }
+
+        let accuracy = agreed * 100 / compared;
+        env.storage().persistent().set(&accuracy_key, &accuracy);
+
+        AccuracyRecomputedEvent {
+            oracle,
+            accuracy,
+            markets_compared: compared,
+        }
+        .publish(&env);
+
+        accuracy
+    }
+
     /// Get oracle's accuracy score
     pub fn get_oracle_accuracy(env: Env, oracle: Address) -> u32 {
         let accuracy_key = (Symbol::new(&env, "oracle_accuracy"), oracle);
@@ -889,10 +2236,10 @@ impl OracleManager {
         market_id: BytesN<32>,
         forced_outcome: u32,
         justification_hash: BytesN<32>,
-    ) {
+    ) -> Result<(), OracleError> {
         // 1. Validate forced_outcome is binary (0 or 1)
         if forced_outcome > 1 {
-            panic!("Invalid outcome: must be 0 or 1");
+            return Err(OracleError::InvalidOutcome);
         }
 
         // 2. Get admin signers and required signatures
@@ -910,7 +2257,7 @@ impl OracleManager {
 
         // 3. Validate we have enough approvers
         if approvers.len() < required_sigs {
-            panic!("Insufficient approvers");
+            return Err(OracleError::InsufficientApprovers);
         }
 
         // 4. Verify all approvers are valid admins and require their auth
@@ -929,7 +2276,7 @@ impl OracleManager {
             }
 
             if !is_valid_admin {
-                panic!("Invalid approver: not an admin");
+                return Err(OracleError::InvalidApprover);
             }
 
             valid_approver_count += 1;
@@ -937,7 +2284,7 @@ impl OracleManager {
 
         // 5. Ensure no duplicate approvers (each admin can only approve once)
         if valid_approver_count != approvers.len() {
-            panic!("Duplicate approvers detected");
+            return Err(OracleError::DuplicateApprovers);
         }
 
         // 6. Check cooldown period
@@ -956,18 +2303,19 @@ impl OracleManager {
         let current_time = env.ledger().timestamp();
 
         if last_override_time > 0 && (current_time - last_override_time) < cooldown_period {
-            panic!("Cooldown period not elapsed");
+            return Err(OracleError::OverrideCooldownActive);
         }
 
         // 7. Verify market exists
         let market_key = (Symbol::new(&env, MARKET_RES_TIME_KEY), market_id.clone());
         if !env.storage().persistent().has(&market_key) {
-            panic!("Market not registered");
+            return Err(OracleError::MarketNotRegistered);
         }
 
         // 8. Store consensus result (override any existing consensus)
         let result_key = (Symbol::new(&env, "consensus_result"), market_id.clone());
         env.storage().persistent().set(&result_key, &forced_outcome);
+        Self::freeze_reward_split(&env, &market_id, forced_outcome);
 
         // 9. Mark market as manually overridden for audit purposes
         let override_flag_key = (Symbol::new(&env, "manual_override"), market_id.clone());
@@ -1010,6 +2358,8 @@ impl OracleManager {
             timestamp: current_time,
         }
         .publish(&env);
+
+        Ok(())
     }
 
     /// Get emergency override record for a market (for audit purposes)
@@ -1068,27 +2418,60 @@ mod tests {
 
     // Do NOT expose contractimpl or initialize here, only use OracleManagerClient
     fn setup_oracle(env: &Env) -> (OracleManagerClient<'_>, Address, Address, Address) {
+        setup_oracle_with_penalties(env, 5000, 20, 5)
+    }
+
+    fn setup_oracle_with_penalties(
+        env: &Env,
+        slash_bps: u32,
+        accuracy_penalty: u32,
+        accuracy_reward: u32,
+    ) -> (OracleManagerClient<'_>, Address, Address, Address) {
         let admin = Address::generate(env);
         let oracle1 = Address::generate(env);
         let oracle2 = Address::generate(env);
 
         let oracle_id = env.register(OracleManager, ());
         let oracle_client = OracleManagerClient::new(env, &oracle_id);
+        let stake_token = env
+            .register_stellar_asset_contract_v2(Address::generate(env))
+            .address();
 
         env.mock_all_auths();
-        oracle_client.initialize(&admin, &2); // Require 2 oracles for consensus
+        // Require 2 oracles for consensus, each locking 10x the challenge stake
+        oracle_client.initialize(
+            &admin,
+            &2,
+            &stake_token,
+            &(CHALLENGE_STAKE_AMOUNT * 10),
+            &slash_bps,
+            &accuracy_penalty,
+            &accuracy_reward,
+        );
 
         (oracle_client, admin, oracle1, oracle2)
     }
 
+    /// Mint the registration stake to `oracle` and register it
+    fn register_funded_oracle(
+        env: &Env,
+        oracle_client: &OracleManagerClient,
+        oracle: &Address,
+        oracle_name: &str,
+    ) {
+        token::StellarAssetClient::new(env, &oracle_client.get_stake_token())
+            .mint(oracle, &oracle_client.get_registration_stake());
+        oracle_client.register_oracle(oracle, &Symbol::new(env, oracle_name));
+    }
+
     fn register_test_oracles(
         env: &Env,
         oracle_client: &OracleManagerClient,
         oracle1: &Address,
         oracle2: &Address,
     ) {
-        oracle_client.register_oracle(oracle1, &Symbol::new(env, "Oracle1"));
-        oracle_client.register_oracle(oracle2, &Symbol::new(env, "Oracle2"));
+        register_funded_oracle(env, oracle_client, oracle1, "Oracle1");
+        register_funded_oracle(env, oracle_client, oracle2, "Oracle2");
     }
 
     fn create_market_id(env: &Env) -> BytesN<32> {
@@ -1140,303 +2523,1133 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Attestation not found")]
-    fn test_challenge_nonexistent_attestation() {
+    fn test_challenge_nonexistent_attestation() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let challenger = Address::generate(&env);
+        let reason = Symbol::new(&env, "fraud");
+
+        // Try to challenge without attestation
+        assert_eq!(
+            oracle_client.try_challenge_attestation(&challenger, &oracle1, &market_id, &reason),
+            Err(Ok(OracleError::AttestationNotFound))
+        );
+    }
+
+    #[test]
+    fn test_challenge_duplicate() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+
+        oracle_client.register_market(&market_id, &resolution_time);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
+
+        let challenger = Address::generate(&env);
+        let reason = Symbol::new(&env, "fraud");
+
+        // First challenge
+        oracle_client.challenge_attestation(&challenger, &oracle1, &market_id, &reason);
+
+        // Try to challenge again
+        assert_eq!(
+            oracle_client.try_challenge_attestation(&challenger, &oracle1, &market_id, &reason),
+            Err(Ok(OracleError::ChallengeAlreadyExists))
+        );
+    }
+
+    #[test]
+    fn test_resolve_challenge_valid_slashes_oracle() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+
+        oracle_client.register_market(&market_id, &resolution_time);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
+
+        // Get initial oracle stake and accuracy
+        let initial_stake = oracle_client.get_oracle_stake(&oracle1);
+        let initial_accuracy = oracle_client.get_oracle_accuracy(&oracle1);
+        assert_eq!(initial_accuracy, 100);
+
+        let challenger = Address::generate(&env);
+        let reason = Symbol::new(&env, "fraud");
+
+        oracle_client.challenge_attestation(&challenger, &oracle1, &market_id, &reason);
+
+        // Admin resolves challenge as valid (oracle was dishonest)
+        oracle_client.resolve_challenge(&oracle1, &market_id, &true);
+
+        // Verify challenge is resolved
+        let challenge = oracle_client.get_challenge(&oracle1, &market_id).unwrap();
+        assert!(challenge.resolved);
+
+        // Verify oracle's stake was slashed (50%)
+        let new_stake = oracle_client.get_oracle_stake(&oracle1);
+        assert_eq!(new_stake, initial_stake / 2);
+
+        // Verify oracle's accuracy was reduced (by 20%)
+        let new_accuracy = oracle_client.get_oracle_accuracy(&oracle1);
+        assert_eq!(new_accuracy, 80);
+
+        // Verify market challenge flag is removed
+        assert!(!oracle_client.has_active_challenge(&market_id));
+    }
+
+    #[test]
+    fn test_resolve_challenge_uses_configured_penalties() {
+        let env = Env::default();
+        let (oracle_client, _admin, oracle1, oracle2) =
+            setup_oracle_with_penalties(&env, 3000, 30, 15);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_a = create_market_id(&env);
+        let market_b = BytesN::from_array(&env, &[2u8; 32]);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_a, &resolution_time);
+        oracle_client.register_market(&market_b, &resolution_time);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        let reason = Symbol::new(&env, "fraud");
+        oracle_client.submit_attestation(&oracle1, &market_a, &1, &data_hash);
+        oracle_client.submit_attestation(&oracle1, &market_b, &1, &data_hash);
+
+        // Valid challenge: 30% slash, 30 accuracy points lost
+        oracle_client.challenge_attestation(&Address::generate(&env), &oracle1, &market_a, &reason);
+        oracle_client.resolve_challenge(&oracle1, &market_a, &true);
+        assert_eq!(
+            oracle_client.get_oracle_stake(&oracle1),
+            CHALLENGE_STAKE_AMOUNT * 10 * 7 / 10
+        );
+        assert_eq!(oracle_client.get_oracle_accuracy(&oracle1), 70);
+
+        // Invalid challenge: 15 accuracy points regained, stake untouched
+        oracle_client.challenge_attestation(&Address::generate(&env), &oracle1, &market_b, &reason);
+        oracle_client.resolve_challenge(&oracle1, &market_b, &false);
+        assert_eq!(
+            oracle_client.get_oracle_stake(&oracle1),
+            CHALLENGE_STAKE_AMOUNT * 10 * 7 / 10
+        );
+        assert_eq!(oracle_client.get_oracle_accuracy(&oracle1), 85);
+    }
+
+    #[test]
+    fn test_resolve_challenge_invalid_rewards_oracle() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+
+        oracle_client.register_market(&market_id, &resolution_time);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
+
+        let initial_stake = oracle_client.get_oracle_stake(&oracle1);
+        let _initial_accuracy = oracle_client.get_oracle_accuracy(&oracle1);
+
+        let challenger = Address::generate(&env);
+        let reason = Symbol::new(&env, "fraud");
+
+        oracle_client.challenge_attestation(&challenger, &oracle1, &market_id, &reason);
+
+        // Admin resolves challenge as invalid (oracle was honest)
+        oracle_client.resolve_challenge(&oracle1, &market_id, &false);
+
+        // Verify challenge is resolved
+        let challenge = oracle_client.get_challenge(&oracle1, &market_id).unwrap();
+        assert!(challenge.resolved);
+
+        // Verify oracle's stake was NOT slashed
+        let new_stake = oracle_client.get_oracle_stake(&oracle1);
+        assert_eq!(new_stake, initial_stake);
+
+        // Verify oracle's accuracy was increased (by 5%)
+        let new_accuracy = oracle_client.get_oracle_accuracy(&oracle1);
+        assert_eq!(new_accuracy, 100); // Capped at 100
+
+        // Verify market challenge flag is removed
+        assert!(!oracle_client.has_active_challenge(&market_id));
+    }
+
+    #[test]
+    fn test_resolve_challenge_deregisters_low_accuracy_oracle() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        // Manually set oracle accuracy to 60% (just above threshold)
+        let accuracy_key = (Symbol::new(&env, "oracle_accuracy"), oracle1.clone());
+        env.as_contract(&oracle_client.address, || {
+            env.storage().persistent().set(&accuracy_key, &60u32);
+        });
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+
+        oracle_client.register_market(&market_id, &resolution_time);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
+
+        let challenger = Address::generate(&env);
+        let reason = Symbol::new(&env, "fraud");
+
+        oracle_client.challenge_attestation(&challenger, &oracle1, &market_id, &reason);
+
+        // Admin resolves challenge as valid - this should drop accuracy to 40% (below 50% threshold)
+        oracle_client.resolve_challenge(&oracle1, &market_id, &true);
+
+        // Verify oracle's accuracy dropped below threshold
+        let new_accuracy = oracle_client.get_oracle_accuracy(&oracle1);
+        assert_eq!(new_accuracy, 40);
+
+        // Verify oracle was deregistered (marked as inactive)
+        let oracle_key = (Symbol::new(&env, "oracle"), oracle1.clone());
+        let is_active: bool = env
+            .as_contract(&oracle_client.address, || {
+                env.storage().persistent().get(&oracle_key)
+            })
+            .unwrap_or(true);
+        assert!(!is_active);
+    }
+
+    #[test]
+    fn test_resolve_nonexistent_challenge() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+
+        // Try to resolve non-existent challenge
+        assert_eq!(
+            oracle_client.try_resolve_challenge(&oracle1, &market_id, &true),
+            Err(Ok(OracleError::ChallengeNotFound))
+        );
+    }
+
+    #[test]
+    fn test_resolve_challenge_twice() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+
+        oracle_client.register_market(&market_id, &resolution_time);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
+
+        let challenger = Address::generate(&env);
+        let reason = Symbol::new(&env, "fraud");
+
+        oracle_client.challenge_attestation(&challenger, &oracle1, &market_id, &reason);
+
+        // First resolution
+        oracle_client.resolve_challenge(&oracle1, &market_id, &true);
+
+        // Try to resolve again
+        assert_eq!(
+            oracle_client.try_resolve_challenge(&oracle1, &market_id, &true),
+            Err(Ok(OracleError::ChallengeAlreadyResolved))
+        );
+    }
+
+    #[test]
+    fn test_oracle_stake_initialized_on_registration() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, _oracle2) = setup_oracle(&env);
+
+        // Register oracle
+        register_funded_oracle(&env, &oracle_client, &oracle1, "Oracle1");
+
+        // Verify the locked stake was recorded and moved into the contract
+        let stake = oracle_client.get_oracle_stake(&oracle1);
+        assert_eq!(stake, CHALLENGE_STAKE_AMOUNT * 10);
+
+        let token_client = token::Client::new(&env, &oracle_client.get_stake_token());
+        assert_eq!(token_client.balance(&oracle1), 0);
+        assert_eq!(token_client.balance(&oracle_client.address), stake);
+    }
+
+    #[test]
+    fn test_unfunded_oracle_registration_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, _oracle2) = setup_oracle(&env);
+
+        assert_eq!(
+            oracle_client.try_register_oracle(&oracle1, &Symbol::new(&env, "Oracle1")),
+            Err(Ok(OracleError::StakeTransferFailed))
+        );
+    }
+
+    #[test]
+    fn test_get_challenge_returns_none_when_no_challenge() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, _oracle2) = setup_oracle(&env);
+        let market_id = create_market_id(&env);
+
+        let challenge = oracle_client.get_challenge(&oracle1, &market_id);
+        assert!(challenge.is_none());
+    }
+
+    #[test]
+    fn test_has_active_challenge_returns_false_initially() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, _oracle1, _oracle2) = setup_oracle(&env);
+        let market_id = create_market_id(&env);
+
+        assert!(!oracle_client.has_active_challenge(&market_id));
+    }
+
+    #[test]
+    fn test_multiple_challenges_different_oracles() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+
+        oracle_client.register_market(&market_id, &resolution_time);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+
+        // Both oracles submit attestations
+        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
+        oracle_client.submit_attestation(&oracle2, &market_id, &0, &data_hash);
+
+        let challenger = Address::generate(&env);
+        let reason = Symbol::new(&env, "fraud");
+
+        // Challenge both oracles
+        oracle_client.challenge_attestation(&challenger, &oracle1, &market_id, &reason);
+        oracle_client.challenge_attestation(&challenger, &oracle2, &market_id, &reason);
+
+        // Verify both challenges exist
+        assert!(oracle_client.get_challenge(&oracle1, &market_id).is_some());
+        assert!(oracle_client.get_challenge(&oracle2, &market_id).is_some());
+    }
+
+    /// Helper: Two registered oracles with attestations on a market past resolution time
+    fn setup_challengeable_market(
+        env: &Env,
+    ) -> (OracleManagerClient<'_>, Address, Address, BytesN<32>) {
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(env);
+        register_test_oracles(env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(env);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_id, &resolution_time);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        let data_hash = BytesN::from_array(env, &[2u8; 32]);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
+        oracle_client.submit_attestation(&oracle2, &market_id, &0, &data_hash);
+
+        (oracle_client, oracle1, oracle2, market_id)
+    }
+
+    #[test]
+    fn test_challenge_cooldown_blocks_rapid_challenges() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (oracle_client, oracle1, oracle2, market_id) = setup_challengeable_market(&env);
+        oracle_client.set_challenge_limits(&3600, &0);
+        assert_eq!(oracle_client.get_challenge_limits(), (3600, 0));
+
+        let challenger = Address::generate(&env);
+        let reason = Symbol::new(&env, "fraud");
+        oracle_client.challenge_attestation(&challenger, &oracle1, &market_id, &reason);
+
+        let result =
+            oracle_client.try_challenge_attestation(&challenger, &oracle2, &market_id, &reason);
+        assert_eq!(result, Err(Ok(OracleError::ChallengeCooldownActive)));
+
+        // Once the cooldown has elapsed the same challenger may challenge again
+        let start = env.ledger().timestamp();
+        env.ledger().with_mut(|li| li.timestamp = start + 3600);
+        oracle_client.challenge_attestation(&challenger, &oracle2, &market_id, &reason);
+        assert_eq!(oracle_client.get_open_challenge_count(&challenger), 2);
+    }
+
+    #[test]
+    fn test_open_challenge_cap_released_on_resolution() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (oracle_client, oracle1, oracle2, market_id) = setup_challengeable_market(&env);
+        oracle_client.set_challenge_limits(&0, &1);
+
+        let challenger = Address::generate(&env);
+        let reason = Symbol::new(&env, "fraud");
+        oracle_client.challenge_attestation(&challenger, &oracle1, &market_id, &reason);
+        assert_eq!(oracle_client.get_open_challenge_count(&challenger), 1);
+
+        let result =
+            oracle_client.try_challenge_attestation(&challenger, &oracle2, &market_id, &reason);
+        assert_eq!(result, Err(Ok(OracleError::TooManyOpenChallenges)));
+
+        // Resolving the open challenge frees the slot
+        oracle_client.resolve_challenge(&oracle1, &market_id, &false);
+        assert_eq!(oracle_client.get_open_challenge_count(&challenger), 0);
+        oracle_client.challenge_attestation(&challenger, &oracle2, &market_id, &reason);
+        assert_eq!(oracle_client.get_open_challenge_count(&challenger), 1);
+    }
+
+    fn sign_feed_message(
+        env: &Env,
+        signing_key: &ed25519_dalek::SigningKey,
+        market_id: &BytesN<32>,
+        outcome: u32,
+        timestamp: u64,
+        nonce: u64,
+    ) -> BytesN<64> {
+        use ed25519_dalek::Signer;
+
+        let mut message = [0u8; 52];
+        message[..32].copy_from_slice(&market_id.to_array());
+        message[32..36].copy_from_slice(&outcome.to_be_bytes());
+        message[36..44].copy_from_slice(&timestamp.to_be_bytes());
+        message[44..].copy_from_slice(&nonce.to_be_bytes());
+        BytesN::from_array(env, &signing_key.sign(&message).to_bytes())
+    }
+
+    #[test]
+    fn test_submit_signed_attestation_valid_signature() {
+        let env = Env::default();
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+        let feed_pubkey = BytesN::from_array(&env, &signing_key.verifying_key().to_bytes());
+        let feed_identity = Address::generate(&env);
+        oracle_client.register_signed_feed(&feed_pubkey, &feed_identity);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_id, &resolution_time);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        let signature = sign_feed_message(&env, &signing_key, &market_id, 1, resolution_time, 1);
+        oracle_client.submit_signed_attestation(&market_id, &1, &1, &signature, &feed_pubkey);
+
+        assert_eq!(oracle_client.get_attestation_counts(&market_id), (1, 0));
+        let attestation = oracle_client
+            .get_attestation(&market_id, &feed_identity)
+            .unwrap();
+        assert_eq!(attestation.outcome, 1);
+    }
+
+    #[test]
+    fn test_submit_signed_attestation_forged_signature_rejected() {
+        let env = Env::default();
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+        let feed_pubkey = BytesN::from_array(&env, &signing_key.verifying_key().to_bytes());
+        oracle_client.register_signed_feed(&feed_pubkey, &Address::generate(&env));
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_id, &resolution_time);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        // Signed by a different key, and for a different outcome
+        let forger = ed25519_dalek::SigningKey::from_bytes(&[9u8; 32]);
+        let forged = sign_feed_message(&env, &forger, &market_id, 1, resolution_time, 1);
+        assert!(oracle_client
+            .try_submit_signed_attestation(&market_id, &1, &1, &forged, &feed_pubkey)
+            .is_err());
+
+        let tampered = sign_feed_message(&env, &signing_key, &market_id, 0, resolution_time, 1);
+        assert!(oracle_client
+            .try_submit_signed_attestation(&market_id, &1, &1, &tampered, &feed_pubkey)
+            .is_err());
+
+        assert_eq!(oracle_client.get_attestation_counts(&market_id), (0, 0));
+    }
+
+    /// A registered signed feed plus two markets past their shared resolution time
+    fn setup_signed_feed_markets(
+        env: &Env,
+        oracle_client: &OracleManagerClient,
+    ) -> (
+        ed25519_dalek::SigningKey,
+        BytesN<32>,
+        BytesN<32>,
+        BytesN<32>,
+        u64,
+    ) {
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+        let feed_pubkey = BytesN::from_array(env, &signing_key.verifying_key().to_bytes());
+        oracle_client.register_signed_feed(&feed_pubkey, &Address::generate(env));
+
+        let market_a = create_market_id(env);
+        let market_b = BytesN::from_array(env, &[2u8; 32]);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_a, &resolution_time);
+        oracle_client.register_market(&market_b, &resolution_time);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        (
+            signing_key,
+            feed_pubkey,
+            market_a,
+            market_b,
+            resolution_time,
+        )
+    }
+
+    #[test]
+    fn test_signed_attestation_replay_rejected() {
+        let env = Env::default();
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+        let (signing_key, feed_pubkey, market_a, market_b, resolution_time) =
+            setup_signed_feed_markets(&env, &oracle_client);
+
+        let signature = sign_feed_message(&env, &signing_key, &market_a, 1, resolution_time, 1);
+        oracle_client.submit_signed_attestation(&market_a, &1, &1, &signature, &feed_pubkey);
+        assert!(oracle_client.is_nonce_used(&feed_pubkey, &1));
+
+        // Exact replay
+        assert!(oracle_client
+            .try_submit_signed_attestation(&market_a, &1, &1, &signature, &feed_pubkey)
+            .is_err());
+
+        // A validly signed message for another market cannot reuse the nonce either
+        let reused = sign_feed_message(&env, &signing_key, &market_b, 1, resolution_time, 1);
+        assert!(oracle_client
+            .try_submit_signed_attestation(&market_b, &1, &1, &reused, &feed_pubkey)
+            .is_err());
+        assert_eq!(oracle_client.get_attestation_counts(&market_b), (0, 0));
+    }
+
+    #[test]
+    fn test_signed_attestations_with_distinct_nonces_accepted() {
+        let env = Env::default();
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+        let (signing_key, feed_pubkey, market_a, market_b, resolution_time) =
+            setup_signed_feed_markets(&env, &oracle_client);
+
+        let sig_a = sign_feed_message(&env, &signing_key, &market_a, 1, resolution_time, 1);
+        let sig_b = sign_feed_message(&env, &signing_key, &market_b, 0, resolution_time, 2);
+        oracle_client.submit_signed_attestation(&market_a, &1, &1, &sig_a, &feed_pubkey);
+        oracle_client.submit_signed_attestation(&market_b, &0, &2, &sig_b, &feed_pubkey);
+
+        assert_eq!(oracle_client.get_attestation_counts(&market_a), (1, 0));
+        assert_eq!(oracle_client.get_attestation_counts(&market_b), (0, 1));
+    }
+
+    /// sha256(market_id || outcome_be_bytes || salt), as reveal_attestation reconstructs it
+    fn compute_attestation_hash(
+        env: &Env,
+        market_id: &BytesN<32>,
+        outcome: u32,
+        salt: &BytesN<32>,
+    ) -> BytesN<32> {
+        let mut preimage = Bytes::new(env);
+        preimage.extend_from_array(&market_id.to_array());
+        preimage.extend_from_array(&outcome.to_be_bytes());
+        preimage.extend_from_array(&salt.to_array());
+        BytesN::from_array(env, &env.crypto().sha256(&preimage).to_array())
+    }
+
+    #[test]
+    fn test_commit_reveal_attestation_flow() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_id, &resolution_time);
+        oracle_client.set_sealed_voting(&market_id, &true);
+        env.ledger().with_mut(|li| li.timestamp = resolution_time);
+
+        let salt1 = BytesN::from_array(&env, &[5u8; 32]);
+        let salt2 = BytesN::from_array(&env, &[6u8; 32]);
+        let hash1 = compute_attestation_hash(&env, &market_id, 1, &salt1);
+        let hash2 = compute_attestation_hash(&env, &market_id, 1, &salt2);
+        oracle_client.commit_attestation(&oracle1, &market_id, &hash1);
+        oracle_client.commit_attestation(&oracle2, &market_id, &hash2);
+
+        // Sealed votes are not counted
+        assert_eq!(
+            oracle_client.get_attestation_commitment(&market_id, &oracle1),
+            Some(hash1)
+        );
+        assert_eq!(oracle_client.get_attestation_counts(&market_id), (0, 0));
+        assert_eq!(oracle_client.check_consensus(&market_id), (false, 0));
+
+        // Plaintext votes would leak the outcome before the reveal window
+        let oracle3 = Address::generate(&env);
+        register_funded_oracle(&env, &oracle_client, &oracle3, "oracle3");
+        assert_eq!(
+            oracle_client.try_submit_attestation(
+                &oracle3,
+                &market_id,
+                &1,
+                &BytesN::from_array(&env, &[0u8; 32])
+            ),
+            Err(Ok(OracleError::SealedVotingRequired))
+        );
+
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + ATTEST_COMMIT_WINDOW);
+        oracle_client.reveal_attestation(&oracle1, &market_id, &1, &salt1);
+        oracle_client.reveal_attestation(&oracle2, &market_id, &1, &salt2);
+
+        assert_eq!(
+            oracle_client.get_attestation_commitment(&market_id, &oracle1),
+            None
+        );
+        assert_eq!(oracle_client.get_attestation_counts(&market_id), (2, 0));
+        assert_eq!(oracle_client.check_consensus(&market_id), (true, 1));
+    }
+
+    #[test]
+    fn test_reveal_attestation_mismatch_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_id, &resolution_time);
+        oracle_client.set_sealed_voting(&market_id, &true);
+        env.ledger().with_mut(|li| li.timestamp = resolution_time);
+
+        let salt = BytesN::from_array(&env, &[5u8; 32]);
+        let hash = compute_attestation_hash(&env, &market_id, 1, &salt);
+        oracle_client.commit_attestation(&oracle1, &market_id, &hash);
+
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + ATTEST_COMMIT_WINDOW);
+        // Revealing a different outcome than was committed
+        assert_eq!(
+            oracle_client.try_reveal_attestation(&oracle1, &market_id, &0, &salt),
+            Err(Ok(OracleError::CommitmentMismatch))
+        );
+    }
+
+    #[test]
+    fn test_reveal_attestation_during_commit_window_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_id, &resolution_time);
+        oracle_client.set_sealed_voting(&market_id, &true);
+        env.ledger().with_mut(|li| li.timestamp = resolution_time);
+
+        let salt = BytesN::from_array(&env, &[5u8; 32]);
+        let hash = compute_attestation_hash(&env, &market_id, 1, &salt);
+        oracle_client.commit_attestation(&oracle1, &market_id, &hash);
+        assert_eq!(
+            oracle_client.try_reveal_attestation(&oracle1, &market_id, &1, &salt),
+            Err(Ok(OracleError::RevealWindowNotOpen))
+        );
+    }
+
+    #[test]
+    fn test_sealed_voting_mode_is_exclusive_and_fixed_at_resolution() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_id, &resolution_time);
+        env.ledger().with_mut(|li| li.timestamp = resolution_time);
+
+        // Open-ballot markets take no commits, and the mode is fixed once voting opens
+        let salt = BytesN::from_array(&env, &[5u8; 32]);
+        let hash = compute_attestation_hash(&env, &market_id, 1, &salt);
+        assert_eq!(
+            oracle_client.try_commit_attestation(&oracle1, &market_id, &hash),
+            Err(Ok(OracleError::SealedVotingNotEnabled))
+        );
+        assert_eq!(
+            oracle_client.try_set_sealed_voting(&market_id, &true),
+            Err(Ok(OracleError::VotingAlreadyOpen))
+        );
+    }
+
+    fn register_batch_markets(env: &Env, oracle_client: &OracleManagerClient) -> Vec<BytesN<32>> {
+        let resolution_time = env.ledger().timestamp() + 100;
+        let mut market_ids = Vec::new(env);
+        for seed in 1..=3u8 {
+            let market_id = BytesN::from_array(env, &[seed; 32]);
+            oracle_client.register_market(&market_id, &resolution_time);
+            market_ids.push_back(market_id);
+        }
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+        market_ids
+    }
+
+    #[test]
+    fn test_recompute_accuracy_from_finalized_history() {
         let env = Env::default();
-        env.mock_all_auths();
-
         let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
         register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+        let market_ids = register_batch_markets(&env, &oracle_client);
 
-        let market_id = create_market_id(&env);
-        let challenger = Address::generate(&env);
-        let reason = Symbol::new(&env, "fraud");
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        for (market_id, outcome) in market_ids.iter().zip([1u32, 0, 1]) {
+            oracle_client.submit_attestation(&oracle1, &market_id, &outcome, &data_hash);
+        }
+        assert_eq!(oracle_client.get_oracle_markets(&oracle1), market_ids);
 
-        // Try to challenge without attestation
-        oracle_client.challenge_attestation(&challenger, &oracle1, &market_id, &reason);
+        // Only the first two markets have a final result, both YES
+        env.as_contract(&oracle_client.address, || {
+            for market_id in market_ids.iter().take(2) {
+                let result_key = (Symbol::new(&env, "consensus_result"), market_id);
+                env.storage().persistent().set(&result_key, &1u32);
+            }
+        });
+
+        assert_eq!(oracle_client.recompute_accuracy(&oracle1), 50);
+        assert_eq!(oracle_client.get_oracle_accuracy(&oracle1), 50);
+
+        // No attestation history leaves the score untouched
+        assert_eq!(oracle_client.recompute_accuracy(&oracle2), 100);
     }
 
     #[test]
-    #[should_panic(expected = "Challenge already exists")]
-    fn test_challenge_duplicate() {
+    fn test_seasoned_oracle_may_attest() {
         let env = Env::default();
-        env.mock_all_auths();
-
         let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        oracle_client.set_min_oracle_age(&1000);
         register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
 
+        env.ledger().with_mut(|li| li.timestamp += 2000);
         let market_id = create_market_id(&env);
-        let resolution_time = env.ledger().timestamp() + 100;
-
+        let resolution_time = env.ledger().timestamp();
         oracle_client.register_market(&market_id, &resolution_time);
-        env.ledger()
-            .with_mut(|li| li.timestamp = resolution_time + 1);
 
         let data_hash = BytesN::from_array(&env, &[2u8; 32]);
         oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
-
-        let challenger = Address::generate(&env);
-        let reason = Symbol::new(&env, "fraud");
-
-        // First challenge
-        oracle_client.challenge_attestation(&challenger, &oracle1, &market_id, &reason);
-
-        // Try to challenge again
-        oracle_client.challenge_attestation(&challenger, &oracle1, &market_id, &reason);
+        assert_eq!(oracle_client.get_attestation_counts(&market_id), (1, 0));
     }
 
     #[test]
-    fn test_resolve_challenge_valid_slashes_oracle() {
+    fn test_abandon_allowed_after_missed_consensus_deadline() {
         let env = Env::default();
-        env.mock_all_auths();
-
         let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
         register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
 
         let market_id = create_market_id(&env);
         let resolution_time = env.ledger().timestamp() + 100;
-
         oracle_client.register_market(&market_id, &resolution_time);
-        env.ledger()
-            .with_mut(|li| li.timestamp = resolution_time + 1);
+        assert_eq!(
+            oracle_client.try_set_consensus_deadline(&market_id, &resolution_time),
+            Err(Ok(OracleError::InvalidConsensusDeadline))
+        );
+        let deadline = resolution_time + 1000;
+        oracle_client.set_consensus_deadline(&market_id, &deadline);
 
+        // Only one of the two required oracles attests
+        env.ledger().with_mut(|li| li.timestamp = resolution_time);
         let data_hash = BytesN::from_array(&env, &[2u8; 32]);
         oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
 
-        // Get initial oracle stake and accuracy
-        let initial_stake = oracle_client.get_oracle_stake(&oracle1);
-        let initial_accuracy = oracle_client.get_oracle_accuracy(&oracle1);
-        assert_eq!(initial_accuracy, 100);
-
-        let challenger = Address::generate(&env);
-        let reason = Symbol::new(&env, "fraud");
-
-        oracle_client.challenge_attestation(&challenger, &oracle1, &market_id, &reason);
-
-        // Admin resolves challenge as valid (oracle was dishonest)
-        oracle_client.resolve_challenge(&oracle1, &market_id, &true);
-
-        // Verify challenge is resolved
-        let challenge = oracle_client.get_challenge(&oracle1, &market_id).unwrap();
-        assert!(challenge.resolved);
-
-        // Verify oracle's stake was slashed (50%)
-        let new_stake = oracle_client.get_oracle_stake(&oracle1);
-        assert_eq!(new_stake, initial_stake / 2);
-
-        // Verify oracle's accuracy was reduced (by 20%)
-        let new_accuracy = oracle_client.get_oracle_accuracy(&oracle1);
-        assert_eq!(new_accuracy, 80);
+        env.ledger().with_mut(|li| li.timestamp = deadline);
+        assert!(!oracle_client.is_consensus_deadline_missed(&market_id));
+        assert_eq!(
+            oracle_client.try_abandon_market(&market_id),
+            Err(Ok(OracleError::ConsensusDeadlineNotMissed))
+        );
 
-        // Verify market challenge flag is removed
-        assert!(!oracle_client.has_active_challenge(&market_id));
+        env.ledger().with_mut(|li| li.timestamp = deadline + 1);
+        assert!(oracle_client.is_consensus_deadline_missed(&market_id));
+        oracle_client.abandon_market(&market_id);
+        assert!(oracle_client.is_market_abandoned(&market_id));
+        assert_eq!(
+            oracle_client.try_submit_attestation(&oracle2, &market_id, &1, &data_hash),
+            Err(Ok(OracleError::MarketAbandoned))
+        );
     }
 
     #[test]
-    fn test_resolve_challenge_invalid_rewards_oracle() {
+    fn test_consensus_before_deadline_blocks_open_abandon() {
         let env = Env::default();
-        env.mock_all_auths();
-
         let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
         register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
 
         let market_id = create_market_id(&env);
         let resolution_time = env.ledger().timestamp() + 100;
-
         oracle_client.register_market(&market_id, &resolution_time);
-        env.ledger()
-            .with_mut(|li| li.timestamp = resolution_time + 1);
+        oracle_client.set_consensus_deadline(&market_id, &(resolution_time + 1000));
 
+        env.ledger().with_mut(|li| li.timestamp = resolution_time);
         let data_hash = BytesN::from_array(&env, &[2u8; 32]);
         oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
+        oracle_client.submit_attestation(&oracle2, &market_id, &1, &data_hash);
 
-        let initial_stake = oracle_client.get_oracle_stake(&oracle1);
-        let _initial_accuracy = oracle_client.get_oracle_accuracy(&oracle1);
-
-        let challenger = Address::generate(&env);
-        let reason = Symbol::new(&env, "fraud");
-
-        oracle_client.challenge_attestation(&challenger, &oracle1, &market_id, &reason);
-
-        // Admin resolves challenge as invalid (oracle was honest)
-        oracle_client.resolve_challenge(&oracle1, &market_id, &false);
-
-        // Verify challenge is resolved
-        let challenge = oracle_client.get_challenge(&oracle1, &market_id).unwrap();
-        assert!(challenge.resolved);
-
-        // Verify oracle's stake was NOT slashed
-        let new_stake = oracle_client.get_oracle_stake(&oracle1);
-        assert_eq!(new_stake, initial_stake);
-
-        // Verify oracle's accuracy was increased (by 5%)
-        let new_accuracy = oracle_client.get_oracle_accuracy(&oracle1);
-        assert_eq!(new_accuracy, 100); // Capped at 100
-
-        // Verify market challenge flag is removed
-        assert!(!oracle_client.has_active_challenge(&market_id));
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1001);
+        assert!(!oracle_client.is_consensus_deadline_missed(&market_id));
+        assert_eq!(
+            oracle_client.try_abandon_market(&market_id),
+            Err(Ok(OracleError::ConsensusDeadlineNotMissed))
+        );
     }
 
     #[test]
-    fn test_resolve_challenge_deregisters_low_accuracy_oracle() {
+    fn test_market_backing_stake_sums_attesting_oracles() {
         let env = Env::default();
-        env.mock_all_auths();
-
         let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
         register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
 
-        // Manually set oracle accuracy to 60% (just above threshold)
-        let accuracy_key = (Symbol::new(&env, "oracle_accuracy"), oracle1.clone());
-        env.as_contract(&oracle_client.address, || {
-            env.storage().persistent().set(&accuracy_key, &60u32);
-        });
-
-        let market_id = create_market_id(&env);
+        let market_a = create_market_id(&env);
+        let market_b = BytesN::from_array(&env, &[3u8; 32]);
         let resolution_time = env.ledger().timestamp() + 100;
-
-        oracle_client.register_market(&market_id, &resolution_time);
+        oracle_client.register_market(&market_a, &resolution_time);
+        oracle_client.register_market(&market_b, &resolution_time);
         env.ledger()
             .with_mut(|li| li.timestamp = resolution_time + 1);
 
+        // A valid challenge on market A halves oracle1's stake
         let data_hash = BytesN::from_array(&env, &[2u8; 32]);
-        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
-
-        let challenger = Address::generate(&env);
-        let reason = Symbol::new(&env, "fraud");
+        oracle_client.submit_attestation(&oracle1, &market_a, &1, &data_hash);
+        oracle_client.challenge_attestation(
+            &Address::generate(&env),
+            &oracle1,
+            &market_a,
+            &Symbol::new(&env, "fraud"),
+        );
+        oracle_client.resolve_challenge(&oracle1, &market_a, &true);
+
+        let stake1 = oracle_client.get_oracle_stake(&oracle1);
+        let stake2 = oracle_client.get_oracle_stake(&oracle2);
+        assert_ne!(stake1, stake2);
+        assert_eq!(oracle_client.get_market_backing_stake(&market_b), 0);
+
+        oracle_client.submit_attestation(&oracle1, &market_b, &1, &data_hash);
+        oracle_client.submit_attestation(&oracle2, &market_b, &1, &data_hash);
+        assert_eq!(
+            oracle_client.get_market_backing_stake(&market_b),
+            stake1 + stake2
+        );
+    }
 
-        oracle_client.challenge_attestation(&challenger, &oracle1, &market_id, &reason);
+    #[test]
+    fn test_markets_needing_attestation_filters_by_time_and_attestation() {
+        let env = Env::default();
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
 
-        // Admin resolves challenge as valid - this should drop accuracy to 40% (below 50% threshold)
-        oracle_client.resolve_challenge(&oracle1, &market_id, &true);
+        let past_market = create_market_id(&env);
+        let future_market = BytesN::from_array(&env, &[3u8; 32]);
+        let now = env.ledger().timestamp();
+        oracle_client.register_market(&past_market, &(now + 100));
+        oracle_client.register_market(&future_market, &(now + 1000));
+        env.ledger().with_mut(|li| li.timestamp = now + 100);
 
-        // Verify oracle's accuracy dropped below threshold
-        let new_accuracy = oracle_client.get_oracle_accuracy(&oracle1);
-        assert_eq!(new_accuracy, 40);
+        assert_eq!(
+            oracle_client.get_registered_markets(),
+            Vec::from_array(&env, [past_market.clone(), future_market.clone()])
+        );
+        let expected = Vec::from_array(&env, [past_market.clone()]);
+        assert_eq!(
+            oracle_client.get_markets_needing_attestation(&oracle1),
+            expected
+        );
 
-        // Verify oracle was deregistered (marked as inactive)
-        let oracle_key = (Symbol::new(&env, "oracle"), oracle1.clone());
-        let is_active: bool = env
-            .as_contract(&oracle_client.address, || {
-                env.storage().persistent().get(&oracle_key)
-            })
-            .unwrap_or(true);
-        assert!(!is_active);
+        // Once oracle1 attests, only oracle2 still owes an attestation
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        oracle_client.submit_attestation(&oracle1, &past_market, &1, &data_hash);
+        assert_eq!(
+            oracle_client
+                .get_markets_needing_attestation(&oracle1)
+                .len(),
+            0
+        );
+        assert_eq!(
+            oracle_client.get_markets_needing_attestation(&oracle2),
+            expected
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Challenge not found")]
-    fn test_resolve_nonexistent_challenge() {
+    fn test_register_market_requires_consensus_oracles() {
         let env = Env::default();
-        env.mock_all_auths();
-
         let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
-        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
-
         let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
 
-        // Try to resolve non-existent challenge
-        oracle_client.resolve_challenge(&oracle1, &market_id, &true);
+        // required_consensus is 2: none, then one oracle, cannot resolve a market
+        assert!(!oracle_client.has_enough_oracles());
+        assert_eq!(
+            oracle_client.try_register_market(&market_id, &resolution_time),
+            Err(Ok(OracleError::InsufficientOracles))
+        );
+        register_funded_oracle(&env, &oracle_client, &oracle1, "Oracle1");
+        assert_eq!(
+            oracle_client.try_register_market(&market_id, &resolution_time),
+            Err(Ok(OracleError::InsufficientOracles))
+        );
+        assert_eq!(oracle_client.get_market_resolution_time(&market_id), None);
+
+        register_funded_oracle(&env, &oracle_client, &oracle2, "Oracle2");
+        assert!(oracle_client.has_enough_oracles());
+        oracle_client.register_market(&market_id, &resolution_time);
+        assert_eq!(
+            oracle_client.get_market_resolution_time(&market_id),
+            Some(resolution_time)
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Challenge already resolved")]
-    fn test_resolve_challenge_twice() {
+    fn test_fresh_oracle_rejected_until_aged() {
         let env = Env::default();
-        env.mock_all_auths();
-
         let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
-        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+        oracle_client.set_min_oracle_age(&1000);
 
         let market_id = create_market_id(&env);
-        let resolution_time = env.ledger().timestamp() + 100;
+        let registered_at = env.ledger().timestamp();
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+        oracle_client.register_market(&market_id, &(registered_at + 100));
 
-        oracle_client.register_market(&market_id, &resolution_time);
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
         env.ledger()
-            .with_mut(|li| li.timestamp = resolution_time + 1);
+            .with_mut(|li| li.timestamp = registered_at + 999);
+        assert!(oracle_client
+            .try_submit_attestation(&oracle1, &market_id, &1, &data_hash)
+            .is_err());
 
-        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        env.ledger()
+            .with_mut(|li| li.timestamp = registered_at + 1000);
         oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
-
-        let challenger = Address::generate(&env);
-        let reason = Symbol::new(&env, "fraud");
-
-        oracle_client.challenge_attestation(&challenger, &oracle1, &market_id, &reason);
-
-        // First resolution
-        oracle_client.resolve_challenge(&oracle1, &market_id, &true);
-
-        // Try to resolve again
-        oracle_client.resolve_challenge(&oracle1, &market_id, &true);
+        assert_eq!(oracle_client.get_attestation_counts(&market_id), (1, 0));
     }
 
     #[test]
-    fn test_oracle_stake_initialized_on_registration() {
+    fn test_submit_attestations_batch_across_markets() {
         let env = Env::default();
-        env.mock_all_auths();
-
-        let (oracle_client, _admin, oracle1, _oracle2) = setup_oracle(&env);
-
-        // Register oracle
-        oracle_client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+        let market_ids = register_batch_markets(&env, &oracle_client);
 
-        // Verify stake was initialized
-        let stake = oracle_client.get_oracle_stake(&oracle1);
-        assert_eq!(stake, CHALLENGE_STAKE_AMOUNT * 10);
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        let mut submissions = Vec::new(&env);
+        submissions.push_back((market_ids.get(0).unwrap(), 1u32, data_hash.clone()));
+        submissions.push_back((market_ids.get(1).unwrap(), 0u32, data_hash.clone()));
+        submissions.push_back((market_ids.get(2).unwrap(), 1u32, data_hash));
+        oracle_client.submit_attestations_batch(&oracle1, &submissions);
+
+        assert_eq!(
+            oracle_client.get_attestation_counts(&market_ids.get(0).unwrap()),
+            (1, 0)
+        );
+        assert_eq!(
+            oracle_client.get_attestation_counts(&market_ids.get(1).unwrap()),
+            (0, 1)
+        );
+        assert_eq!(
+            oracle_client.get_attestation_counts(&market_ids.get(2).unwrap()),
+            (1, 0)
+        );
     }
 
     #[test]
-    fn test_get_challenge_returns_none_when_no_challenge() {
+    fn test_submit_attestations_batch_is_all_or_nothing() {
         let env = Env::default();
-        env.mock_all_auths();
-
-        let (oracle_client, _admin, oracle1, _oracle2) = setup_oracle(&env);
-        let market_id = create_market_id(&env);
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+        let market_ids = register_batch_markets(&env, &oracle_client);
 
-        let challenge = oracle_client.get_challenge(&oracle1, &market_id);
-        assert!(challenge.is_none());
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        let mut submissions = Vec::new(&env);
+        submissions.push_back((market_ids.get(0).unwrap(), 1u32, data_hash.clone()));
+        submissions.push_back((market_ids.get(1).unwrap(), 1u32, data_hash.clone()));
+        submissions.push_back((market_ids.get(2).unwrap(), 7u32, data_hash));
+        assert!(oracle_client
+            .try_submit_attestations_batch(&oracle1, &submissions)
+            .is_err());
+
+        for market_id in market_ids.iter() {
+            assert_eq!(oracle_client.get_attestation_counts(&market_id), (0, 0));
+        }
     }
 
     #[test]
-    fn test_has_active_challenge_returns_false_initially() {
+    fn test_weighted_consensus_expert_outweighs_default_oracles() {
         let env = Env::default();
-        env.mock_all_auths();
+        let (oracle_client, _admin, expert, _oracle2) = setup_oracle(&env);
+
+        register_funded_oracle(&env, &oracle_client, &expert, "Expert");
+        oracle_client.set_oracle_weight(&expert, &5);
+        assert_eq!(oracle_client.get_oracle_weight(&expert), 5);
+
+        let mut oracles = Vec::new(&env);
+        for _ in 0..3 {
+            let oracle = Address::generate(&env);
+            register_funded_oracle(&env, &oracle_client, &oracle, "Oracle");
+            oracles.push_back(oracle);
+        }
 
-        let (oracle_client, _admin, _oracle1, _oracle2) = setup_oracle(&env);
         let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_id, &resolution_time);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
 
-        assert!(!oracle_client.has_active_challenge(&market_id));
+        oracle_client.submit_attestation(&expert, &market_id, &0, &data_hash);
+        for oracle in oracles.iter() {
+            oracle_client.submit_attestation(&oracle, &market_id, &1, &data_hash);
+        }
+
+        // Head count says YES (3 vs 1); weights say NO (5 vs 3)
+        assert_eq!(oracle_client.check_consensus(&market_id), (true, 1));
+        assert_eq!(
+            oracle_client.check_consensus_weighted(&market_id),
+            (true, 0)
+        );
     }
 
     #[test]
-    fn test_multiple_challenges_different_oracles() {
+    fn test_zero_oracle_weight_rejected() {
         let env = Env::default();
-        env.mock_all_auths();
-
         let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
         register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
 
-        let market_id = create_market_id(&env);
-        let resolution_time = env.ledger().timestamp() + 100;
+        assert_eq!(
+            oracle_client.try_set_oracle_weight(&oracle1, &0),
+            Err(Ok(OracleError::InvalidWeight))
+        );
+    }
+
+    /// Register five oracles and have them vote YES/NO in the given split
+    fn cast_votes(env: &Env, oracle_client: &OracleManagerClient, yes: u32, no: u32) -> BytesN<32> {
+        let mut oracles = Vec::new(env);
+        for _ in 0..(yes + no) {
+            let oracle = Address::generate(env);
+            register_funded_oracle(env, oracle_client, &oracle, "Oracle");
+            oracles.push_back(oracle);
+        }
 
+        let market_id = create_market_id(env);
+        let resolution_time = env.ledger().timestamp() + 100;
         oracle_client.register_market(&market_id, &resolution_time);
         env.ledger()
             .with_mut(|li| li.timestamp = resolution_time + 1);
 
-        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        let data_hash = BytesN::from_array(env, &[2u8; 32]);
+        for (i, oracle) in oracles.iter().enumerate() {
+            let vote = if (i as u32) < yes { 1 } else { 0 };
+            oracle_client.submit_attestation(&oracle, &market_id, &vote, &data_hash);
+        }
+        market_id
+    }
 
-        // Both oracles submit attestations
-        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
-        oracle_client.submit_attestation(&oracle2, &market_id, &0, &data_hash);
+    #[test]
+    fn test_simple_majority_fails_supermajority() {
+        let env = Env::default();
+        let (oracle_client, _admin, _oracle1, _oracle2) = setup_oracle(&env);
 
-        let challenger = Address::generate(&env);
-        let reason = Symbol::new(&env, "fraud");
+        // 3 of 5 (60%) is a majority but not 2/3
+        let market_id = cast_votes(&env, &oracle_client, 3, 2);
+        assert_eq!(oracle_client.check_consensus(&market_id), (true, 1));
 
-        // Challenge both oracles
-        oracle_client.challenge_attestation(&challenger, &oracle1, &market_id, &reason);
-        oracle_client.challenge_attestation(&challenger, &oracle2, &market_id, &reason);
+        oracle_client.set_supermajority(&6666);
+        assert_eq!(oracle_client.check_consensus(&market_id), (false, 0));
+    }
 
-        // Verify both challenges exist
-        assert!(oracle_client.get_challenge(&oracle1, &market_id).is_some());
-        assert!(oracle_client.get_challenge(&oracle2, &market_id).is_some());
+    #[test]
+    fn test_clear_supermajority_passes() {
+        let env = Env::default();
+        let (oracle_client, _admin, _oracle1, _oracle2) = setup_oracle(&env);
+        oracle_client.set_supermajority(&6666);
+        assert_eq!(oracle_client.get_supermajority(), 6666);
+
+        // 4 of 5 (80%) clears 2/3
+        let market_id = cast_votes(&env, &oracle_client, 1, 4);
+        assert_eq!(oracle_client.check_consensus(&market_id), (true, 0));
+    }
+
+    #[test]
+    fn test_supermajority_rejects_non_majority_fraction() {
+        let env = Env::default();
+        let (oracle_client, _admin, _oracle1, _oracle2) = setup_oracle(&env);
+        assert_eq!(
+            oracle_client.try_set_supermajority(&5000),
+            Err(Ok(OracleError::InvalidSupermajority))
+        );
     }
 }
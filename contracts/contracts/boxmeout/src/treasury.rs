@@ -1,7 +1,7 @@
 // contract/src/treasury.rs - Treasury Contract Implementation
 // Handles fee collection and reward distribution
 
-use soroban_sdk::{contract, contractevent, contractimpl, token, Address, Env, Symbol};
+use soroban_sdk::{contract, contractevent, contractimpl, token, Address, Env, Symbol, Vec};
 
 #[contractevent]
 pub struct TreasuryInitializedEvent {
@@ -39,6 +39,18 @@ pub struct EmergencyWithdrawalEvent {
     pub timestamp: u64,
 }
 
+#[contractevent]
+pub struct TreasuryHaltedEvent {
+    pub admin: Address,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+pub struct TreasuryResumedEvent {
+    pub admin: Address,
+    pub timestamp: u64,
+}
+
 #[contractevent]
 pub struct LeaderboardDistributedEvent {
     pub total_amount: i128,
@@ -54,6 +66,9 @@ const LEADERBOARD_FEES_KEY: &str = "leaderboard_fees";
 const CREATOR_FEES_KEY: &str = "creator_fees";
 const TOTAL_FEES_KEY: &str = "total_fees";
 const DISTRIBUTION_KEY: &str = "distribution";
+const FEES_BY_SOURCE_PREFIX: &str = "fees_by_source";
+const FEE_SOURCES_KEY: &str = "fee_sources";
+const HALTED_KEY: &str = "halted";
 
 /// Fee distribution ratios (sum to 100)
 #[soroban_sdk::contracttype]
@@ -214,6 +229,24 @@ impl Treasury {
         self::update_pool_balance(&env, CREATOR_FEES_KEY, creator_share);
         self::update_pool_balance(&env, TOTAL_FEES_KEY, amount);
 
+        // Attribute the deposit to its source (the market contract, for market fees)
+        let source_key = (Symbol::new(&env, FEES_BY_SOURCE_PREFIX), source.clone());
+        let attributed: i128 = env.storage().persistent().get(&source_key).unwrap_or(0);
+        if attributed == 0 {
+            let mut sources: Vec<Address> = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, FEE_SOURCES_KEY))
+                .unwrap_or_else(|| Vec::new(&env));
+            sources.push_back(source.clone());
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, FEE_SOURCES_KEY), &sources);
+        }
+        env.storage()
+            .persistent()
+            .set(&source_key, &(attributed + amount));
+
         // Emit FeeCollected(source, amount, timestamp)
         FeeCollectedEvent {
             source,
@@ -255,6 +288,37 @@ impl Treasury {
             .unwrap_or(0)
     }
 
+    /// Get the total fees deposited by one source (e.g. a market contract address)
+    pub fn get_fees_by_market(env: Env, market_address: Address) -> i128 {
+        let source_key = (Symbol::new(&env, FEES_BY_SOURCE_PREFIX), market_address);
+        env.storage().persistent().get(&source_key).unwrap_or(0)
+    }
+
+    /// Get up to `limit` fee sources ranked by total fees deposited, highest first
+    /// Sources with equal totals keep the order they first deposited in
+    pub fn get_top_fee_sources(env: Env, limit: u32) -> Vec<(Address, i128)> {
+        let sources: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, FEE_SOURCES_KEY))
+            .unwrap_or_else(|| Vec::new(&env));
+
+        let mut ranked: Vec<(Address, i128)> = Vec::new(&env);
+        for source in sources.iter() {
+            let total = Self::get_fees_by_market(env.clone(), source.clone());
+            let mut index = ranked.len();
+            while index > 0 && ranked.get(index - 1).unwrap().1 < total {
+                index -= 1;
+            }
+            ranked.insert(index, (source, total));
+        }
+
+        while ranked.len() > limit {
+            ranked.pop_back();
+        }
+        ranked
+    }
+
     /// Distribute rewards to leaderboard winners
     pub fn distribute_leaderboard_rewards(
         env: Env,
@@ -272,6 +336,7 @@ impl Treasury {
         if admin != stored_admin {
             panic!("Unauthorized: only admin can distribute");
         }
+        require_not_halted(&env);
 
         // Validate total shares = 100%
         let mut total_shares = 0u32;
@@ -338,6 +403,7 @@ impl Treasury {
         if admin != stored_admin {
             panic!("Unauthorized: only admin can distribute rewards");
         }
+        require_not_halted(&env);
 
         let creator_fees: i128 = env
             .storage()
@@ -391,6 +457,46 @@ impl Treasury {
         token_client.balance(&env.current_contract_address())
     }
 
+    /// Admin: freeze the treasury after a suspected drain
+    ///
+    /// While halted, withdrawals and reward distributions panic; fee deposits keep working.
+    pub fn emergency_halt(env: Env, admin: Address) {
+        require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, HALTED_KEY), &true);
+
+        TreasuryHaltedEvent {
+            admin,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+    }
+
+    /// Admin: lift an emergency halt
+    pub fn resume(env: Env, admin: Address) {
+        require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .remove(&Symbol::new(&env, HALTED_KEY));
+
+        TreasuryResumedEvent {
+            admin,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+    }
+
+    /// Whether the treasury is under an emergency halt
+    pub fn is_halted(env: Env) -> bool {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, HALTED_KEY))
+            .unwrap_or(false)
+    }
+
     /// Emergency withdrawal of funds
     pub fn emergency_withdraw(env: Env, admin: Address, recipient: Address, amount: i128) {
         admin.require_auth();
@@ -402,6 +508,7 @@ impl Treasury {
         if admin != stored_admin {
             panic!("Unauthorized");
         }
+        require_not_halted(&env);
 
         let usdc_token: Address = env
             .storage()
@@ -421,6 +528,24 @@ impl Treasury {
     }
 }
 
+fn require_admin(env: &Env, admin: &Address) {
+    admin.require_auth();
+    let stored_admin: Address = env
+        .storage()
+        .persistent()
+        .get(&Symbol::new(env, ADMIN_KEY))
+        .expect("Not initialized");
+    if *admin != stored_admin {
+        panic!("Unauthorized");
+    }
+}
+
+fn require_not_halted(env: &Env) {
+    if Treasury::is_halted(env.clone()) {
+        panic!("Treasury halted");
+    }
+}
+
 fn update_pool_balance(env: &Env, key: &str, delta: i128) {
     let current: i128 = env
         .storage()
@@ -479,6 +604,86 @@ mod tests {
         assert_eq!(treasury.get_total_fees(), 0);
     }
 
+    #[test]
+    fn test_emergency_halt_blocks_outflows_until_resume() {
+        let env = Env::default();
+        let (treasury, usdc_client, admin, _, _factory) = setup_treasury(&env);
+        let source = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        usdc_client.mint(&source, &2_000);
+        treasury.deposit_fees(&source, &1_000);
+
+        treasury.emergency_halt(&admin);
+        assert!(treasury.is_halted());
+
+        assert!(treasury
+            .try_emergency_withdraw(&admin, &recipient, &100)
+            .is_err());
+        let mut shares = soroban_sdk::Vec::new(&env);
+        shares.push_back((recipient.clone(), 100u32));
+        assert!(treasury
+            .try_distribute_leaderboard_rewards(&admin, &shares)
+            .is_err());
+        let mut rewards = soroban_sdk::Vec::new(&env);
+        rewards.push_back((recipient.clone(), 10i128));
+        assert!(treasury
+            .try_distribute_creator_rewards(&admin, &rewards)
+            .is_err());
+
+        // Deposits still land while halted
+        treasury.deposit_fees(&source, &1_000);
+        assert_eq!(treasury.get_treasury_balance(), 2_000);
+        assert_eq!(usdc_client.balance(&recipient), 0);
+
+        treasury.resume(&admin);
+        assert!(!treasury.is_halted());
+        treasury.emergency_withdraw(&admin, &recipient, &100);
+        assert_eq!(usdc_client.balance(&recipient), 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_emergency_halt_only_admin() {
+        let env = Env::default();
+        let (treasury, _, _, _, _) = setup_treasury(&env);
+        treasury.emergency_halt(&Address::generate(&env));
+    }
+
+    #[test]
+    fn test_fees_attributed_and_ranked_by_market() {
+        let env = Env::default();
+        let (treasury, usdc_client, _admin, _, _factory) = setup_treasury(&env);
+
+        let small = Address::generate(&env);
+        let large = Address::generate(&env);
+        let medium = Address::generate(&env);
+        for market in [&small, &large, &medium] {
+            usdc_client.mint(market, &10_000);
+        }
+
+        treasury.deposit_fees(&small, &100);
+        treasury.deposit_fees(&large, &2_000);
+        treasury.deposit_fees(&medium, &500);
+        treasury.deposit_fees(&medium, &400);
+
+        assert_eq!(treasury.get_fees_by_market(&small), 100);
+        assert_eq!(treasury.get_fees_by_market(&medium), 900);
+        assert_eq!(treasury.get_fees_by_market(&Address::generate(&env)), 0);
+
+        assert_eq!(
+            treasury.get_top_fee_sources(&10),
+            soroban_sdk::Vec::from_array(
+                &env,
+                [(large.clone(), 2_000), (medium.clone(), 900), (small, 100)]
+            )
+        );
+        assert_eq!(
+            treasury.get_top_fee_sources(&2),
+            soroban_sdk::Vec::from_array(&env, [(large, 2_000), (medium, 900)])
+        );
+        assert_eq!(treasury.get_top_fee_sources(&0).len(), 0);
+    }
+
     #[test]
     #[should_panic(expected = "Ratios must sum to 100")]
     fn test_set_fee_distribution_invalid_sum() {
@@ -253,10 +253,15 @@ fn test_update_treasury_address() {
 }
 */
 
-use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, Symbol, Vec,
+};
 
 // Import the Factory contract
 use boxmeout::factory::{MarketFactory, MarketFactoryClient};
+use boxmeout::market::{PredictionMarket, PredictionMarketClient};
+use boxmeout::treasury::{Treasury, TreasuryClient};
 // Helper function to create test environment
 fn create_test_env() -> Env {
     Env::default()
@@ -268,7 +273,6 @@ fn register_factory(env: &Env) -> Address {
 }
 
 // Helper to create a mock USDC token
-#[allow(dead_code)]
 fn create_mock_token(env: &Env, admin: &Address) -> Address {
     let token_address = env.register_stellar_asset_contract_v2(admin.clone());
     token_address.address()
@@ -397,3 +401,118 @@ fn test_update_treasury_address() {
     // Test admin can update treasury address
     // Test non-admin cannot update
 }
+
+// Helper to initialize a factory wired to a real treasury; returns (client, admin, usdc)
+fn setup_factory(env: &Env) -> (MarketFactoryClient<'_>, Address, Address) {
+    env.mock_all_auths();
+
+    let admin = Address::generate(env);
+    let usdc = create_mock_token(env, &Address::generate(env));
+
+    let factory_id = register_factory(env);
+    let treasury_id = env.register(Treasury, ());
+    let client = MarketFactoryClient::new(env, &factory_id);
+    client.initialize(&admin, &usdc, &treasury_id);
+    TreasuryClient::new(env, &treasury_id).initialize(&admin, &usdc, &factory_id);
+
+    (client, admin, usdc)
+}
+
+// Helper to create a funded market that closes halfway to its resolution `duration` from now
+fn create_market_resolving_in(
+    env: &Env,
+    client: &MarketFactoryClient,
+    usdc: &Address,
+    duration: u64,
+) -> BytesN<32> {
+    let creator = Address::generate(env);
+    token::StellarAssetClient::new(env, usdc).mint(&creator, &100_000_000);
+
+    let now = env.ledger().timestamp();
+    client.create_market(
+        &creator,
+        &Symbol::new(env, "Mayweather"),
+        &Symbol::new(env, "MayweatherWins"),
+        &Symbol::new(env, "Boxing"),
+        &(now + duration / 2),
+        &(now + duration),
+    )
+}
+
+#[test]
+fn test_market_within_max_duration_accepted() {
+    let env = create_test_env();
+    let (client, admin, usdc) = setup_factory(&env);
+
+    client.set_max_market_duration(&admin, &(30 * 86400));
+    assert_eq!(client.get_max_market_duration(), 30 * 86400);
+
+    create_market_resolving_in(&env, &client, &usdc, 30 * 86400);
+    assert_eq!(client.get_market_count(), 1);
+}
+
+#[test]
+#[should_panic(expected = "market duration exceeds maximum")]
+fn test_market_over_max_duration_rejected() {
+    let env = create_test_env();
+    let (client, admin, usdc) = setup_factory(&env);
+
+    client.set_max_market_duration(&admin, &(30 * 86400));
+    create_market_resolving_in(&env, &client, &usdc, 30 * 86400 + 1);
+}
+
+#[test]
+fn test_created_markets_paginate_in_creation_order() {
+    let env = create_test_env();
+    let (client, _admin, usdc) = setup_factory(&env);
+
+    let mut all = Vec::new(&env);
+    for _ in 0..5 {
+        all.push_back(create_market_resolving_in(&env, &client, &usdc, 86400));
+    }
+    assert_eq!(client.get_market_count(), 5);
+
+    let mut paged = Vec::new(&env);
+    for offset in [0u32, 2, 4] {
+        let page = client.get_markets_page(&offset, &2);
+        assert_eq!(page.len(), (5 - offset).min(2));
+        paged.append(&page);
+    }
+    assert_eq!(paged, all);
+
+    assert_eq!(client.get_markets_page(&5, &2).len(), 0);
+    assert_eq!(client.get_markets_page(&1, &0).len(), 0);
+    assert_eq!(client.get_markets_page(&3, &u32::MAX).len(), 2);
+}
+
+#[test]
+fn test_factory_keeper_closes_market() {
+    let env = create_test_env();
+    let (client, admin, usdc) = setup_factory(&env);
+
+    let market = PredictionMarketClient::new(&env, &env.register(PredictionMarket, ()));
+    let market_id = BytesN::from_array(&env, &[1u8; 32]);
+    market.initialize(
+        &market_id,
+        &Address::generate(&env),
+        &client.address,
+        &usdc,
+        &Address::generate(&env),
+        &2000,
+        &3000,
+        &Vec::new(&env),
+        &0,
+        &Vec::new(&env),
+    );
+
+    let keeper = Address::generate(&env);
+    client.set_market_keeper(&admin, &market.address, &keeper, &true);
+    assert!(market.is_keeper(&keeper));
+
+    env.ledger().with_mut(|li| li.timestamp = 2000);
+    market.close_market(&keeper, &market_id);
+    assert_eq!(market.get_market_state_value(), Some(1));
+
+    client.set_market_keeper(&admin, &market.address, &keeper, &false);
+    assert!(!market.is_keeper(&keeper));
+}
@@ -12,11 +12,17 @@ use boxmeout::{AMMClient, MarketFactory, MarketFactoryClient, OracleManager, Ora
 // ...rest of the file...
 */
 
-use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token,
+    xdr::ToXdr,
+    Address, Bytes, BytesN, Env, Symbol, Vec,
+};
 
 use boxmeout::{
     amm::{AMMClient, AMM},
     factory::{MarketFactory, MarketFactoryClient},
+    market::{PredictionMarket, PredictionMarketClient},
     oracle::{OracleManager, OracleManagerClient},
     treasury::{Treasury, TreasuryClient},
 };
@@ -48,7 +54,7 @@ fn test_complete_prediction_flow() {
     // Step 2: Initialize all contracts
     factory_client.initialize(&admin, &usdc_token, &treasury_id);
     treasury_client.initialize(&admin, &usdc_token, &factory_id);
-    oracle_client.initialize(&admin, &2u32);
+    oracle_client.initialize(&admin, &2u32, &usdc_token, &0, &5000, &20, &5);
     amm_client.initialize(&admin, &factory_id, &usdc_token, &100_000_000_000u128);
 
     // Step 3: Register oracles
@@ -111,7 +117,7 @@ fn test_complete_prediction_flow() {
     // oracle_client.submit_attestation(&oracle3, &market_id, &0u32); // NO
 
     // Step 12: Resolve market (2 of 3 oracles voted YES)
-    // market_client.resolve_market(&market_id);
+    // market_client.resolve_market(&oracle_id, &market_id);
 
     // Step 13: Winners claim rewards
     // market_client.claim_winnings(&user1, &market_id);
@@ -163,7 +169,7 @@ fn test_oracle_consensus_flow() {
     let oracle_client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    oracle_client.initialize(&admin, &2u32);
+    oracle_client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     // Register 3 oracles
     let oracle1 = Address::generate(&env);
@@ -214,6 +220,485 @@ fn test_multiple_markets() {
     // Verify independent operation
 }
 
+/// Integration test: Factory aggregates a user's ROI across resolved markets
+#[test]
+fn test_user_roi_across_win_and_loss() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let usdc = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let usdc_admin = token::StellarAssetClient::new(&env, &usdc);
+
+    let factory_id = env.register(MarketFactory, ());
+    let factory_client = MarketFactoryClient::new(&env, &factory_id);
+    factory_client.initialize(&admin, &usdc, &Address::generate(&env));
+
+    let user = Address::generate(&env);
+    let deploy_market = |seed: u8| {
+        let market_id = BytesN::from_array(&env, &[seed; 32]);
+        let market_client = PredictionMarketClient::new(&env, &env.register(PredictionMarket, ()));
+        market_client.initialize(
+            &market_id,
+            &Address::generate(&env),
+            &factory_id,
+            &usdc,
+            &Address::generate(&env),
+            &2000,
+            &3000,
+            &Vec::new(&env),
+            &0,
+            &Vec::new(&env),
+        );
+        (market_id, market_client)
+    };
+
+    // Market A: user staked 100 on YES, YES won against a 100 NO pool
+    let (market_a, client_a) = deploy_market(1);
+    client_a.test_set_prediction(&user, &1, &100);
+    client_a.test_setup_resolution(&market_a, &1, &100, &100);
+    usdc_admin.mint(&client_a.address, &200);
+    assert_eq!(client_a.claim_winnings(&user, &market_a), 180);
+
+    // Market B: user staked 50 on NO, YES won
+    let (market_b, client_b) = deploy_market(2);
+    client_b.test_set_prediction(&user, &0, &50);
+    client_b.test_setup_resolution(&market_b, &1, &200, &50);
+
+    let mut markets = Vec::new(&env);
+    markets.push_back(client_a.address.clone());
+    markets.push_back(client_b.address.clone());
+
+    let (total_staked, total_net_returned) = factory_client.get_user_roi(&user, &markets);
+    assert_eq!(total_staked, 150);
+    assert_eq!(total_net_returned, 180);
+}
+
+/// Helper: A closed market with 600 staked on YES (two bettors), 400 on NO and a 600 creator bond
+#[allow(clippy::type_complexity)]
+fn setup_seedable_market(
+    env: &Env,
+) -> (
+    BytesN<32>,
+    PredictionMarketClient<'_>,
+    AMMClient<'_>,
+    Address,
+    Address,
+    Vec<Address>,
+) {
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+
+    let usdc = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    let usdc_client = token::StellarAssetClient::new(env, &usdc);
+
+    let amm_client = AMMClient::new(env, &env.register(AMM, ()));
+    amm_client.initialize(
+        &Address::generate(env),
+        &Address::generate(env),
+        &usdc,
+        &100_000_000_000u128,
+    );
+
+    let market_id = BytesN::from_array(env, &[3u8; 32]);
+    let creator = Address::generate(env);
+    let oracle = Address::generate(env);
+    usdc_client.mint(&creator, &600);
+    let market_client = PredictionMarketClient::new(env, &env.register(PredictionMarket, ()));
+    market_client.initialize(
+        &market_id,
+        &creator,
+        &Address::generate(env),
+        &usdc,
+        &oracle,
+        &2000,
+        &3000,
+        &Vec::new(env),
+        &600,
+        &Vec::new(env),
+    );
+
+    // 600 staked on YES across two bettors and 400 on NO, escrowed in the market
+    let mut yes_users = Vec::new(env);
+    for _ in 0..2 {
+        let yes_user = Address::generate(env);
+        market_client.test_add_participant(&yes_user);
+        market_client.test_set_prediction(&yes_user, &1, &300);
+        yes_users.push_back(yes_user);
+    }
+    let no_user = Address::generate(env);
+    market_client.test_add_participant(&no_user);
+    market_client.test_set_prediction(&no_user, &0, &400);
+    market_client.recompute_pools(&creator, &market_id);
+    usdc_client.mint(&market_client.address, &1000);
+
+    env.ledger().with_mut(|li| li.timestamp = 2000);
+    market_client.close_market(&oracle, &market_id);
+
+    (
+        market_id,
+        market_client,
+        amm_client,
+        usdc,
+        creator,
+        yes_users,
+    )
+}
+
+/// Integration test: A closed market's creator seeds a fresh AMM pool sized from its prediction pools
+#[test]
+fn test_seed_amm_from_closed_market_pools() {
+    let env = Env::default();
+    let (market_id, market_client, amm_client, usdc, creator, _) = setup_seedable_market(&env);
+
+    token::StellarAssetClient::new(&env, &usdc).mint(&creator, &500);
+    market_client.configure_amm_seeding(&creator, &amm_client.address, &5000);
+    assert_eq!(market_client.seed_amm_from_pools(&creator, &market_id), 500);
+
+    let (yes_reserve, no_reserve, total_liquidity, yes_odds, no_odds) =
+        amm_client.get_pool_state(&market_id);
+    assert_eq!((yes_reserve, no_reserve, total_liquidity), (250, 250, 500));
+    assert_eq!((yes_odds, no_odds), (5000, 5000));
+    assert!(amm_client.get_lp_balance(&market_id, &creator) > 0);
+
+    // The creator funded the seed; bettor principal and the bond stay escrowed
+    let usdc_client = token::Client::new(&env, &usdc);
+    assert_eq!(usdc_client.balance(&amm_client.address), 500);
+    assert_eq!(usdc_client.balance(&creator), 0);
+    assert_eq!(usdc_client.balance(&market_client.address), 1600);
+    assert_eq!(market_client.get_creator_bond(), 600);
+}
+
+/// Integration test: Seeding the AMM leaves enough escrow for every winner to claim
+#[test]
+fn test_seed_amm_then_resolve_pays_every_winner() {
+    let env = Env::default();
+    let (market_id, market_client, amm_client, usdc, creator, yes_users) =
+        setup_seedable_market(&env);
+
+    token::StellarAssetClient::new(&env, &usdc).mint(&creator, &1000);
+    market_client.configure_amm_seeding(&creator, &amm_client.address, &10000);
+    assert_eq!(
+        market_client.seed_amm_from_pools(&creator, &market_id),
+        1000
+    );
+    assert_eq!(market_client.get_creator_bond(), 600);
+
+    market_client.test_setup_resolution(&market_id, &1, &600, &400);
+
+    // Each 300 YES stake takes half of the 1000 pool, less the 10% fee
+    let usdc_client = token::Client::new(&env, &usdc);
+    for yes_user in yes_users.iter() {
+        assert_eq!(market_client.claim_winnings(&yes_user, &market_id), 450);
+        assert_eq!(usdc_client.balance(&yes_user), 450);
+    }
+    // The withheld fees and the untouched 600 bond remain escrowed
+    assert_eq!(usdc_client.balance(&market_client.address), 700);
+}
+
+/// Integration test: Closing a market snaps the linked AMM pool to the pari-mutuel odds
+#[test]
+fn test_close_market_settles_amm_to_pool_odds() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+
+    let admin = Address::generate(&env);
+    let usdc = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc);
+
+    let amm_id = env.register(AMM, ());
+    let amm_client = AMMClient::new(&env, &amm_id);
+    amm_client.initialize(
+        &admin,
+        &Address::generate(&env),
+        &usdc,
+        &100_000_000_000u128,
+    );
+
+    let market_id = BytesN::from_array(&env, &[5u8; 32]);
+    let creator = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let market_client = PredictionMarketClient::new(&env, &env.register(PredictionMarket, ()));
+    market_client.initialize(
+        &market_id,
+        &creator,
+        &Address::generate(&env),
+        &usdc,
+        &oracle,
+        &2000,
+        &3000,
+        &Vec::new(&env),
+        &0,
+        &Vec::new(&env),
+    );
+
+    // The AMM opens at 50/50 while the prediction pools lean 60/40 YES
+    let lp = Address::generate(&env);
+    usdc_client.mint(&lp, &1_000_000);
+    amm_client.create_pool(&lp, &market_id, &1_000_000u128);
+    amm_client.set_pool_market(&admin, &market_id, &market_client.address);
+    let k_before = amm_client.get_pool_k(&market_id);
+
+    let (yes_user, no_user) = (Address::generate(&env), Address::generate(&env));
+    market_client.test_add_participant(&yes_user);
+    market_client.test_set_prediction(&yes_user, &1, &600);
+    market_client.test_add_participant(&no_user);
+    market_client.test_set_prediction(&no_user, &0, &400);
+    market_client.recompute_pools(&creator, &market_id);
+    assert_eq!(amm_client.get_odds(&market_id), (5000, 5000));
+
+    market_client.configure_amm_close_settlement(&creator, &amm_id, &true);
+    env.ledger().with_mut(|li| li.timestamp = 2000);
+    market_client.close_market(&oracle, &market_id);
+
+    let pool_odds = market_client.project_final_odds(&market_id, &0);
+    assert_eq!(pool_odds, (6000, 4000));
+    assert_eq!(amm_client.get_odds(&market_id), pool_odds);
+    // Rebalancing never grows the invariant or the USDC the reserves claim
+    assert!(amm_client.get_pool_k(&market_id) <= k_before);
+    let (yes_reserve, no_reserve, _, _, _) = amm_client.get_pool_state(&market_id);
+    assert_eq!(yes_reserve + no_reserve, 1_000_000);
+}
+
+/// Integration test: Cancelling a market mid-attestation releases the oracle's challenge stakes
+#[test]
+fn test_cancel_market_releases_oracle_challenges() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+
+    let admin = Address::generate(&env);
+    let stake_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let oracle_id = env.register(OracleManager, ());
+    let oracle_client = OracleManagerClient::new(&env, &oracle_id);
+    oracle_client.initialize(&admin, &1, &stake_token, &0, &5000, &20, &5);
+
+    let market_id = BytesN::from_array(&env, &[4u8; 32]);
+    let creator = Address::generate(&env);
+    let market_contract = env.register(PredictionMarket, ());
+    let market_client = PredictionMarketClient::new(&env, &market_contract);
+    market_client.initialize(
+        &market_id,
+        &creator,
+        &Address::generate(&env),
+        &stake_token,
+        &oracle_id,
+        &2000,
+        &3000,
+        &Vec::new(&env),
+        &0,
+        &Vec::new(&env),
+    );
+
+    let oracle = Address::generate(&env);
+    oracle_client.register_oracle(&oracle, &Symbol::new(&env, "oracle"));
+
+    oracle_client.register_market(&market_id, &3000);
+    oracle_client.bind_market_contract(&market_id, &market_contract);
+
+    env.ledger().with_mut(|li| li.timestamp = 3000);
+    oracle_client.submit_attestation(
+        &oracle,
+        &market_id,
+        &1,
+        &BytesN::from_array(&env, &[0u8; 32]),
+    );
+
+    let challenger = Address::generate(&env);
+    oracle_client.challenge_attestation(
+        &challenger,
+        &oracle,
+        &market_id,
+        &Symbol::new(&env, "wrong"),
+    );
+    assert!(oracle_client.has_active_challenge(&market_id));
+
+    market_client.cancel_market(&creator, &market_id);
+
+    assert!(oracle_client.is_market_abandoned(&market_id));
+    assert!(!oracle_client.has_active_challenge(&market_id));
+    let challenge = oracle_client.get_challenge(&oracle, &market_id).unwrap();
+    assert!(challenge.resolved);
+    assert_eq!(
+        oracle_client.get_released_challenge_stake(&challenger),
+        challenge.stake
+    );
+    // The oracle's registration stake is untouched by the release
+    assert_eq!(oracle_client.get_oracle_stake(&oracle), 0);
+    assert_eq!(oracle_client.get_oracle_accuracy(&oracle), 100);
+}
+
+fn setup_tracked_markets(
+    env: &Env,
+) -> (
+    MarketFactoryClient<'_>,
+    Address,
+    [PredictionMarketClient<'_>; 2],
+    token::StellarAssetClient<'_>,
+) {
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+
+    let admin = Address::generate(env);
+    let usdc = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    let factory_id = env.register(MarketFactory, ());
+    let treasury_id = env.register(Treasury, ());
+    let factory_client = MarketFactoryClient::new(env, &factory_id);
+    factory_client.initialize(&admin, &usdc, &treasury_id);
+    TreasuryClient::new(env, &treasury_id).initialize(&admin, &usdc, &factory_id);
+
+    let markets = [10u8, 11u8].map(|seed| {
+        let market_client = PredictionMarketClient::new(env, &env.register(PredictionMarket, ()));
+        market_client.initialize(
+            &BytesN::from_array(env, &[seed; 32]),
+            &Address::generate(env),
+            &factory_id,
+            &usdc,
+            &Address::generate(env),
+            &2000,
+            &3000,
+            &Vec::new(env),
+            &0,
+            &Vec::new(env),
+        );
+        factory_client.track_market(&admin, &market_client.address);
+        market_client
+    });
+
+    (
+        factory_client,
+        admin,
+        markets,
+        token::StellarAssetClient::new(env, &usdc),
+    )
+}
+
+/// Integration test: A user at the per-user active market cap cannot commit to another market
+#[test]
+fn test_user_blocked_at_active_market_cap() {
+    let env = Env::default();
+    let (factory_client, admin, markets, usdc_client) = setup_tracked_markets(&env);
+    factory_client.set_max_active_per_user(&admin, &1);
+
+    let user = Address::generate(&env);
+    usdc_client.mint(&user, &1000);
+
+    markets[0].commit_prediction(&user, &BytesN::from_array(&env, &[1u8; 32]), &100);
+    assert_eq!(factory_client.get_user_active_count(&user), 1);
+
+    let blocked =
+        markets[1].try_commit_prediction(&user, &BytesN::from_array(&env, &[2u8; 32]), &100);
+    assert!(blocked.is_err());
+    assert_eq!(factory_client.get_user_active_count(&user), 1);
+    assert_eq!(
+        token::Client::new(&env, &usdc_client.address).balance(&user),
+        900
+    );
+}
+
+/// Integration test: Factory aggregates escrow and liabilities across tracked markets
+#[test]
+fn test_protocol_health_across_tracked_markets() {
+    let env = Env::default();
+    let (factory_client, _admin, markets, usdc_client) = setup_tracked_markets(&env);
+    assert_eq!(factory_client.get_protocol_health(), (0, 0, 10000));
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    usdc_client.mint(&alice, &1000);
+    usdc_client.mint(&bob, &1000);
+
+    markets[0].commit_prediction(&alice, &BytesN::from_array(&env, &[1u8; 32]), &300);
+    markets[0].commit_prediction(&bob, &BytesN::from_array(&env, &[2u8; 32]), &200);
+    markets[1].commit_prediction(&alice, &BytesN::from_array(&env, &[3u8; 32]), &150);
+
+    assert_eq!(markets[0].get_liabilities(), 500);
+    assert_eq!(markets[1].get_liabilities(), 150);
+    assert_eq!(factory_client.get_protocol_health(), (650, 650, 10000));
+
+    // Extra escrow beyond what is owed shows as over-collateralization
+    usdc_client.mint(&markets[1].address, &650);
+    assert_eq!(factory_client.get_protocol_health(), (1300, 650, 20000));
+}
+
+/// Integration test: A refund closes the position and frees a slot under the cap
+#[test]
+fn test_refund_frees_active_market_slot() {
+    let env = Env::default();
+    let (factory_client, admin, markets, usdc_client) = setup_tracked_markets(&env);
+    factory_client.set_max_active_per_user(&admin, &1);
+
+    let user = Address::generate(&env);
+    usdc_client.mint(&user, &1000);
+
+    let first = &markets[0];
+    let first_id = BytesN::from_array(&env, &[10u8; 32]);
+    first.commit_prediction(&user, &BytesN::from_array(&env, &[1u8; 32]), &100);
+
+    let creator: Address = env.as_contract(&first.address, || {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, "creator"))
+            .unwrap()
+    });
+    first.cancel_market(&creator, &first_id);
+    first.claim_refund(&user, &first_id);
+    assert_eq!(factory_client.get_user_active_count(&user), 0);
+
+    markets[1].commit_prediction(&user, &BytesN::from_array(&env, &[2u8; 32]), &100);
+    assert_eq!(factory_client.get_user_active_count(&user), 1);
+}
+
+/// Integration test: Losers close out their positions after resolution and free their slot
+#[test]
+fn test_losing_positions_free_active_market_slot() {
+    let env = Env::default();
+    let (factory_client, admin, markets, usdc_client) = setup_tracked_markets(&env);
+    factory_client.set_max_active_per_user(&admin, &1);
+
+    let market = &markets[0];
+    let market_id = BytesN::from_array(&env, &[10u8; 32]);
+    let loser = Address::generate(&env);
+    let silent = Address::generate(&env);
+    usdc_client.mint(&loser, &1000);
+    usdc_client.mint(&silent, &1000);
+
+    // The loser reveals NO; the silent committer never reveals
+    let salt = BytesN::from_array(&env, &[7u8; 32]);
+    let mut preimage = Bytes::new(&env);
+    preimage.extend_from_array(&market_id.to_array());
+    preimage.append(&loser.clone().to_xdr(&env));
+    preimage.extend_from_array(&0u32.to_be_bytes());
+    preimage.extend_from_array(&salt.to_array());
+    let commit_hash = BytesN::from_array(&env, &env.crypto().sha256(&preimage).to_array());
+    market.commit_prediction(&loser, &commit_hash, &100);
+    market.commit_prediction(&silent, &BytesN::from_array(&env, &[2u8; 32]), &100);
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    market.reveal_prediction(&loser, &market_id, &0, &100, &salt);
+    assert_eq!(factory_client.get_user_active_count(&loser), 1);
+    assert_eq!(factory_client.get_user_active_count(&silent), 1);
+
+    market.test_setup_resolution(&market_id, &1, &0, &100);
+    market.close_losing_position(&loser);
+    market.close_losing_position(&silent);
+    assert_eq!(factory_client.get_user_active_count(&loser), 0);
+    assert_eq!(factory_client.get_user_active_count(&silent), 0);
+
+    markets[1].commit_prediction(&loser, &BytesN::from_array(&env, &[3u8; 32]), &100);
+    assert_eq!(factory_client.get_user_active_count(&loser), 1);
+}
+
 /// Integration test: Edge cases and error handling
 #[test]
 fn test_error_scenarios() {
@@ -13,12 +13,12 @@ use boxmeout::{OracleManager, OracleManagerClient};
 */
 
 use soroban_sdk::{
-    testutils::{Address as _, Ledger},
-    Address, BytesN, Env, Symbol,
+    testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke},
+    token, Address, BytesN, Env, IntoVal, Symbol, Val, Vec,
 };
 
 use boxmeout::market::PredictionMarket;
-use boxmeout::oracle::{OracleManager, OracleManagerClient};
+use boxmeout::oracle::{OracleError, OracleManager, OracleManagerClient};
 
 fn create_test_env() -> Env {
     Env::default()
@@ -38,7 +38,15 @@ fn test_oracle_initialize() {
     let required_consensus = 2u32; // 2 of 3 oracles
 
     env.mock_all_auths();
-    client.initialize(&admin, &required_consensus);
+    client.initialize(
+        &admin,
+        &required_consensus,
+        &Address::generate(&env),
+        &0,
+        &5000,
+        &20,
+        &5,
+    );
 
     // TODO: Add getters to verify
     // Verify required_consensus stored correctly
@@ -54,7 +62,15 @@ fn test_register_oracle() {
 
     let admin = Address::generate(&env);
     let required_consensus = 2u32;
-    client.initialize(&admin, &required_consensus);
+    client.initialize(
+        &admin,
+        &required_consensus,
+        &Address::generate(&env),
+        &0,
+        &5000,
+        &20,
+        &5,
+    );
 
     // Register oracle
     let oracle1 = Address::generate(&env);
@@ -75,7 +91,7 @@ fn test_register_multiple_oracles() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     // Register 3 oracles
     let oracle1 = Address::generate(&env);
@@ -90,7 +106,6 @@ fn test_register_multiple_oracles() {
 }
 
 #[test]
-#[should_panic(expected = "Maximum oracle limit reached")]
 fn test_register_oracle_exceeds_limit() {
     let env = create_test_env();
     env.mock_all_auths();
@@ -99,18 +114,22 @@ fn test_register_oracle_exceeds_limit() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
-    // Register 11 oracles (limit is 10)
-    for _ in 0..11 {
+    // Register 10 oracles (the limit), then attempt an 11th
+    for _ in 0..10 {
         let oracle = Address::generate(&env);
         let name = Symbol::new(&env, "Oracle");
         client.register_oracle(&oracle, &name);
     }
+
+    assert_eq!(
+        client.try_register_oracle(&Address::generate(&env), &Symbol::new(&env, "Oracle")),
+        Err(Ok(OracleError::OracleLimitReached))
+    );
 }
 
 #[test]
-#[should_panic(expected = "Oracle already registered")]
 fn test_register_duplicate_oracle() {
     let env = create_test_env();
     env.mock_all_auths();
@@ -119,7 +138,7 @@ fn test_register_duplicate_oracle() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     let oracle1 = Address::generate(&env);
     let name = Symbol::new(&env, "Oracle1");
@@ -128,7 +147,10 @@ fn test_register_duplicate_oracle() {
     client.register_oracle(&oracle1, &name);
 
     // Try to register same oracle again
-    client.register_oracle(&oracle1, &name);
+    assert_eq!(
+        client.try_register_oracle(&oracle1, &name),
+        Err(Ok(OracleError::OracleAlreadyRegistered))
+    );
 }
 
 #[test]
@@ -140,7 +162,7 @@ fn test_submit_attestation() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     let oracle1 = Address::generate(&env);
     client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
@@ -148,6 +170,9 @@ fn test_submit_attestation() {
     let market_id = BytesN::from_array(&env, &[1u8; 32]);
     let resolution_time = 1000u64;
 
+    // Markets can only be registered once required_consensus oracles exist
+    client.register_oracle(&Address::generate(&env), &Symbol::new(&env, "Backup1"));
+
     // Register market with resolution time
     client.register_market(&market_id, &resolution_time);
 
@@ -174,7 +199,7 @@ fn test_check_consensus_reached() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     let oracle1 = Address::generate(&env);
     let oracle2 = Address::generate(&env);
@@ -212,7 +237,7 @@ fn test_check_consensus_not_reached() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &3u32); // Need 3 oracles
+    client.initialize(&admin, &3u32, &Address::generate(&env), &0, &5000, &20, &5); // Need 3 oracles
 
     let oracle1 = Address::generate(&env);
     let oracle2 = Address::generate(&env);
@@ -222,6 +247,9 @@ fn test_check_consensus_not_reached() {
     let market_id = BytesN::from_array(&env, &[1u8; 32]);
     let resolution_time = 1000u64;
 
+    // Markets can only be registered once required_consensus oracles exist
+    client.register_oracle(&Address::generate(&env), &Symbol::new(&env, "Backup1"));
+
     // Register market and set timestamp past resolution time
     client.register_market(&market_id, &resolution_time);
     env.ledger().set_timestamp(1001);
@@ -255,7 +283,7 @@ fn test_check_consensus_tie_handling() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32); // threshold 2
+    client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5); // threshold 2
 
     let oracle1 = Address::generate(&env);
     let oracle2 = Address::generate(&env);
@@ -299,7 +327,7 @@ fn test_deregister_oracle_success() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     // Register an oracle
     let oracle1 = Address::generate(&env);
@@ -325,7 +353,6 @@ fn test_deregister_oracle_success() {
 
 /// Test deregistering an oracle that is not registered
 #[test]
-#[should_panic(expected = "Oracle not registered or already inactive")]
 fn test_deregister_oracle_not_registered() {
     let env = create_test_env();
     env.mock_all_auths();
@@ -334,16 +361,18 @@ fn test_deregister_oracle_not_registered() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     // Try to deregister an oracle that was never registered
     let oracle1 = Address::generate(&env);
-    client.deregister_oracle(&oracle1);
+    assert_eq!(
+        client.try_deregister_oracle(&oracle1),
+        Err(Ok(OracleError::OracleNotRegistered))
+    );
 }
 
 /// Test deregistering an already deregistered oracle
 #[test]
-#[should_panic(expected = "Oracle not registered or already inactive")]
 fn test_deregister_oracle_already_inactive() {
     let env = create_test_env();
     env.mock_all_auths();
@@ -352,7 +381,7 @@ fn test_deregister_oracle_already_inactive() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     let oracle1 = Address::generate(&env);
     client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
@@ -361,7 +390,61 @@ fn test_deregister_oracle_already_inactive() {
     client.deregister_oracle(&oracle1);
 
     // Try to deregister again - should fail
-    client.deregister_oracle(&oracle1);
+    assert_eq!(
+        client.try_deregister_oracle(&oracle1),
+        Err(Ok(OracleError::OracleNotRegistered))
+    );
+}
+
+/// Test that deregistration refunds the registration stake left after a slash
+#[test]
+fn test_deregister_oracle_refunds_remaining_stake() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let client = OracleManagerClient::new(&env, &register_oracle(&env));
+    let stake_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    // 1000 stake, half slashed on a valid challenge
+    client.initialize(
+        &Address::generate(&env),
+        &1u32,
+        &stake_token,
+        &1000,
+        &5000,
+        &20,
+        &5,
+    );
+
+    let oracle = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &stake_token).mint(&oracle, &1000);
+    client.register_oracle(&oracle, &Symbol::new(&env, "Oracle1"));
+    let token_client = token::Client::new(&env, &stake_token);
+    assert_eq!(token_client.balance(&oracle), 0);
+
+    let market_id = BytesN::from_array(&env, &[9u8; 32]);
+    client.register_market(&market_id, &100);
+    env.ledger().with_mut(|li| li.timestamp = 101);
+    client.submit_attestation(
+        &oracle,
+        &market_id,
+        &1u32,
+        &BytesN::from_array(&env, &[0u8; 32]),
+    );
+    client.challenge_attestation(
+        &Address::generate(&env),
+        &oracle,
+        &market_id,
+        &Symbol::new(&env, "wrong"),
+    );
+
+    client.resolve_challenge(&oracle, &market_id, &true);
+    assert_eq!(client.get_oracle_stake(&oracle), 500);
+
+    client.deregister_oracle(&oracle);
+    assert_eq!(token_client.balance(&oracle), 500);
+    assert_eq!(client.get_oracle_stake(&oracle), 0);
 }
 
 /// Test that consensus threshold is recalculated after deregistration
@@ -375,7 +458,7 @@ fn test_deregister_oracle_recalculates_threshold() {
 
     let admin = Address::generate(&env);
     // Set threshold to 3
-    client.initialize(&admin, &3u32);
+    client.initialize(&admin, &3u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     // Register 3 oracles
     let oracle1 = Address::generate(&env);
@@ -414,7 +497,7 @@ fn test_deregister_multiple_oracles() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     let oracle1 = Address::generate(&env);
     let oracle2 = Address::generate(&env);
@@ -452,7 +535,7 @@ fn test_deregister_oracle_preserves_existing_attestations() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     let oracle1 = Address::generate(&env);
     let oracle2 = Address::generate(&env);
@@ -503,7 +586,7 @@ fn test_submit_attestation_stores_attestation() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     let oracle1 = Address::generate(&env);
     client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
@@ -511,6 +594,9 @@ fn test_submit_attestation_stores_attestation() {
     let market_id = BytesN::from_array(&env, &[2u8; 32]);
     let resolution_time = 1000u64;
 
+    // Markets can only be registered once required_consensus oracles exist
+    client.register_oracle(&Address::generate(&env), &Symbol::new(&env, "Backup1"));
+
     // Register market with resolution time
     client.register_market(&market_id, &resolution_time);
 
@@ -538,7 +624,6 @@ fn test_submit_attestation_stores_attestation() {
 
 /// Non-attestor (unregistered oracle) is rejected
 #[test]
-#[should_panic(expected = "Oracle not registered")]
 fn test_submit_attestation_non_attestor_rejected() {
     let env = create_test_env();
     env.mock_all_auths();
@@ -547,7 +632,7 @@ fn test_submit_attestation_non_attestor_rejected() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     // Note: we do NOT register unregistered_oracle as an oracle
     let unregistered_oracle = Address::generate(&env);
@@ -555,6 +640,10 @@ fn test_submit_attestation_non_attestor_rejected() {
     let market_id = BytesN::from_array(&env, &[3u8; 32]);
     let resolution_time = 1000u64;
 
+    // Markets can only be registered once required_consensus oracles exist
+    client.register_oracle(&Address::generate(&env), &Symbol::new(&env, "Backup1"));
+    client.register_oracle(&Address::generate(&env), &Symbol::new(&env, "Backup2"));
+
     // Register market
     client.register_market(&market_id, &resolution_time);
 
@@ -564,12 +653,14 @@ fn test_submit_attestation_non_attestor_rejected() {
     let data_hash = BytesN::from_array(&env, &[0u8; 32]);
 
     // This should panic because oracle is not registered
-    client.submit_attestation(&unregistered_oracle, &market_id, &1u32, &data_hash);
+    assert_eq!(
+        client.try_submit_attestation(&unregistered_oracle, &market_id, &1u32, &data_hash),
+        Err(Ok(OracleError::OracleNotRegistered))
+    );
 }
 
 /// Cannot attest before resolution_time
 #[test]
-#[should_panic(expected = "Cannot attest before resolution time")]
 fn test_submit_attestation_before_resolution_time() {
     let env = create_test_env();
     env.mock_all_auths();
@@ -578,7 +669,7 @@ fn test_submit_attestation_before_resolution_time() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     let oracle1 = Address::generate(&env);
     client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
@@ -586,6 +677,9 @@ fn test_submit_attestation_before_resolution_time() {
     let market_id = BytesN::from_array(&env, &[4u8; 32]);
     let resolution_time = 2000u64;
 
+    // Markets can only be registered once required_consensus oracles exist
+    client.register_oracle(&Address::generate(&env), &Symbol::new(&env, "Backup1"));
+
     // Register market with resolution time of 2000
     client.register_market(&market_id, &resolution_time);
 
@@ -595,12 +689,14 @@ fn test_submit_attestation_before_resolution_time() {
     let data_hash = BytesN::from_array(&env, &[0u8; 32]);
 
     // This should panic because we're before resolution time
-    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash);
+    assert_eq!(
+        client.try_submit_attestation(&oracle1, &market_id, &1u32, &data_hash),
+        Err(Ok(OracleError::TooEarlyToAttest))
+    );
 }
 
 /// Invalid outcome (not 0 or 1) is rejected
 #[test]
-#[should_panic(expected = "Invalid attestation result")]
 fn test_submit_attestation_invalid_outcome_rejected() {
     let env = create_test_env();
     env.mock_all_auths();
@@ -609,7 +705,7 @@ fn test_submit_attestation_invalid_outcome_rejected() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     let oracle1 = Address::generate(&env);
     client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
@@ -617,6 +713,9 @@ fn test_submit_attestation_invalid_outcome_rejected() {
     let market_id = BytesN::from_array(&env, &[5u8; 32]);
     let resolution_time = 1000u64;
 
+    // Markets can only be registered once required_consensus oracles exist
+    client.register_oracle(&Address::generate(&env), &Symbol::new(&env, "Backup1"));
+
     // Register market
     client.register_market(&market_id, &resolution_time);
 
@@ -626,7 +725,10 @@ fn test_submit_attestation_invalid_outcome_rejected() {
     let data_hash = BytesN::from_array(&env, &[0u8; 32]);
 
     // This should panic because outcome 2 is invalid (only 0 or 1 allowed)
-    client.submit_attestation(&oracle1, &market_id, &2u32, &data_hash);
+    assert_eq!(
+        client.try_submit_attestation(&oracle1, &market_id, &2u32, &data_hash),
+        Err(Ok(OracleError::InvalidOutcome))
+    );
 }
 
 /// Verify AttestationSubmitted event is emitted correctly
@@ -639,7 +741,7 @@ fn test_submit_attestation_event_emitted() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     let oracle1 = Address::generate(&env);
     client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
@@ -647,6 +749,9 @@ fn test_submit_attestation_event_emitted() {
     let market_id = BytesN::from_array(&env, &[6u8; 32]);
     let resolution_time = 1000u64;
 
+    // Markets can only be registered once required_consensus oracles exist
+    client.register_oracle(&Address::generate(&env), &Symbol::new(&env, "Backup1"));
+
     // Register market
     client.register_market(&market_id, &resolution_time);
 
@@ -680,11 +785,15 @@ fn test_register_market() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     let market_id = BytesN::from_array(&env, &[7u8; 32]);
     let resolution_time = 3000u64;
 
+    // Markets can only be registered once required_consensus oracles exist
+    client.register_oracle(&Address::generate(&env), &Symbol::new(&env, "Backup1"));
+    client.register_oracle(&Address::generate(&env), &Symbol::new(&env, "Backup2"));
+
     // Register market
     client.register_market(&market_id, &resolution_time);
 
@@ -709,7 +818,7 @@ fn test_attestation_count_tracking() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     let oracle1 = Address::generate(&env);
     let oracle2 = Address::generate(&env);
@@ -765,7 +874,7 @@ fn test_finalize_resolution_integration() {
 
     // Initialize oracle with 2 of 3 consensus
     let admin = Address::generate(&env);
-    oracle_client.initialize(&admin, &2u32);
+    oracle_client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     // Register 3 oracles
     let oracle1 = Address::generate(&env);
@@ -789,6 +898,9 @@ fn test_finalize_resolution_integration() {
         &oracle_id,
         &closing_time,
         &resolution_time,
+        &Vec::new(&env),
+        &0,
+        &Vec::new(&env),
     );
 
     // Register market in oracle
@@ -799,7 +911,7 @@ fn test_finalize_resolution_integration() {
 
     // Close market first
     env.ledger().set_timestamp(closing_time + 10);
-    market_client.close_market(&market_id_bytes);
+    market_client.close_market(&oracle_id, &market_id_bytes);
 
     // Advance to after resolution time
     env.ledger().set_timestamp(resolution_time + 10);
@@ -830,9 +942,117 @@ fn test_finalize_resolution_integration() {
     assert_eq!(stored_result, 1);
 }
 
+/// Integration test: agreeing oracles split the resolution reward budget after finalization
+#[test]
+fn test_resolution_reward_paid_to_agreeing_oracles() {
+    use boxmeout::market::PredictionMarketClient;
+
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let oracle_id = register_oracle(&env);
+    let oracle_client = OracleManagerClient::new(&env, &oracle_id);
+    let stake_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let admin = Address::generate(&env);
+    oracle_client.initialize(&admin, &2u32, &stake_token, &0, &5000, &20, &5);
+
+    let oracle1 = Address::generate(&env);
+    let oracle2 = Address::generate(&env);
+    let dissenter = Address::generate(&env);
+    let latecomer = Address::generate(&env);
+    oracle_client.register_oracle(&oracle1, &Symbol::new(&env, "O1"));
+    oracle_client.register_oracle(&oracle2, &Symbol::new(&env, "O2"));
+    oracle_client.register_oracle(&dissenter, &Symbol::new(&env, "O3"));
+    oracle_client.register_oracle(&latecomer, &Symbol::new(&env, "O4"));
+
+    let market_id_bytes = BytesN::from_array(&env, &[9u8; 32]);
+    let market_contract_id = env.register(PredictionMarket, ());
+    let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+    market_client.initialize(
+        &market_id_bytes,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &stake_token,
+        &oracle_id,
+        &500,
+        &1000,
+        &Vec::new(&env),
+        &0,
+        &Vec::new(&env),
+    );
+    oracle_client.register_market(&market_id_bytes, &1000);
+
+    // The creator funds a 1001-unit budget at registration
+    let funder = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &stake_token).mint(&funder, &1001);
+    oracle_client.fund_resolution_reward(&funder, &market_id_bytes, &1001);
+    assert_eq!(
+        oracle_client.get_resolution_reward_budget(&market_id_bytes),
+        1001
+    );
+
+    env.ledger().set_timestamp(510);
+    market_client.close_market(&oracle_id, &market_id_bytes);
+    env.ledger().set_timestamp(1010);
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+    oracle_client.submit_attestation(&oracle1, &market_id_bytes, &1u32, &data_hash);
+    oracle_client.submit_attestation(&oracle2, &market_id_bytes, &1u32, &data_hash);
+    oracle_client.submit_attestation(&dissenter, &market_id_bytes, &0u32, &data_hash);
+
+    // Nothing is claimable before finalization
+    assert_eq!(
+        oracle_client.try_claim_resolution_reward(&oracle1, &market_id_bytes),
+        Err(Ok(OracleError::ResolutionNotFinalized))
+    );
+
+    env.ledger().set_timestamp(1000 + 604800 + 10);
+    oracle_client.finalize_resolution(&market_id_bytes, &market_contract_id);
+
+    // Two oracles agreed with the YES outcome: 1001 / 2 each, dust stays behind
+    let token_client = token::Client::new(&env, &stake_token);
+    assert_eq!(
+        oracle_client.claim_resolution_reward(&oracle1, &market_id_bytes),
+        500
+    );
+
+    // The split is frozen: no late agreeing vote or top-up can change it
+    assert_eq!(
+        oracle_client.try_submit_attestation(&latecomer, &market_id_bytes, &1u32, &data_hash),
+        Err(Ok(OracleError::ResolutionAlreadyFinalized))
+    );
+    token::StellarAssetClient::new(&env, &stake_token).mint(&funder, &1000);
+    assert_eq!(
+        oracle_client.try_fund_resolution_reward(&funder, &market_id_bytes, &1000),
+        Err(Ok(OracleError::ResolutionAlreadyFinalized))
+    );
+
+    assert_eq!(
+        oracle_client.claim_resolution_reward(&oracle2, &market_id_bytes),
+        500
+    );
+    assert_eq!(token_client.balance(&oracle1), 500);
+    assert_eq!(token_client.balance(&oracle2), 500);
+
+    assert_eq!(
+        oracle_client.try_claim_resolution_reward(&dissenter, &market_id_bytes),
+        Err(Ok(OracleError::RewardNotEarned))
+    );
+    assert_eq!(
+        oracle_client.try_claim_resolution_reward(&oracle1, &market_id_bytes),
+        Err(Ok(OracleError::RewardAlreadyClaimed))
+    );
+    assert_eq!(token_client.balance(&dissenter), 0);
+    assert_eq!(token_client.balance(&oracle_id), 1);
+    assert_eq!(
+        oracle_client.get_resolution_reward_budget(&market_id_bytes),
+        1
+    );
+}
+
 /// Test finalize_resolution fails if consensus not reached
 #[test]
-#[should_panic(expected = "Consensus not reached")]
 fn test_finalize_resolution_no_consensus() {
     use boxmeout::market::PredictionMarket;
 
@@ -846,12 +1066,16 @@ fn test_finalize_resolution_no_consensus() {
     let market_id_bytes = BytesN::from_array(&env, &[10u8; 32]);
 
     let admin = Address::generate(&env);
-    oracle_client.initialize(&admin, &3u32); // Need 3 votes
+    oracle_client.initialize(&admin, &3u32, &Address::generate(&env), &0, &5000, &20, &5); // Need 3 votes
 
     let oracle1 = Address::generate(&env);
     oracle_client.register_oracle(&oracle1, &Symbol::new(&env, "O1"));
 
     let resolution_time = 1000u64;
+    // Markets can only be registered once required_consensus oracles exist
+    oracle_client.register_oracle(&Address::generate(&env), &Symbol::new(&env, "Backup1"));
+    oracle_client.register_oracle(&Address::generate(&env), &Symbol::new(&env, "Backup2"));
+
     oracle_client.register_market(&market_id_bytes, &resolution_time);
 
     // Only 1 attestation (not enough for consensus)
@@ -863,12 +1087,14 @@ fn test_finalize_resolution_no_consensus() {
     env.ledger().set_timestamp(resolution_time + 604800 + 10);
 
     // Should panic: consensus not reached
-    oracle_client.finalize_resolution(&market_id_bytes, &market_contract_id);
+    assert_eq!(
+        oracle_client.try_finalize_resolution(&market_id_bytes, &market_contract_id),
+        Err(Ok(OracleError::ConsensusNotReached))
+    );
 }
 
 /// Test finalize_resolution fails if dispute period not elapsed
 #[test]
-#[should_panic(expected = "Dispute period not elapsed")]
 fn test_finalize_resolution_dispute_period_not_elapsed() {
     use boxmeout::market::PredictionMarket;
 
@@ -882,7 +1108,7 @@ fn test_finalize_resolution_dispute_period_not_elapsed() {
     let market_id_bytes = BytesN::from_array(&env, &[11u8; 32]);
 
     let admin = Address::generate(&env);
-    oracle_client.initialize(&admin, &2u32);
+    oracle_client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     let oracle1 = Address::generate(&env);
     let oracle2 = Address::generate(&env);
@@ -902,12 +1128,14 @@ fn test_finalize_resolution_dispute_period_not_elapsed() {
     env.ledger().set_timestamp(resolution_time + 100);
 
     // Should panic: dispute period not elapsed
-    oracle_client.finalize_resolution(&market_id_bytes, &market_contract_id);
+    assert_eq!(
+        oracle_client.try_finalize_resolution(&market_id_bytes, &market_contract_id),
+        Err(Ok(OracleError::DisputePeriodNotElapsed))
+    );
 }
 
 /// Test finalize_resolution fails if market not registered
 #[test]
-#[should_panic(expected = "Market not registered")]
 fn test_finalize_resolution_market_not_registered() {
     let env = create_test_env();
     env.mock_all_auths();
@@ -919,8 +1147,177 @@ fn test_finalize_resolution_market_not_registered() {
     let market_id_bytes = BytesN::from_array(&env, &[12u8; 32]);
 
     let admin = Address::generate(&env);
-    oracle_client.initialize(&admin, &2u32);
+    oracle_client.initialize(&admin, &2u32, &Address::generate(&env), &0, &5000, &20, &5);
 
     // Market not registered - should panic
-    oracle_client.finalize_resolution(&market_id_bytes, &market_contract_id);
+    assert_eq!(
+        oracle_client.try_finalize_resolution(&market_id_bytes, &market_contract_id),
+        Err(Ok(OracleError::MarketNotRegistered))
+    );
+}
+
+/// Registrar and resolver delegated to distinct addresses
+fn setup_split_roles(env: &Env) -> (OracleManagerClient<'_>, Address, Address) {
+    env.mock_all_auths();
+
+    let client = OracleManagerClient::new(env, &register_oracle(env));
+    client.initialize(
+        &Address::generate(env),
+        &1u32,
+        &Address::generate(env),
+        &0,
+        &5000,
+        &20,
+        &5,
+    );
+
+    let registrar = Address::generate(env);
+    let resolver = Address::generate(env);
+    client.set_registrar(&registrar);
+    client.set_resolver(&resolver);
+    assert_eq!(client.get_registrar(), registrar);
+    assert_eq!(client.get_resolver(), resolver);
+
+    (client, registrar, resolver)
+}
+
+/// An attested-and-challenged oracle, ready for arbitration
+fn setup_challenge(env: &Env, client: &OracleManagerClient) -> (Address, BytesN<32>) {
+    env.mock_all_auths();
+
+    let oracle = Address::generate(env);
+    client.register_oracle(&oracle, &Symbol::new(env, "Oracle1"));
+
+    let market_id = BytesN::from_array(env, &[9u8; 32]);
+    client.register_market(&market_id, &100);
+    env.ledger().with_mut(|li| li.timestamp = 101);
+    client.submit_attestation(
+        &oracle,
+        &market_id,
+        &1u32,
+        &BytesN::from_array(env, &[0u8; 32]),
+    );
+    client.challenge_attestation(
+        &Address::generate(env),
+        &oracle,
+        &market_id,
+        &Symbol::new(env, "wrong"),
+    );
+
+    (oracle, market_id)
+}
+
+fn mock_role_auth(
+    env: &Env,
+    client: &OracleManagerClient,
+    role: &Address,
+    fn_name: &str,
+    args: Vec<Val>,
+) {
+    env.mock_auths(&[MockAuth {
+        address: role,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name,
+            args,
+            sub_invokes: &[],
+        },
+    }]);
+}
+
+#[test]
+fn test_registrar_can_register_but_not_resolve_challenges() {
+    let env = create_test_env();
+    let (client, registrar, _resolver) = setup_split_roles(&env);
+    let (oracle, market_id) = setup_challenge(&env, &client);
+
+    let new_oracle = Address::generate(&env);
+    let name = Symbol::new(&env, "Oracle2");
+    mock_role_auth(
+        &env,
+        &client,
+        &registrar,
+        "register_oracle",
+        (new_oracle.clone(), name.clone()).into_val(&env),
+    );
+    client.register_oracle(&new_oracle, &name);
+
+    mock_role_auth(
+        &env,
+        &client,
+        &registrar,
+        "resolve_challenge",
+        (oracle.clone(), market_id.clone(), true).into_val(&env),
+    );
+    assert!(client
+        .try_resolve_challenge(&oracle, &market_id, &true)
+        .is_err());
+    assert!(!client.get_challenge(&oracle, &market_id).unwrap().resolved);
+}
+
+#[test]
+fn test_resolver_can_resolve_challenges_but_not_register() {
+    let env = create_test_env();
+    let (client, _registrar, resolver) = setup_split_roles(&env);
+    let (oracle, market_id) = setup_challenge(&env, &client);
+
+    let new_oracle = Address::generate(&env);
+    let name = Symbol::new(&env, "Oracle2");
+    mock_role_auth(
+        &env,
+        &client,
+        &resolver,
+        "register_oracle",
+        (new_oracle.clone(), name.clone()).into_val(&env),
+    );
+    assert!(client.try_register_oracle(&new_oracle, &name).is_err());
+
+    mock_role_auth(
+        &env,
+        &client,
+        &resolver,
+        "resolve_challenge",
+        (oracle.clone(), market_id.clone(), false).into_val(&env),
+    );
+    client.resolve_challenge(&oracle, &market_id, &false);
+    assert!(client.get_challenge(&oracle, &market_id).unwrap().resolved);
+}
+
+#[test]
+fn test_only_resolver_can_recompute_accuracy() {
+    let env = create_test_env();
+    let (client, registrar, resolver) = setup_split_roles(&env);
+    let (oracle, market_id) = setup_challenge(&env, &client);
+
+    mock_role_auth(
+        &env,
+        &client,
+        &resolver,
+        "resolve_challenge",
+        (oracle.clone(), market_id.clone(), true).into_val(&env),
+    );
+    client.resolve_challenge(&oracle, &market_id, &true);
+    let penalized = client.get_oracle_accuracy(&oracle);
+
+    // Neither the registrar nor the oracle itself can recompute away the penalty
+    for caller in [&registrar, &oracle] {
+        mock_role_auth(
+            &env,
+            &client,
+            caller,
+            "recompute_accuracy",
+            (oracle.clone(),).into_val(&env),
+        );
+        assert!(client.try_recompute_accuracy(&oracle).is_err());
+    }
+    assert_eq!(client.get_oracle_accuracy(&oracle), penalized);
+
+    mock_role_auth(
+        &env,
+        &client,
+        &resolver,
+        "recompute_accuracy",
+        (oracle.clone(),).into_val(&env),
+    );
+    assert_eq!(client.recompute_accuracy(&oracle), penalized);
 }